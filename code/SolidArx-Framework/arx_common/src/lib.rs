@@ -0,0 +1,23 @@
+//! Funzioni condivise tra il framework Arx e le macro procedurali di `arx_derive`.
+//!
+//! `arx_derive` non può dipendere dal framework, quindi le convenzioni che devono coincidere
+//! nel codice generato e nel CLI (es. i nomi delle tabelle) sono definite in questo crate.
+
+/// Converte un nome PascalCase in snake_case.
+///
+/// Utilizzata da `#[derive(ArxModel)]` per il nome di default della tabella e da
+/// `model_generator.rs` e `table_scraper.rs` per derivare il nome delle tabelle dalle struct.
+pub fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
@@ -0,0 +1,734 @@
+//! Macro procedurali del framework Arx.
+//!
+//! ### `#[derive(ArxModel)]`
+//! Implementa per un modello il trait `ArxModel` e i trait CRUD di `crud_ops.rs`
+//! (`Create`, `Read`, `Update`, `Delete`, `List`, `Search`, `Revoke`, `SoftDelete`), delegando alle funzioni
+//! generiche di `crud_ops.rs` che scelgono lo storage in base all'`AllocType` del modello.
+//! Non è più necessario riconoscere il modello tramite `std::any::type_name`.
+//! Le operazioni non dichiarate in `ops` restituiscono `CrudError::OperationNotPermitted`.
+//!
+//! Il codice generato utilizza percorsi `crate::crud::...`, quindi la derive è utilizzabile
+//! solo dai modelli definiti all'interno del framework (`crud/models/default` e `crud/models/dev`).
+//!
+//! ### Attributi supportati:
+//! - `#[arx(table = "nome")]`: Nome della tabella, di default il nome della struct in snake_case.
+//! - `#[arx(store = "memory" | "database" | "cached")]`: Storage di default del modello, di default `database`.
+//! - `#[arx(cache = "write_through" | "write_behind")]`: Politica di scrittura della cache con `store = "cached"`.
+//! - `#[arx(capacity = 256, ttl = 300)]`: Numero massimo di record in cache e TTL in secondi (0 per nessuna scadenza).
+//! - `#[arx(id = "auto" | "uuid_v7" | "snowflake" | "manual")]`: Strategia di generazione dell'ID
+//!   (vedi `id_generator.rs`), di default `uuid_v7` per `id: Uuid` e `auto` per gli ID interi.
+//!
+//! Il tipo del campo `id` (`u32`, `u64` o `Uuid`) diventa il tipo della chiave `ArxModel::Id`.
+//! - `#[arx(ops = "create,read,...")]`: Operazioni CRUD dichiarate, di default `create,read,update,delete,list`.
+//! - `#[arx(audit)]`: Registra ogni modifica del modello nel log di audit (vedi `crud/audit.rs`).
+//! - `#[arx(has_many = "Comment")]`: Modello dipendente a cui applicare la politica di eliminazione (ripetibile).
+//! - `#[arx(skip)]` sul campo: Il campo non viene persistito e viene ricostruito con `Default::default()`.
+//! - `#[arx(secret)]` sul campo: Il campo viene persistito ma escluso da esportazioni, log di audit ed eventi
+//!   di watch (es. password e chiavi API); se assente dai valori viene ricostruito con `Default::default()`.
+//! - `#[arx(belongs_to = "Article", on_delete = "cascade" | "restrict")]` sul campo: Chiave esterna verso
+//!   il modello indicato, con politica di eliminazione di default `restrict` (vedi `crud/relations.rs`).
+//! - `#[arx(validate(email, length(min = 3, max = 32), ...))]` sul campo: Regole verificate da `Create` e `Update`
+//!   (`email`, `regex = "..."`, `length`, `range`, `cron`, `url`, `path`, `custom = "funzione"`, vedi `crud/validation.rs`).
+//!
+//! I campi `store`, `ops` e `memory` non vengono mai persistiti.
+//! I modelli derivano anche `Serialize` e `Deserialize` con `#[serde(skip)]` su questi campi: `store` e `ops`
+//! vengono ricostruiti con `default = "Modello::default_store"` e `default = "Modello::declared_operations"`.
+//! I modelli che dichiarano l'operazione `revoke` richiedono un campo `revocation: Option<Revocation>`.
+//! I modelli con un campo `version: u64` utilizzano il controllo ottimistico della concorrenza in `Update`.
+//! I modelli con un campo `deleted_at: Option<NaiveDateTime>` spostano i record eliminati nel cestino.
+//!
+//! ### Esempio:
+//! ```rust,ignore
+//! #[derive(Debug, Clone, ArxModel)]
+//! #[arx(store = "cached", cache = "write_through", capacity = 512, ops = "create,read,update,delete,list,search,revoke")]
+//! pub struct Device {
+//!     pub id: u32,
+//!     pub name: String,
+//!     pub revocation: Option<Revocation>,
+//!     pub store: AllocType,
+//!     pub memory: Box<[u8]>,
+//!     pub ops: CrudOperations,
+//! }
+//! ```
+
+use arx_common::to_snake_case;
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, ToTokens};
+use syn::meta::ParseNestedMeta;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Expr, Fields, Ident, LitInt, LitStr, Path, Type};
+
+/// Campi gestiti dal framework e mai persistiti.
+const FRAMEWORK_FIELDS: [&str; 3] = ["store", "ops", "memory"];
+/// Operazioni CRUD di default, come in `arx model new`.
+const DEFAULT_OPS: &str = "create,read,update,delete,list";
+/// Nomi delle operazioni CRUD nell'ordine dei campi di `CrudOperations`.
+const CRUD_OPS: [&str; 7] = ["create", "read", "update", "delete", "list", "search", "revoke"];
+/// Tipi di `deleted_at` che abilitano il cestino, senza spazi come i tipi dei campi persistiti.
+const SOFT_DELETE_TYPES: [&str; 3] = ["Option<chrono::NaiveDateTime>", "Option<::chrono::NaiveDateTime>", "Option<NaiveDateTime>"];
+
+/// Opzioni lette dall'attributo `#[arx(...)]` della struct.
+struct ModelOptions {
+    table: String,
+    /// Variante di `AllocType`.
+    store: &'static str,
+    ops: Vec<String>,
+    /// Variante di `CachePolicy`.
+    cache_policy: &'static str,
+    cache_capacity: Option<usize>,
+    cache_ttl: Option<u64>,
+    /// Variante di `IdStrategy`, `None` per la strategia di default del tipo dell'ID.
+    id_strategy: Option<&'static str>,
+    /// Registrazione delle modifiche nel log di audit.
+    audit: bool,
+    /// Modelli dipendenti dichiarati con `#[arx(has_many = "...")]`.
+    has_many: Vec<Path>,
+}
+
+/// Campo persistito del modello.
+struct PersistedField {
+    ident: Ident,
+    name: String,
+    /// Tipo del campo senza spazi, es. `Option<chrono::NaiveDateTime>`.
+    ty: String,
+    /// Modello collegato e variante di `OnDelete`, dichiarati con `#[arx(belongs_to = "...")]`.
+    belongs_to: Option<(Path, &'static str)>,
+    /// Regole di validazione (`crate::crud::validation::Rule`) dichiarate con `#[arx(validate(...))]`.
+    rules: Vec<TokenStream2>,
+    /// Funzioni di validazione dichiarate con `validate(custom = "...")`.
+    custom: Vec<Path>,
+    /// Campo dichiarato con `#[arx(secret)]`.
+    secret: bool,
+    cfgs: Vec<Attribute>,
+}
+
+fn parse_model_options(input: &DeriveInput) -> syn::Result<ModelOptions> {
+    let mut options = ModelOptions {
+        table: to_snake_case(&input.ident.to_string()),
+        store: "Database",
+        ops: DEFAULT_OPS.split(',').map(String::from).collect(),
+        cache_policy: "WriteThrough",
+        cache_capacity: None,
+        cache_ttl: None,
+        id_strategy: None,
+        audit: false,
+        has_many: Vec::new(),
+    };
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("arx")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("audit") {
+                options.audit = true;
+                return Ok(());
+            } else if meta.path.is_ident("capacity") {
+                options.cache_capacity = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                return Ok(());
+            } else if meta.path.is_ident("ttl") {
+                options.cache_ttl = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                return Ok(());
+            }
+
+            let value: LitStr = meta.value()?.parse()?;
+            if meta.path.is_ident("table") {
+                options.table = value.value();
+            } else if meta.path.is_ident("store") {
+                options.store = match value.value().as_str() {
+                    "memory" | "inmemory" => "InMemory",
+                    "database" | "db" => "Database",
+                    "cached" | "cache" => "Cached",
+                    other => return Err(meta.error(format!("Tipo di allocazione non riconosciuto: {}", other))),
+                };
+            } else if meta.path.is_ident("id") {
+                options.id_strategy = Some(match value.value().as_str() {
+                    "auto" | "auto_increment" => "AutoIncrement",
+                    "uuid_v7" | "uuid" => "UuidV7",
+                    "snowflake" => "Snowflake",
+                    "manual" => "Manual",
+                    other => return Err(meta.error(format!("Strategia di ID non riconosciuta: {}", other))),
+                });
+            } else if meta.path.is_ident("cache") {
+                options.cache_policy = match value.value().as_str() {
+                    "write_through" | "write-through" => "WriteThrough",
+                    "write_behind" | "write-behind" => "WriteBehind",
+                    other => return Err(meta.error(format!("Politica di cache non riconosciuta: {}", other))),
+                };
+            } else if meta.path.is_ident("has_many") {
+                options.has_many.push(value.parse::<Path>()?);
+            } else if meta.path.is_ident("ops") {
+                let ops: Vec<String> = value.value()
+                    .split(',')
+                    .map(|op| op.trim().to_lowercase())
+                    .filter(|op| !op.is_empty())
+                    .collect();
+                if let Some(op) = ops.iter().find(|op| !CRUD_OPS.contains(&op.as_str())) {
+                    return Err(meta.error(format!("Operazione CRUD non riconosciuta: {}", op)));
+                }
+                options.ops = ops;
+            } else {
+                return Err(meta.error("Attributo arx non supportato, usare table, store, ops, id, cache, capacity, ttl, audit oppure has_many"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+/// Opzioni lette dall'attributo `#[arx(...)]` di un campo.
+struct FieldOptions {
+    skip: bool,
+    secret: bool,
+    /// Modello collegato e variante di `OnDelete`, per i campi chiave esterna.
+    belongs_to: Option<(Path, &'static str)>,
+    rules: Vec<TokenStream2>,
+    custom: Vec<Path>,
+}
+
+/// Estremi `min` e `max` di una regola `length(...)` o `range(...)`.
+fn parse_bounds<T: ToTokens>(
+    meta: &ParseNestedMeta,
+    parse: impl Fn(&ParseNestedMeta) -> syn::Result<T>,
+) -> syn::Result<(TokenStream2, TokenStream2)> {
+    let (mut min, mut max) = (quote! { None }, quote! { None });
+    meta.parse_nested_meta(|bound| {
+        let value = parse(&bound)?;
+        if bound.path.is_ident("min") {
+            min = quote! { Some(#value) };
+        } else if bound.path.is_ident("max") {
+            max = quote! { Some(#value) };
+        } else {
+            return Err(bound.error("Estremo non supportato, usare min oppure max"));
+        }
+        Ok(())
+    })?;
+    Ok((min, max))
+}
+
+/// Regola di `validate(...)`, aggiunta alle regole o alle funzioni `custom` del campo.
+fn parse_rule(meta: &ParseNestedMeta, rules: &mut Vec<TokenStream2>, custom: &mut Vec<Path>) -> syn::Result<()> {
+    let rule = quote! { crate::crud::validation::Rule };
+    let name = meta.path.get_ident().map(Ident::to_string).unwrap_or_default();
+    match name.as_str() {
+        "email" => rules.push(quote! { #rule::Email }),
+        "cron" => rules.push(quote! { #rule::Cron }),
+        "url" => rules.push(quote! { #rule::Url }),
+        "path" => rules.push(quote! { #rule::Path }),
+        "regex" => {
+            let pattern: LitStr = meta.value()?.parse()?;
+            rules.push(quote! { #rule::Regex(#pattern) });
+        }
+        "length" => {
+            let (min, max) = parse_bounds(meta, |bound| bound.value()?.parse::<LitInt>()?.base10_parse::<usize>())?;
+            rules.push(quote! { #rule::Length { min: #min, max: #max } });
+        }
+        "range" => {
+            let (min, max) = parse_bounds(meta, |bound| {
+                let expr: Expr = bound.value()?.parse()?;
+                expr.to_token_stream()
+                    .to_string()
+                    .replace(' ', "")
+                    .parse::<f64>()
+                    .map_err(|_| bound.error("Gli estremi di range devono essere numerici"))
+            })?;
+            rules.push(quote! { #rule::Range { min: #min, max: #max } });
+        }
+        "custom" => custom.push(meta.value()?.parse::<LitStr>()?.parse::<Path>()?),
+        _ => {
+            return Err(meta.error("Regola di validazione non supportata, usare email, regex, length, range, cron, url, path oppure custom"))
+        }
+    }
+    Ok(())
+}
+
+fn parse_field_options(attrs: &[Attribute]) -> syn::Result<FieldOptions> {
+    let mut skip = false;
+    let mut secret = false;
+    let mut belongs_to = None;
+    let mut on_delete = None;
+    let (mut rules, mut custom) = (Vec::new(), Vec::new());
+    for attr in attrs.iter().filter(|a| a.path().is_ident("arx")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+            } else if meta.path.is_ident("secret") {
+                secret = true;
+            } else if meta.path.is_ident("belongs_to") {
+                belongs_to = Some(meta.value()?.parse::<LitStr>()?.parse::<Path>()?);
+            } else if meta.path.is_ident("on_delete") {
+                on_delete = Some(match meta.value()?.parse::<LitStr>()?.value().as_str() {
+                    "cascade" => "Cascade",
+                    "restrict" => "Restrict",
+                    other => return Err(meta.error(format!("Politica di eliminazione non riconosciuta: {}", other))),
+                });
+            } else if meta.path.is_ident("validate") {
+                meta.parse_nested_meta(|rule| parse_rule(&rule, &mut rules, &mut custom))?;
+            } else {
+                return Err(meta.error("Attributo arx del campo non supportato, usare skip, secret, belongs_to, on_delete oppure validate"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(FieldOptions {
+        skip,
+        secret,
+        belongs_to: belongs_to.map(|target| (target, on_delete.unwrap_or("Restrict"))),
+        rules,
+        custom,
+    })
+}
+
+/// Variante di `IdStrategy` per il tipo del campo `id`, verificando che il tipo supporti la strategia dichiarata.
+fn id_strategy(id_type: &Type, declared: Option<&'static str>) -> syn::Result<&'static str> {
+    let type_name = match id_type {
+        Type::Path(path) => path.path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default(),
+        _ => String::new(),
+    };
+    let strategy = declared.unwrap_or(if type_name == "Uuid" { "UuidV7" } else { "AutoIncrement" });
+    let supported = match strategy {
+        "AutoIncrement" => type_name == "u32" || type_name == "u64",
+        "UuidV7" => type_name == "Uuid",
+        "Snowflake" => type_name == "u64",
+        _ => ["u32", "u64", "Uuid"].contains(&type_name.as_str()),
+    };
+    if supported {
+        Ok(strategy)
+    } else {
+        Err(syn::Error::new_spanned(
+            id_type,
+            format!("Il tipo dell'ID non supporta la strategia {}: usare u32/u64 per auto, u64 per snowflake e Uuid per uuid_v7", strategy),
+        ))
+    }
+}
+
+#[proc_macro_derive(ArxModel, attributes(arx))]
+pub fn derive_arx_model(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let options = parse_model_options(input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(name, "ArxModel richiede una struct con campi nominati")),
+        },
+        _ => return Err(syn::Error::new_spanned(name, "ArxModel è applicabile solo alle struct")),
+    };
+
+    let mut id_type = None;
+    let mut has_store = false;
+    let mut persisted = Vec::new();
+    let mut defaulted = Vec::new();
+    for field in fields {
+        let ident = field.ident.clone().expect("campi nominati");
+        let field_name = ident.to_string();
+        let cfgs: Vec<Attribute> = field.attrs.iter().filter(|a| a.path().is_ident("cfg")).cloned().collect();
+
+        match field_name.as_str() {
+            "id" => id_type = Some(field.ty.clone()),
+            "store" => has_store = true,
+            "ops" => {}
+            _ if FRAMEWORK_FIELDS.contains(&field_name.as_str()) => defaulted.push((ident, cfgs)),
+            _ => {
+                let field_options = parse_field_options(&field.attrs)?;
+                if field_options.skip {
+                    defaulted.push((ident, cfgs));
+                    continue;
+                }
+                let ty = field.ty.to_token_stream().to_string().replace(' ', "");
+                persisted.push(PersistedField {
+                    ident,
+                    name: field_name,
+                    ty,
+                    belongs_to: field_options.belongs_to,
+                    rules: field_options.rules,
+                    custom: field_options.custom,
+                    secret: field_options.secret,
+                    cfgs,
+                });
+            }
+        }
+    }
+    let id_type = match id_type {
+        Some(id_type) => id_type,
+        None => return Err(syn::Error::new_spanned(name, "ArxModel richiede un campo `id` (u32, u64 o Uuid)")),
+    };
+    let id_strategy = Ident::new(id_strategy(&id_type, options.id_strategy)?, Span::call_site());
+    let has_ops = fields.iter().any(|f| f.ident.as_ref().is_some_and(|i| i == "ops"));
+    let has_revocation = persisted.iter().any(|f| f.name == "revocation");
+    if options.ops.iter().any(|op| op == "revoke") && !has_revocation {
+        return Err(syn::Error::new_spanned(
+            name,
+            "I modelli con l'operazione revoke richiedono un campo `revocation: Option<Revocation>`",
+        ));
+    }
+
+    let table = &options.table;
+    let store_variant = Ident::new(options.store, Span::call_site());
+    let cache_policy = Ident::new(options.cache_policy, Span::call_site());
+    let cache_capacity = options.cache_capacity.map(|capacity| quote! { config.capacity = #capacity; });
+    let cache_ttl = options.cache_ttl.map(|ttl| match ttl {
+        0 => quote! { config.ttl = None; },
+        ttl => quote! { config.ttl = Some(::std::time::Duration::from_secs(#ttl)); },
+    });
+    let op_flags = CRUD_OPS.iter().map(|op| {
+        let flag = Ident::new(op, Span::call_site());
+        let enabled = options.ops.iter().any(|o| o == op);
+        quote! { #flag: #enabled }
+    });
+
+    let store_fn = if has_store {
+        quote! { fn store(&self) -> crate::crud::crud_ops::AllocType { self.store.clone() } }
+    } else {
+        quote! { fn store(&self) -> crate::crud::crud_ops::AllocType { Self::default_store() } }
+    };
+
+    let field_names = persisted.iter().map(|f| {
+        let (cfgs, field_name) = (&f.cfgs, &f.name);
+        quote! { #(#cfgs)* names.push(#field_name); }
+    });
+    let text_fields = persisted
+        .iter()
+        .filter(|f| !f.secret && (f.ty == "String" || f.ty == "Option<String>"))
+        .map(|f| {
+            let (cfgs, field_name) = (&f.cfgs, &f.name);
+            quote! { #(#cfgs)* names.push(#field_name); }
+        });
+    let to_values = persisted.iter().map(|f| {
+        let (cfgs, ident, field_name) = (&f.cfgs, &f.ident, &f.name);
+        quote! {
+            #(#cfgs)*
+            values.push((#field_name, crate::crud::model_value::ToModelValue::to_model_value(&self.#ident)));
+        }
+    });
+    let from_values = persisted.iter().map(|f| {
+        let (cfgs, ident, field_name) = (&f.cfgs, &f.ident, &f.name);
+        if f.secret {
+            // I campi segreti mancano dai valori registrati nel log di audit e inviati con gli eventi
+            quote! {
+                #(#cfgs)*
+                #ident: if values.contains_key(#field_name) {
+                    crate::crud::model_value::field_value(values, #field_name)?
+                } else {
+                    ::std::default::Default::default()
+                },
+            }
+        } else {
+            quote! { #(#cfgs)* #ident: crate::crud::model_value::field_value(values, #field_name)?, }
+        }
+    });
+    let defaulted_fields = defaulted.iter().map(|(ident, cfgs)| {
+        quote! { #(#cfgs)* #ident: ::std::default::Default::default(), }
+    });
+    let revocation_fns = has_revocation.then(|| {
+        quote! {
+            const REVOCABLE: bool = true;
+
+            fn revocation(&self) -> Option<crate::crud::revocation::Revocation> {
+                self.revocation.clone()
+            }
+
+            fn set_revocation(&mut self, revocation: Option<crate::crud::revocation::Revocation>) {
+                self.revocation = revocation;
+            }
+        }
+    });
+    // Solo `version: u64`, altri campi `version` (es. `FirmwareVersion`) restano dati del modello
+    let version_fns = persisted.iter().any(|f| f.name == "version" && f.ty == "u64").then(|| {
+        quote! {
+            const VERSIONED: bool = true;
+
+            fn version(&self) -> u64 {
+                self.version
+            }
+
+            fn set_version(&mut self, version: u64) {
+                self.version = version;
+            }
+        }
+    });
+    // Solo `deleted_at: Option<NaiveDateTime>`, con altri tipi il campo resta un dato del modello
+    let soft_delete_fns = persisted.iter().any(|f| f.name == "deleted_at" && SOFT_DELETE_TYPES.contains(&f.ty.as_str())).then(|| {
+        quote! {
+            const SOFT_DELETE: bool = true;
+
+            fn deleted_at(&self) -> Option<::chrono::NaiveDateTime> {
+                self.deleted_at
+            }
+
+            fn set_deleted_at(&mut self, deleted_at: Option<::chrono::NaiveDateTime>) {
+                self.deleted_at = deleted_at;
+            }
+        }
+    });
+    let audited = options.audit.then(|| quote! { const AUDITED: bool = true; });
+    let secrets: Vec<&PersistedField> = persisted.iter().filter(|f| f.secret).collect();
+    let secret_fns = (!secrets.is_empty()).then(|| {
+        let names = secrets.iter().map(|f| {
+            let (cfgs, field_name) = (&f.cfgs, &f.name);
+            quote! { #(#cfgs)* names.push(#field_name); }
+        });
+        let redactions = secrets.iter().map(|f| {
+            let (cfgs, ident) = (&f.cfgs, &f.ident);
+            quote! { #(#cfgs)* { self.#ident = ::std::default::Default::default(); } }
+        });
+        quote! {
+            fn secret_fields() -> Vec<&'static str> {
+                #[allow(unused_mut)]
+                let mut names = Vec::new();
+                #(#names)*
+                names
+            }
+
+            fn redact(&mut self) {
+                #(#redactions)*
+            }
+        }
+    });
+    let foreign_keys: Vec<&PersistedField> = persisted.iter().filter(|f| f.belongs_to.is_some()).collect();
+    let relation_fns = (!foreign_keys.is_empty()).then(|| {
+        let relations = foreign_keys.iter().map(|f| {
+            let (cfgs, field_name) = (&f.cfgs, &f.name);
+            let (target, on_delete) = f.belongs_to.as_ref().expect("chiave esterna");
+            let on_delete = Ident::new(on_delete, Span::call_site());
+            quote! {
+                #(#cfgs)*
+                relations.push(crate::crud::relations::Relation {
+                    field: #field_name,
+                    target: <#target as crate::crud::crud_ops::ArxModel>::TABLE_NAME,
+                    on_delete: crate::crud::relations::OnDelete::#on_delete,
+                });
+            }
+        });
+        let checks = foreign_keys.iter().map(|f| {
+            let (cfgs, ident) = (&f.cfgs, &f.ident);
+            let (target, _) = f.belongs_to.as_ref().expect("chiave esterna");
+            quote! { #(#cfgs)* crate::crud::relations::ensure_exists::<Self, #target>(self, &self.#ident)?; }
+        });
+        quote! {
+            fn relations() -> Vec<crate::crud::relations::Relation> {
+                #[allow(unused_mut)]
+                let mut relations = Vec::new();
+                #(#relations)*
+                relations
+            }
+
+            fn check_references(&self) -> Result<(), crate::crud::crud_ops::CrudError> {
+                #(#checks)*
+                Ok(())
+            }
+        }
+    });
+    let dependents_fn = (!options.has_many.is_empty()).then(|| {
+        let dependents = options.has_many.iter().map(|child| {
+            quote! {
+                crate::crud::relations::on_parent_delete::<Self, #child>
+                    as fn(&#id_type) -> Result<(), crate::crud::crud_ops::CrudError>
+            }
+        });
+        quote! {
+            fn dependents() -> Vec<crate::crud::crud_ops::DependentPolicy<#id_type>> {
+                vec![#(#dependents),*]
+            }
+        }
+    });
+    let validated: Vec<&PersistedField> =
+        persisted.iter().filter(|f| !f.rules.is_empty() || !f.custom.is_empty()).collect();
+    let validate_fn = (!validated.is_empty()).then(|| {
+        let checks = validated.iter().map(|f| {
+            let (cfgs, ident, field_name, rules) = (&f.cfgs, &f.ident, &f.name, &f.rules);
+            let rules = (!rules.is_empty()).then(|| {
+                quote! {
+                    #(#cfgs)*
+                    errors.extend(crate::crud::validation::check(
+                        #field_name,
+                        &crate::crud::model_value::ToModelValue::to_model_value(&self.#ident),
+                        &[#(#rules),*],
+                    ));
+                }
+            });
+            let custom = f.custom.iter().map(|custom| {
+                quote! {
+                    #(#cfgs)*
+                    errors.extend(#custom(&self.#ident).err().map(|message| {
+                        crate::crud::validation::FieldError::new(#field_name, "custom", message)
+                    }));
+                }
+            });
+            quote! { #rules #(#custom)* }
+        });
+        quote! {
+            fn validate(&self) -> Result<(), Vec<crate::crud::validation::FieldError>> {
+                #[allow(unused_mut)]
+                let mut errors = Vec::new();
+                #(#checks)*
+                if errors.is_empty() { Ok(()) } else { Err(errors) }
+            }
+        }
+    });
+    let store_field = has_store.then(|| quote! { store: Self::default_store(), });
+    let ops_field = has_ops.then(|| quote! { ops: Self::declared_operations(), });
+
+    Ok(quote! {
+        impl crate::crud::crud_ops::ArxModel for #name {
+            const TABLE_NAME: &'static str = #table;
+            const ID_STRATEGY: crate::crud::id_generator::IdStrategy = crate::crud::id_generator::IdStrategy::#id_strategy;
+            type Id = #id_type;
+            #audited
+
+            fn id(&self) -> #id_type {
+                self.id.clone()
+            }
+
+            fn set_id(&mut self, id: #id_type) {
+                self.id = id;
+            }
+
+            #store_fn
+
+            fn default_store() -> crate::crud::crud_ops::AllocType {
+                crate::crud::crud_ops::AllocType::#store_variant
+            }
+
+            fn declared_operations() -> crate::crud::crud_ops::CrudOperations {
+                crate::crud::crud_ops::CrudOperations { #(#op_flags),* }
+            }
+
+            fn memory_store() -> &'static ::std::sync::Mutex<::std::collections::HashMap<#id_type, Self>> {
+                static STORE: ::once_cell::sync::Lazy<::std::sync::Mutex<::std::collections::HashMap<#id_type, #name>>> =
+                    ::once_cell::sync::Lazy::new(|| ::std::sync::Mutex::new(::std::collections::HashMap::new()));
+                &STORE
+            }
+
+            fn cache_config() -> crate::crud::model_cache::CacheConfig {
+                #[allow(unused_mut)]
+                let mut config = crate::crud::model_cache::CacheConfig {
+                    policy: crate::crud::model_cache::CachePolicy::#cache_policy,
+                    ..::std::default::Default::default()
+                };
+                #cache_capacity
+                #cache_ttl
+                config
+            }
+
+            fn model_cache() -> &'static ::std::sync::Mutex<crate::crud::model_cache::ModelCache<Self>> {
+                static CACHE: ::once_cell::sync::Lazy<::std::sync::Mutex<crate::crud::model_cache::ModelCache<#name>>> =
+                    ::once_cell::sync::Lazy::new(|| {
+                        ::std::sync::Mutex::new(crate::crud::model_cache::ModelCache::new(
+                            <#name as crate::crud::crud_ops::ArxModel>::cache_config(),
+                        ))
+                    });
+                &CACHE
+            }
+
+            fn field_names() -> Vec<&'static str> {
+                let mut names = vec!["id"];
+                #(#field_names)*
+                names
+            }
+
+            fn text_fields() -> Vec<&'static str> {
+                #[allow(unused_mut)]
+                let mut names = Vec::new();
+                #(#text_fields)*
+                names
+            }
+
+            fn to_values(&self) -> Vec<(&'static str, crate::crud::model_value::ModelValue)> {
+                let mut values = vec![("id", crate::crud::model_value::ToModelValue::to_model_value(&self.id))];
+                #(#to_values)*
+                values
+            }
+
+            fn from_values(
+                values: &::std::collections::HashMap<String, crate::crud::model_value::ModelValue>,
+            ) -> Result<Self, String> {
+                Ok(#name {
+                    id: crate::crud::model_value::field_value(values, "id")?,
+                    #(#from_values)*
+                    #(#defaulted_fields)*
+                    #store_field
+                    #ops_field
+                })
+            }
+
+            #revocation_fns
+            #version_fns
+            #soft_delete_fns
+            #secret_fns
+            #validate_fn
+            #relation_fns
+            #dependents_fn
+        }
+
+        impl crate::crud::crud_ops::Create<#name> for #name {
+            fn create(item: #name) -> Result<#name, crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::create_model(item)
+            }
+        }
+
+        impl crate::crud::crud_ops::Read<#name> for #name {
+            fn read(id: #id_type) -> Result<#name, crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::read_model::<#name>(id)
+            }
+        }
+
+        impl crate::crud::crud_ops::Update<#name> for #name {
+            fn update(item: #name) -> Result<#name, crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::update_model(item)
+            }
+        }
+
+        impl crate::crud::crud_ops::Delete for #name {
+            fn delete(id: #id_type) -> Result<(), crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::delete_model::<#name>(id)
+            }
+        }
+
+        impl crate::crud::crud_ops::List<#name> for #name {
+            fn list(query: &crate::crud::query::Query) -> Result<crate::crud::query::Page<#name>, crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::list_models::<#name>(query)
+            }
+        }
+
+        impl crate::crud::crud_ops::Search<#name> for #name {
+            fn search(query: &crate::crud::query::Query) -> Result<crate::crud::query::Page<#name>, crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::search_models::<#name>(query)
+            }
+        }
+
+        impl crate::crud::crud_ops::Revoke for #name {
+            fn revoke(
+                id: #id_type,
+                reason: &str,
+                actor: &str,
+            ) -> Result<crate::crud::revocation::Revocation, crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::revoke_model::<#name>(id, reason, actor)
+            }
+
+            fn is_revoked(id: #id_type) -> Result<bool, crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::is_revoked_model::<#name>(id)
+            }
+        }
+
+        impl crate::crud::crud_ops::SoftDelete for #name {
+            fn restore(id: #id_type) -> Result<#name, crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::restore_model::<#name>(id)
+            }
+
+            fn list_trashed(query: &crate::crud::query::Query) -> Result<crate::crud::query::Page<#name>, crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::list_trashed_models::<#name>(query)
+            }
+
+            fn purge(retention: ::std::time::Duration) -> Result<usize, crate::crud::crud_ops::CrudError> {
+                crate::crud::crud_ops::purge_trashed_models::<#name>(retention)
+            }
+        }
+    })
+}
@@ -3,6 +3,6 @@ pub mod routers;
 
 pub fn initialize() -> Result<(), String> {
     // Logica di inizializzazione per API
-    println!("Initializing API module...");
+    log::info!("Initializing API module...");
     Ok(())
 }
@@ -2,6 +2,6 @@ pub mod auth_core;
 
 pub fn initialize() -> Result<(), String> {
     // Qui puoi mettere il codice di inizializzazione specifico per il modulo auth
-    println!("Auth module initialized");
+    log::info!("Auth module initialized");
     Ok(())
 }
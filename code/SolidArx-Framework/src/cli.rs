@@ -1,15 +1,29 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use std::str::FromStr;
-use crate::config::global_config::{
-    ApplicationType,
-    DatabaseType,
-}; 
+use crate::config::global_config::ApplicationType;
+use crate::config::network_config::{ConnectionConfig, DatabaseType};
+use crate::monitoring::output::OutputFormat;
+use crate::config::project_config::{
+    CoreProfile,
+    DatabaseProfile,
+    MemoryProfile,
+    ProfileConfig,
+    ProjectConfig,
+    DEFAULT_CONFIG_PATH,
+    DEFAULT_PROFILE,
+};
 
 /// CLI per ArxFramework
 #[derive(Parser)]
 #[command(name = "Arx")]
 #[command(about = "CLI per il framework Arx", long_about = None)]
 pub struct Cli {
+    /// File di configurazione di progetto contenente i profili
+    #[arg(long = "config", global = true, default_value = DEFAULT_CONFIG_PATH)]
+    pub config: String,
+    /// Formato dell'output dei comandi (text o json)
+    #[arg(long = "output", global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -30,7 +44,6 @@ impl FromStr for DatabaseType {
 }
 
 /// Converte il ClI input in un tipo di applicazione
-
 impl FromStr for ApplicationType {
 
     type Err = String;
@@ -42,6 +55,7 @@ impl FromStr for ApplicationType {
             "desktopapp" | "desktop" | "da" => Ok(ApplicationType::DesktopApp),
             "automationscript" | "automation" | "as" => Ok(ApplicationType::AutomationScript),
             "embeddedsystem" | "embedded" | "es" => Ok(ApplicationType::EmbeddedSystem),
+            "none" => Ok(ApplicationType::None),
             _ => Err(format!("Tipo di applicazione non riconosciuto: {}", input)),
         }
     }
@@ -54,8 +68,11 @@ pub enum Commands {
     /// Inizializza un nuovo progetto con un tipo di applicazione specifico
     Init {
         /// Il tipo di applicazione da inizializzare (WebApp, ApiBackend, DesktopApp, etc.)
-        #[arg(short = 'a', long = "app-type")]
-        app_type: ApplicationType,
+        #[arg(short = 'a', long = "app-type", required_unless_present = "interactive")]
+        app_type: Option<ApplicationType>,
+        /// Configurazione guidata di applicazione, memoria e database
+        #[arg(short = 'i', long = "interactive", conflicts_with = "app_type")]
+        interactive: bool,
         /// Memory multiplier per mole di memoria
         /// Usato per genrare variabile di config per il modulo crud_ops.rs
         #[arg(short = 'm', long = "memory-scale", default_value_t = 1)] // Valore di default: 1.0 
@@ -69,74 +86,365 @@ pub enum Commands {
         /// Pool size per la memoria
         #[arg(short = 'p', long = "pool-size", default_value_t = 0)] // Valore di default: 8
         pool_size: usize,
+        /// Profilo di `arx.toml` in cui salvare la configurazione
+        #[arg(long = "profile", default_value = DEFAULT_PROFILE)]
+        profile: String,
+    },
+    /// Avvia il CoreSystem con la configurazione di un profilo, applicando gli override passati dal CLI
+    ///
+    /// Esempio: arx run --profile prod --max-threads 16
+    Run {
+        /// Profilo di `arx.toml` da utilizzare (es. dev, prod)
+        #[arg(long = "profile", default_value = DEFAULT_PROFILE)]
+        profile: String,
+        #[command(flatten)]
+        overrides: RunOverrides,
+    },
+    /// Mostra la configurazione di un profilo di `arx.toml` e i valori risolti per `arx run`
+    Config {
+        /// Profilo di `arx.toml` da mostrare
+        #[arg(long = "profile", default_value = DEFAULT_PROFILE)]
+        profile: String,
+    },
+    /// Mostra lo stato del progetto: profili di `arx.toml`, se sono pronti per `arx run`
+    /// e feature compilate nel binario
+    Status,
+    /// Gestione dei modelli CRUD personalizzati in `crud/models/dev`
+    Model {
+        #[command(subcommand)]
+        action: ModelCommands,
+    },
+    /// Gestione esplicita dello schema del database tramite migrazioni
+    Db {
+        #[command(flatten)]
+        connection: DbConnectionArgs,
+        #[command(subcommand)]
+        action: DbCommands,
     },
+    /// Esportazione e importazione dei record dei modelli CRUD per spostare i dati tra ambienti
+    ///
+    /// Esempio: arx data --profile staging export configuration --format csv --file configuration.csv
+    Data {
+        #[command(flatten)]
+        connection: DbConnectionArgs,
+        #[command(subcommand)]
+        action: DataCommands,
+    },
+    /// Diagnostica dell'ambiente: Python, cartella dei log, database, feature e cartelle dei modelli
+    ///
+    /// Tipo di applicazione e database vengono letti dal profilo di `arx.toml`, se presente,
+    /// e possono essere sovrascritti dal CLI.
+    Doctor {
+        /// Profilo di `arx.toml` da verificare
+        #[arg(long = "profile", default_value = DEFAULT_PROFILE)]
+        profile: String,
+        /// Il tipo di applicazione di cui verificare le feature richieste
+        #[arg(short = 'a', long = "app-type")]
+        app_type: Option<ApplicationType>,
+        /// Il tipo di database di cui verificare la raggiungibilità
+        #[arg(short = 'd', long = "database-type")]
+        database_type: Option<DatabaseType>,
+        /// URL del database di cui verificare la raggiungibilità
+        #[arg(short = 'u', long = "database-url")]
+        database_url: Option<String>,
+    },
+    /// Configura il database di un profilo di `arx.toml`
     Database {
 
-        /// Disabilita l'utilizzo del database
-        #[arg(short = 'd', long = "database-type"), default_value_t = DatabaseType::None]
-        database_type_reference: DatabaseType,
+        /// Il tipo di database (PostgreSQL, SQLite, MongoDB, None per disabilitarlo)
+        #[arg(short = 'd', long = "database-type")]
+        database_type: DatabaseType,
 
         /// URL del database da utilizzare
-        #[arg(short = 'url', long = "database-url"), default_value_t = None]
+        #[arg(short = 'u', long = "database-url")]
         database_url: Option<String>,
 
         /// Numero massimo di connessioni simultanee consentite
-        #[arg(short = 'c', long = "max-connections", default_value_t = None)]
+        #[arg(short = 'c', long = "max-connections")]
         max_connections: Option<u32>,
 
         /// Numero massimo di tentativi di riconnessione in caso di fallimento
-        #[arg(short = 'r', long = "retry-attempts", default_value_t = None)]
-        max_attempts: Option<u32>,
+        #[arg(short = 'r', long = "retry-attempts")]
+        retry_attempts: Option<u32>,
 
         /// Durata massima per mantenere una connessione inattiva
-        #[arg(short = 'i', long = "max-idle-time", default_value_t = None)]
+        #[arg(short = 'i', long = "max-idle-time")]
         max_idle_time: Option<u64>,
 
         /// Tempo massimo di attesa per stabilire una connessione
-        #[arg(short = 't', long = "connection-timeout", default_value_t = None)]
+        #[arg(short = 't', long = "connection-timeout")]
         connection_timeout: Option<u64>,
 
+        /// Profilo di `arx.toml` in cui salvare la configurazione
+        #[arg(long = "profile", default_value = DEFAULT_PROFILE)]
+        profile: String,
+
         // I campi sono utilizzati in:
+        // - `config/project_config.rs` per il salvataggio nel profilo
         // - `config/network_config.rs` per la configurazione della connessione al database
         // - `network/connection_management.rs` per la gestione delle connessioni al database
-        // - `core/system_core.rs` nella funzione run() per lo scraping dei dati
+    },
+}
+
+impl Commands {
+    /// Restituisce il nome del comando, utilizzato nel documento JSON di `--output json`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Commands::Init { .. } => "init",
+            Commands::Run { .. } => "run",
+            Commands::Config { .. } => "config",
+            Commands::Status => "status",
+            Commands::Model { action: ModelCommands::New { .. } } => "model new",
+            Commands::Db { action, .. } => match action {
+                DbCommands::Migrate { .. } => "db migrate",
+                DbCommands::Status => "db status",
+                DbCommands::Rollback { .. } => "db rollback",
+                DbCommands::Plan => "db plan",
+                DbCommands::Introspect { .. } => "db introspect",
+            },
+            Commands::Data { action, .. } => match action {
+                DataCommands::Export { .. } => "data export",
+                DataCommands::Import { .. } => "data import",
+            },
+            Commands::Doctor { .. } => "doctor",
+            Commands::Database { .. } => "database",
+        }
+    }
+}
+
+/// Override della configurazione del profilo per `arx run`
+///
+/// I valori non specificati vengono letti dal profilo di `arx.toml`.
+#[derive(Args, Default)]
+pub struct RunOverrides {
+    /// Il tipo di applicazione da avviare
+    #[arg(short = 'a', long = "app-type")]
+    pub app_type: Option<ApplicationType>,
+    /// Numero massimo di thread per l'applicazione
+    #[arg(short = 't', long = "max-threads")]
+    pub max_threads: Option<u8>,
+    /// Memory multiplier per mole di memoria
+    #[arg(short = 'm', long = "memory-scale")]
+    pub memory_scale: Option<u8>,
+    /// Buffer size per la memoria
+    #[arg(short = 'b', long = "buffer-size")]
+    pub buffer_size: Option<usize>,
+    /// Pool size per la memoria
+    #[arg(short = 'p', long = "pool-size")]
+    pub pool_size: Option<usize>,
+    /// Il tipo di database (PostgreSQL, SQLite, MongoDB, None)
+    #[arg(short = 'd', long = "database-type")]
+    pub database_type: Option<DatabaseType>,
+    /// URL del database da utilizzare
+    #[arg(short = 'u', long = "database-url")]
+    pub database_url: Option<String>,
+    /// Numero massimo di connessioni simultanee consentite
+    #[arg(long = "max-connections")]
+    pub max_connections: Option<u32>,
+    /// Numero massimo di tentativi di riconnessione in caso di fallimento
+    #[arg(long = "retry-attempts")]
+    pub retry_attempts: Option<u32>,
+    /// Durata massima per mantenere una connessione inattiva
+    #[arg(long = "max-idle-time")]
+    pub max_idle_time: Option<u64>,
+    /// Tempo massimo di attesa per stabilire una connessione
+    #[arg(long = "connection-timeout")]
+    pub connection_timeout: Option<u64>,
+}
+
+impl RunOverrides {
+    /// Converte gli override in un `ProfileConfig` da combinare con il profilo salvato
+    pub fn profile(&self) -> ProfileConfig {
+        ProfileConfig {
+            core: CoreProfile {
+                app_type: self.app_type.as_ref().map(|a| format!("{:?}", a).to_lowercase()),
+                max_threads: self.max_threads,
+            },
+            memory: MemoryProfile {
+                memory_scale: self.memory_scale,
+                buffer_size: self.buffer_size,
+                pool_size: self.pool_size,
+            },
+            database: DatabaseProfile {
+                database_type: self.database_type.as_ref().map(|d| d.kind().to_string()),
+                database_url: self.database_url.clone(),
+                max_connections: self.max_connections,
+                retry_attempts: self.retry_attempts,
+                max_idle_time: self.max_idle_time,
+                connection_timeout: self.connection_timeout,
+            },
+        }
+    }
+}
+
+/// Sottocomandi di `arx model`
+#[derive(Subcommand)]
+pub enum ModelCommands {
+    /// Genera un nuovo modello in `crud/models/dev`, lo registra in `models/mod.rs` e lo collega a `crud_ops.rs`
+    ///
+    /// Esempio: arx model new Invoice --field amount:f64 --field customer_id:u32 --store database --ops create,read,list
+    New {
+        /// Nome del modello in PascalCase (es. Invoice)
+        name: String,
+        /// Campo del modello nel formato nome:tipo, ripetibile
+        #[arg(short = 'f', long = "field")]
+        fields: Vec<String>,
+        /// Tipo di allocazione del modello (database, memory o cached)
+        #[arg(short = 's', long = "store", default_value = "database")]
+        store: String,
+        /// Operazioni CRUD abilitate separate da virgola
+        #[arg(short = 'o', long = "ops", default_value = "create,read,update,delete,list")]
+        ops: String,
+    },
+}
+
+/// Argomenti di connessione condivisi dai sottocomandi di `arx db` e `arx data`
+///
+/// Se `-d` e `-u` non sono specificati il database viene letto dal profilo di `arx.toml`.
+#[derive(Args)]
+pub struct DbConnectionArgs {
+    /// Profilo di `arx.toml` da cui leggere il database
+    #[arg(long = "profile", default_value = DEFAULT_PROFILE)]
+    pub profile: String,
+    /// Il tipo di database (PostgreSQL, SQLite, MongoDB), sovrascrive quello del profilo
+    #[arg(short = 'd', long = "database-type")]
+    pub database_type: Option<DatabaseType>,
+    /// URL del database da utilizzare, sovrascrive quello del profilo
+    #[arg(short = 'u', long = "database-url")]
+    pub database_url: Option<String>,
+}
+
+impl DbConnectionArgs {
+    /// Restituisce il `DatabaseType` del profilo selezionato con gli override di `-d` e `-u`
+    ///
+    /// # Ritorna
+    /// Un errore se il profilo non esiste e `-d` e `-u` non sono entrambi specificati,
+    /// oppure se il profilo non ha un database configurato.
+    pub fn database(&self, project_config: &ProjectConfig) -> Result<DatabaseType, String> {
+        let overrides = ProfileConfig {
+            database: DatabaseProfile {
+                database_type: self.database_type.as_ref().map(|d| d.kind().to_string()),
+                database_url: self.database_url.clone(),
+                ..DatabaseProfile::default()
+            },
+            ..ProfileConfig::default()
+        };
+        let profile_config = match project_config.profile(&self.profile) {
+            Ok(profile_config) => profile_config.merge(&overrides),
+            Err(_) if self.database_type.is_some() && self.database_url.is_some() => overrides,
+            Err(e) => return Err(e),
+        };
+
+        match profile_config.database_type()? {
+            DatabaseType::None => Err(format!(
+                "Nessun database configurato nel profilo '{}'. Specificare -d e -u oppure eseguire 'arx database --profile {}'",
+                self.profile, self.profile
+            )),
+            database => Ok(database),
+        }
     }
-    Help{
-        "HELP\n
-        Command: Arx\n
-        arx init  # Init app\n
-
-        Options:\n 
-        --app_type or -a\n
-        \n
-            Application types:\n
-            --app_type <WebApp>\n
-            --app_type <ApiBackend>\n
-            --app_type <DesktopApp>\n
-            --app_type <AutomationScript>\n
-            --app_type <EmbeddedSystem>\n
-        \n
-        --memory_scale u8 or --m u8           # Set Memory Multiplier, DEFAULT: 1\n
-        --max_threads u8 or --t u8            # Set Max Threads, DEFAULT: 8\n
-        --buffer_size usize or --b usize      # Set Buffer Size, DEFAULT: 1024\n
-        --version or --v                      # Display Version\n
-        \n
-        Command: Arx database\n
-        arx database #Define Database\n 
-        \n
-        Options:\n
-        --database_type <Bool> or --d <Bool>                         # Disable Database, Default: true\n
-        --database_url Option<String>  or --url Option<String>          # Set Database URL, Default: None\n
-        --max_connections Option<u32> or --c Option<u32>                # Set Max Connections, Default: None\ \n
-        --retry_attempts Option<u32> or --r Option<u32>                 # Set Retry Attempts, Default: None\\n
-        --max_idle_time Option<u64> or --i Option<u64>                  # Set Max Idle Time, Default: None\\n
-        --connection_timeout Option<u64> or --t Option<u64>             # Set Connection Timeout, Default: None\n\n
-        
-        Default None implica gestione predefinita da parte del codice\\n"
+}
+
+/// Sottocomandi di `arx db`
+#[derive(Subcommand)]
+pub enum DbCommands {
+    /// Applica le modifiche dello schema ricavate dai modelli e le registra nello storico
+    Migrate {
+        /// Nome descrittivo della migrazione, di default auto_<timestamp>
+        #[arg(short = 'n', long = "name")]
+        name: Option<String>,
+    },
+    /// Mostra le migrazioni applicate e le modifiche in attesa
+    Status,
+    /// Annulla le ultime <n> migrazioni applicate
+    Rollback {
+        /// Numero di migrazioni da annullare
+        #[arg(default_value_t = 1)]
+        steps: u32,
+    },
+    /// Mostra le istruzioni che `arx db migrate` applicherebbe, senza eseguirle
+    Plan,
+    /// Genera i modelli in `crud/models/dev` a partire dallo schema di un database esistente
+    Introspect {
+        /// Tabella (o collezione) da includere, ripetibile. Di default tutte
+        #[arg(short = 't', long = "table")]
+        tables: Vec<String>,
+        /// Numero di documenti campionati per ogni collezione MongoDB
+        #[arg(short = 's', long = "sample-size", default_value_t = 100)]
+        sample_size: u32,
+        /// Mostra i modelli che verrebbero generati senza scrivere i file
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+    },
+}
+
+/// Sottocomandi di `arx data`
+#[derive(Subcommand)]
+pub enum DataCommands {
+    /// Esporta tutti i record di un modello, compresi quelli revocati e nel cestino
+    Export {
+        /// Nome della tabella del modello (es. sensor_data)
+        model: String,
+        /// Formato del file (json o csv)
+        #[arg(short = 'f', long = "format", default_value = "json", value_parser = ["json", "csv"])]
+        format: String,
+        /// File in cui scrivere i record
+        #[arg(long = "file")]
+        file: String,
+    },
+    /// Importa i record di un modello da un file esportato, mantenendo gli ID
+    Import {
+        /// Nome della tabella del modello (es. sensor_data)
+        model: String,
+        /// Formato del file (json o csv)
+        #[arg(short = 'f', long = "format", default_value = "json", value_parser = ["json", "csv"])]
+        format: String,
+        /// File da cui leggere i record
+        #[arg(long = "file")]
+        file: String,
+        /// Numero di record inseriti in un'unica transazione
+        #[arg(short = 'b', long = "batch-size", default_value_t = 500)]
+        batch_size: usize,
     },
 }
 
 /// Parsing degli argomenti e ritorno della configurazione CLI
 pub fn parse_arguments() -> Result<Cli, clap::Error> {
     Cli::try_parse()
-}
\ No newline at end of file
+}
+/// Pre-analisi degli argomenti quando il parsing di clap fallisce.
+///
+/// Restituisce il formato richiesto con `--output` (o `--output=`) e il nome del comando
+/// riconosciuto (es. `db status`, `arx` se nessun sottocomando è valido), così che anche
+/// gli errori di clap vengano emessi come documento JSON con `--output json`.
+pub fn requested_output<I, S>(args: I) -> (OutputFormat, String)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    let args: Vec<String> = args.into_iter()
+        .map(|arg| arg.as_ref().to_string_lossy().into_owned())
+        .collect();
+
+    let mut format = OutputFormat::Text;
+    let mut command = Cli::command();
+    let mut names = Vec::new();
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        let value = match arg.strip_prefix("--output") {
+            Some("") => iter.next().map(String::as_str),
+            Some(value) => value.strip_prefix('='),
+            None => None,
+        };
+        if let Some(value) = value {
+            // Un valore non valido è esso stesso l'errore di clap: resta il formato testuale
+            format = <OutputFormat as ValueEnum>::from_str(value, true).unwrap_or(OutputFormat::Text);
+        } else if let Some(subcommand) = command.find_subcommand(arg).cloned() {
+            names.push(subcommand.get_name().to_string());
+            command = subcommand;
+        }
+    }
+
+    let command = if names.is_empty() { "arx".to_string() } else { names.join(" ") };
+    (format, command)
+}
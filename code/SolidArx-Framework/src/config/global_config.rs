@@ -19,6 +19,47 @@ pub enum ApplicationType {
     None,
 }
 
+impl ApplicationType {
+    /// Restituisce le feature dei moduli richieste dal tipo di applicazione, come definite
+    /// dalle feature per tipo di applicazione di `Cargo.toml` (es. `automation = ["task_automation"]`).
+    ///
+    /// Utilizzata sia da `CoreSystem::run` che da `arx doctor`.
+    pub fn required_features(&self) -> &'static [&'static str] {
+        match self {
+            ApplicationType::WebApp => &["auth", "crud", "api", "frontend"],
+            ApplicationType::ApiBackend => &["auth", "crud", "api"],
+            ApplicationType::DesktopApp => &["auth", "crud", "file_management", "frontend"],
+            ApplicationType::AutomationScript => &["task_automation"],
+            ApplicationType::EmbeddedSystem | ApplicationType::None => &[],
+        }
+    }
+
+    /// Restituisce le feature richieste dal tipo di applicazione non compilate nel binario corrente.
+    pub fn missing_features(&self) -> Vec<&'static str> {
+        let compiled = compiled_features();
+        self.required_features()
+            .iter()
+            .filter(|feature| !compiled.contains(feature))
+            .copied()
+            .collect()
+    }
+}
+
+/// Restituisce le feature dei moduli compilate nel binario corrente.
+pub fn compiled_features() -> Vec<&'static str> {
+    let features = [
+        ("auth", cfg!(feature = "auth")),
+        ("crud", cfg!(feature = "crud")),
+        ("api", cfg!(feature = "api")),
+        ("file_management", cfg!(feature = "file_management")),
+        ("task_automation", cfg!(feature = "task_automation")),
+        ("blockchain", cfg!(feature = "blockchain")),
+        ("ml", cfg!(feature = "ml")),
+        ("frontend", cfg!(feature = "frontend")),
+    ];
+    features.iter().filter(|(_, enabled)| *enabled).map(|(name, _)| *name).collect()
+}
+
 
 /// Configurazione globale del sistema.
 ///
@@ -37,7 +78,10 @@ impl Default for CoreConfig {
             max_threads: 4,
         }
     }
-    fn new(app_type: ApplicationType, max_threads: u8) -> Self {
+}
+
+impl CoreConfig {
+    pub fn new(app_type: ApplicationType, max_threads: u8) -> Self {
         CoreConfig {
             app_type,
             max_threads,
@@ -0,0 +1,165 @@
+//! Modulo per l'inizializzazione guidata del progetto.
+//!
+//! Utilizzato da `arx init --interactive`, guida l'utente nella scelta del tipo di applicazione,
+//! delle impostazioni di memoria e thread e del database. Per buffer e pool vengono mostrati i valori
+//! di default del tipo di applicazione scelto (`define_buffer_size`, `define_pool_size`).
+//! Ogni risposta viene validata subito e, se non valida, la domanda viene ripetuta.
+//!
+//! Il risultato è un `ProfileConfig` che viene salvato in `arx.toml` come per `arx init` e `arx database`.
+//!
+//! Le funzioni accettano un input `BufRead` e un output `Write` generici, così da poter essere
+//! testate senza un terminale. Le domande vengono scritte su stderr da `main.rs`,
+//! in modo che stdout resti riservato all'output del comando (anche con `--output json`).
+
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use crate::config::global_config::ApplicationType;
+use crate::config::network_config::DatabaseType;
+use crate::config::project_config::{CoreProfile, DatabaseProfile, MemoryProfile, ProfileConfig};
+use crate::core::memory_management::{define_buffer_size, define_pool_size};
+
+/// Valore di default di `memory_scale`, come in `Commands::Init`.
+const DEFAULT_MEMORY_SCALE: u8 = 1;
+/// Valore di default di `max_threads`, come in `Commands::Init`.
+const DEFAULT_MAX_THREADS: u8 = 8;
+
+/// Pone una domanda e restituisce la risposta, oppure il default se la risposta è vuota.
+///
+/// # Parametri
+/// - `question`: Testo della domanda.
+/// - `default`: Valore mostrato tra parentesi quadre e restituito con una risposta vuota.
+/// - `validate`: Funzione di validazione, in caso di errore il messaggio viene mostrato e la domanda ripetuta.
+fn ask<T, R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    question: &str,
+    default: Option<&str>,
+    validate: impl Fn(&str) -> Result<T, String>,
+) -> Result<T, String> {
+    loop {
+        let prompt = match default {
+            Some(default) => write!(output, "{} [{}]: ", question, default),
+            None => write!(output, "{}: ", question),
+        };
+        prompt.and_then(|_| output.flush()).map_err(|e| e.to_string())?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+            return Err("Input terminato prima del completamento della configurazione".to_string());
+        }
+
+        let answer = match line.trim() {
+            "" => default.unwrap_or(""),
+            answer => answer,
+        };
+        match validate(answer) {
+            Ok(value) => return Ok(value),
+            Err(e) => writeln!(output, "  {}", e).map_err(|e| e.to_string())?,
+        }
+    }
+}
+
+/// Valida il tipo di applicazione, `None` non è ammesso.
+pub fn validate_app_type(answer: &str) -> Result<ApplicationType, String> {
+    match ApplicationType::from_str(answer)? {
+        ApplicationType::None => Err("Selezionare un tipo di applicazione".to_string()),
+        app_type => Ok(app_type),
+    }
+}
+
+/// Valida un numero `u8` maggiore di 0.
+pub fn validate_positive_u8(answer: &str) -> Result<u8, String> {
+    match answer.parse::<u8>() {
+        Ok(0) => Err("Il valore deve essere maggiore di 0".to_string()),
+        Ok(value) => Ok(value),
+        Err(_) => Err(format!("Inserire un numero tra 1 e {}", u8::MAX)),
+    }
+}
+
+/// Valida una dimensione in byte, entro il limite accettato da `define_buffer_size` e `define_pool_size`.
+pub fn validate_size(answer: &str) -> Result<usize, String> {
+    match answer.parse::<usize>() {
+        Ok(0) => Err("Il valore deve essere maggiore di 0".to_string()),
+        Ok(value) if value > usize::MAX / 2 => Err(format!("Il valore massimo è {}", usize::MAX / 2)),
+        Ok(value) => Ok(value),
+        Err(_) => Err("Inserire una dimensione in byte".to_string()),
+    }
+}
+
+/// Valida l'URL del database in base al tipo di database scelto.
+pub fn validate_database_url(database_type: &DatabaseType, answer: &str) -> Result<String, String> {
+    if answer.is_empty() {
+        return Err("L'URL del database è obbligatorio".to_string());
+    }
+
+    let schemes: &[&str] = match database_type {
+        DatabaseType::PostgreSQL(_) => &["postgres://", "postgresql://"],
+        DatabaseType::MongoDB(_) => &["mongodb://", "mongodb+srv://"],
+        // SQLite accetta il percorso del file del database
+        DatabaseType::SQLite(_) | DatabaseType::None => &[],
+    };
+    if !schemes.is_empty() && !schemes.iter().any(|scheme| answer.starts_with(scheme)) {
+        return Err(format!("L'URL deve iniziare con {}", schemes.join(" oppure ")));
+    }
+    Ok(answer.to_string())
+}
+
+/// Esegue la configurazione guidata e restituisce il profilo risultante.
+///
+/// # Parametri
+/// - `input`: Sorgente delle risposte (stdin in `main.rs`).
+/// - `output`: Destinazione delle domande (stderr in `main.rs`).
+///
+/// # Ritorna
+/// Il `ProfileConfig` da salvare in `arx.toml`, oppure un errore se l'input termina prima della fine.
+pub fn run_wizard<R: BufRead, W: Write>(input: &mut R, output: &mut W) -> Result<ProfileConfig, String> {
+    writeln!(output, "Configurazione guidata del progetto Arx").map_err(|e| e.to_string())?;
+
+    let app_type = ask(
+        input,
+        output,
+        "Tipo di applicazione (webapp, apibackend, desktopapp, automationscript, embeddedsystem)",
+        None,
+        validate_app_type,
+    )?;
+
+    let memory_scale = ask(input, output, "Memory scale", Some(&DEFAULT_MEMORY_SCALE.to_string()), validate_positive_u8)?;
+    let max_threads = ask(input, output, "Numero massimo di thread", Some(&DEFAULT_MAX_THREADS.to_string()), validate_positive_u8)?;
+
+    // Default specifici per il tipo di applicazione scelto
+    let default_buffer = define_buffer_size(app_type.clone(), 0);
+    let default_pool = define_pool_size(app_type.clone(), 0);
+    let buffer_size = ask(input, output, "Buffer size in byte", Some(&default_buffer.to_string()), validate_size)?;
+    let pool_size = ask(input, output, "Pool size in byte", Some(&default_pool.to_string()), validate_size)?;
+
+    let database_type = ask(
+        input,
+        output,
+        "Tipo di database (postgresql, sqlite, mongodb, none)",
+        Some("none"),
+        DatabaseType::from_str,
+    )?;
+    let database_url = match database_type {
+        DatabaseType::None => None,
+        _ => Some(ask(input, output, "URL del database", None, |answer| validate_database_url(&database_type, answer))?),
+    };
+
+    // I valori uguali ai default del tipo di applicazione non vengono salvati, così seguono eventuali modifiche future
+    Ok(ProfileConfig {
+        core: CoreProfile {
+            app_type: Some(format!("{:?}", app_type).to_lowercase()),
+            max_threads: Some(max_threads),
+        },
+        memory: MemoryProfile {
+            memory_scale: Some(memory_scale),
+            buffer_size: (buffer_size != default_buffer).then_some(buffer_size),
+            pool_size: (pool_size != default_pool).then_some(pool_size),
+        },
+        database: DatabaseProfile {
+            database_type: Some(database_type.kind().to_string()),
+            database_url,
+            ..DatabaseProfile::default()
+        },
+    })
+}
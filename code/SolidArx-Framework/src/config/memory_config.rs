@@ -2,6 +2,7 @@
 ///
 /// Questa configurazione è utilizzata per gestire le impostazioni relative alla memoria,
 /// come la dimensione del pool di buffer o la dimensione del buffer nei sistemi embedded.
+#[derive(Debug, Clone)]
 pub struct MemoryConfig {
     pub pool_size: usize,    // Dimensione del pool di buffer (per PoolBased)
     pub buffer_size: usize,  // Dimensione del buffer (per Embedded)
@@ -1,3 +1,5 @@
 pub mod global_config;
 pub mod network_config;
-pub mod memory_config;
\ No newline at end of file
+pub mod memory_config;
+pub mod project_config;
+pub mod init_wizard;
@@ -7,7 +7,7 @@ use cfg_if::cfg_if;
 /// - `retry_attempts`: Numero massimo di tentativi di riconnessione in caso di fallimento.
 /// - `max_idle_time`: Durata massima per mantenere una connessione inattiva.
 /// - `connection_timeout`: Tempo massimo di attesa per stabilire una connessione.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ConnectionConfig {
     pub database_url: Option<String>,
     pub max_connections: Option<u32>,
@@ -30,7 +30,13 @@ pub enum DatabaseType {
 impl DatabaseType {
     /// Crea una nuova istanza di `DatabaseType` in base alla configurazione dell'applicazione.
     /// Restituisce un `Result` che contiene un errore se nessuna configurazione è definita per l'app corrente.
+    ///
+    /// # Parametri
+    /// - `kind`: Tipo di database da configurare, la `ConnectionConfig` contenuta viene ignorata.
+    // I parametri vengono riassegnati solo se è attiva una feature di applicazione
+    #[allow(unused_mut)]
     pub fn new( 
+        kind: DatabaseType,
         du: Option<String>, 
         mut mc: Option<u32>, 
         mut ra: Option<u32>, 
@@ -41,7 +47,7 @@ impl DatabaseType {
         // Sezione di controllo delle variabili costruttore
         // Imposta valori di default specifici per ogni app se non definiti
         
-        if du == None {
+        if du.is_none() {
             error!("Database URL non impostato per l' applicazione. Utilizzare il comando CLI per impostare l'URL del database.\
                     Digitare 'Arx Help' per ulteriori informazioni");
             return Err("Database URL non impostato per l' applicazione. Utilizzare il comando CLI per impostare l'URL del database.\
@@ -49,7 +55,7 @@ impl DatabaseType {
         }   
 
         // Questa sezione di occupa di impostare i valori di default per le variabili non definite
-        if mc == None {
+        if mc.is_none() {
             cfg_if! {
                 if #[cfg(feature = "webapp")] {
                     mc = Some(100);
@@ -65,7 +71,7 @@ impl DatabaseType {
             }
         }
         
-        if ra == None {
+        if ra.is_none() {
             cfg_if! {
                 if #[cfg(feature = "webapp")] {
                     ra = Some(3);
@@ -81,7 +87,7 @@ impl DatabaseType {
             }
         }
         
-        if mit == None {
+        if mit.is_none() {
             cfg_if! {
                 if #[cfg(feature = "webapp")] {
                     mit = Some(300);
@@ -97,7 +103,7 @@ impl DatabaseType {
             }
         }
         
-        if ct == None {
+        if ct.is_none() {
             cfg_if! {
                 if #[cfg(feature = "webapp")] {
                     ct = Some(5);
@@ -113,19 +119,32 @@ impl DatabaseType {
             }
         }
         
-        return Ok(
-            DatabaseType::PostgreSQL(
-                ConnectionConfig {
-                    database_url:du,
-                    max_connections:mc,
-                    retry_attempts:ra,
-                    max_idle_time:mit,       
-                    connection_timeout:ct,   
-                }
-            )
-        );
+        let config = ConnectionConfig {
+            database_url:du,
+            max_connections:mc,
+            retry_attempts:ra,
+            max_idle_time:mit,       
+            connection_timeout:ct,   
+        };
+
+        match kind {
+            DatabaseType::PostgreSQL(_) => Ok(DatabaseType::PostgreSQL(config)),
+            DatabaseType::SQLite(_) => Ok(DatabaseType::SQLite(config)),
+            DatabaseType::MongoDB(_) => Ok(DatabaseType::MongoDB(config)),
+            DatabaseType::None => Ok(DatabaseType::None),
+        }
             
     }
+
+    /// Restituisce il nome del tipo di database, utilizzato per salvarlo nei profili di `arx.toml`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DatabaseType::PostgreSQL(_) => "postgresql",
+            DatabaseType::SQLite(_) => "sqlite",
+            DatabaseType::MongoDB(_) => "mongodb",
+            DatabaseType::None => "none",
+        }
+    }
        
 
     /// Funzione di log che fornisce informazioni di stato sulla connessione al database.
@@ -0,0 +1,240 @@
+//! Modulo per la gestione della configurazione di progetto e dei profili.
+//!
+//! La configurazione di progetto è salvata nel file `arx.toml` nella root del progetto e contiene
+//! uno o più profili con nome (es. `dev`, `prod`). Ogni profilo raccoglie le impostazioni
+//! del Core, della Memoria e del Database, così che `arx run --profile <nome>` possa avviare
+//! il `CoreSystem` in una sola invocazione.
+//!
+//! I profili vengono scritti da `arx init` e `arx database`, mentre `arx run` li legge
+//! applicando eventuali override passati dal CLI.
+//!
+//! ### Esempio di `arx.toml`:
+//! ```toml
+//! [profiles.dev.core]
+//! app_type = "webapp"
+//! max_threads = 8
+//!
+//! [profiles.dev.memory]
+//! memory_scale = 1
+//!
+//! [profiles.dev.database]
+//! database_type = "sqlite"
+//! database_url = "dev.db"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use log::info;
+
+use crate::config::global_config::{ApplicationType, CoreConfig};
+use crate::config::memory_config::MemoryConfig;
+use crate::config::network_config::DatabaseType;
+use crate::core::memory_management::{define_buffer_size, define_multiplier, define_pool_size};
+
+/// Percorso di default del file di configurazione di progetto.
+pub const DEFAULT_CONFIG_PATH: &str = "arx.toml";
+/// Profilo utilizzato quando non ne viene specificato uno.
+pub const DEFAULT_PROFILE: &str = "dev";
+/// Numero di thread di default, come in `Commands::Init`.
+const DEFAULT_MAX_THREADS: u8 = 8;
+
+/// Restituisce la root del progetto, così che i percorsi del framework (es. la cartella dei log)
+/// non dipendano dalla cartella da cui viene eseguito `arx`.
+///
+/// Risale dalla cartella corrente fino alla prima che contiene `arx.toml` oppure `Cargo.toml`,
+/// altrimenti utilizza la cartella del pacchetto come `model_generator::models_path`.
+pub fn project_root() -> PathBuf {
+    std::env::current_dir()
+        .ok()
+        .and_then(|dir| {
+            dir.ancestors()
+                .find(|ancestor| ancestor.join(DEFAULT_CONFIG_PATH).is_file() || ancestor.join("Cargo.toml").is_file())
+                .map(Path::to_path_buf)
+        })
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")))
+}
+
+/// Impostazioni del Core salvate nel profilo.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoreProfile {
+    pub app_type: Option<String>,
+    pub max_threads: Option<u8>,
+}
+
+/// Impostazioni della Memoria salvate nel profilo.
+///
+/// I valori assenti (o pari a 0) vengono calcolati in base al tipo di applicazione
+/// tramite `define_pool_size`, `define_buffer_size` e `define_multiplier`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryProfile {
+    pub memory_scale: Option<u8>,
+    pub buffer_size: Option<usize>,
+    pub pool_size: Option<usize>,
+}
+
+/// Impostazioni del Database salvate nel profilo.
+///
+/// I valori di connessione assenti vengono impostati da `DatabaseType::new` in base alla feature attiva.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DatabaseProfile {
+    pub database_type: Option<String>,
+    pub database_url: Option<String>,
+    pub max_connections: Option<u32>,
+    pub retry_attempts: Option<u32>,
+    pub max_idle_time: Option<u64>,
+    pub connection_timeout: Option<u64>,
+}
+
+/// Profilo con nome che combina le impostazioni di Core, Memoria e Database.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    #[serde(default)]
+    pub core: CoreProfile,
+    #[serde(default)]
+    pub memory: MemoryProfile,
+    #[serde(default)]
+    pub database: DatabaseProfile,
+}
+
+/// Configurazione risolta di un profilo, pronta per `CoreSystem::new`.
+#[derive(Debug)]
+pub struct ResolvedProfile {
+    pub core: CoreConfig,
+    pub memory: MemoryConfig,
+    pub database: DatabaseType,
+}
+
+/// Restituisce `overrides` se presente, altrimenti `base`.
+fn pick<T: Clone>(base: &Option<T>, overrides: &Option<T>) -> Option<T> {
+    overrides.clone().or_else(|| base.clone())
+}
+
+impl ProfileConfig {
+    /// Combina il profilo con gli override passati dal CLI.
+    ///
+    /// # Parametri
+    /// - `overrides`: Profilo con i soli valori passati dal CLI, i valori presenti hanno la precedenza.
+    ///
+    /// # Ritorna
+    /// Un nuovo `ProfileConfig` con gli override applicati.
+    pub fn merge(&self, overrides: &ProfileConfig) -> ProfileConfig {
+        ProfileConfig {
+            core: CoreProfile {
+                app_type: pick(&self.core.app_type, &overrides.core.app_type),
+                max_threads: pick(&self.core.max_threads, &overrides.core.max_threads),
+            },
+            memory: MemoryProfile {
+                memory_scale: pick(&self.memory.memory_scale, &overrides.memory.memory_scale),
+                buffer_size: pick(&self.memory.buffer_size, &overrides.memory.buffer_size),
+                pool_size: pick(&self.memory.pool_size, &overrides.memory.pool_size),
+            },
+            database: DatabaseProfile {
+                database_type: pick(&self.database.database_type, &overrides.database.database_type),
+                database_url: pick(&self.database.database_url, &overrides.database.database_url),
+                max_connections: pick(&self.database.max_connections, &overrides.database.max_connections),
+                retry_attempts: pick(&self.database.retry_attempts, &overrides.database.retry_attempts),
+                max_idle_time: pick(&self.database.max_idle_time, &overrides.database.max_idle_time),
+                connection_timeout: pick(&self.database.connection_timeout, &overrides.database.connection_timeout),
+            },
+        }
+    }
+
+    /// Restituisce il tipo di applicazione del profilo, se impostato.
+    pub fn app_type(&self) -> Result<Option<ApplicationType>, String> {
+        self.core.app_type.as_deref().map(ApplicationType::from_str).transpose()
+    }
+
+    /// Restituisce la configurazione del database del profilo.
+    ///
+    /// # Ritorna
+    /// `DatabaseType::None` se il profilo non ha un database configurato.
+    pub fn database_type(&self) -> Result<DatabaseType, String> {
+        let database_type = match self.database.database_type.as_deref() {
+            None => return Ok(DatabaseType::None),
+            Some(database_type) => DatabaseType::from_str(database_type)?,
+        };
+        if let DatabaseType::None = database_type {
+            return Ok(DatabaseType::None);
+        }
+
+        DatabaseType::new(
+            database_type,
+            self.database.database_url.clone(),
+            self.database.max_connections,
+            self.database.retry_attempts,
+            self.database.max_idle_time,
+            self.database.connection_timeout,
+        ).map_err(|e| e.to_string())
+    }
+
+    /// Risolve il profilo nelle configurazioni utilizzate dal `CoreSystem`.
+    ///
+    /// # Ritorna
+    /// Un `ResolvedProfile` o un errore se il tipo di applicazione non è impostato
+    /// oppure la configurazione del database non è valida.
+    pub fn resolve(&self) -> Result<ResolvedProfile, String> {
+        let app_type = self.app_type()?
+            .ok_or_else(|| "Tipo di applicazione non impostato nel profilo. Eseguire 'arx init'".to_string())?;
+
+        let core = CoreConfig::new(app_type.clone(), self.core.max_threads.unwrap_or(DEFAULT_MAX_THREADS));
+        let memory = MemoryConfig::new(
+            define_pool_size(app_type.clone(), self.memory.pool_size.unwrap_or(0)),
+            define_buffer_size(app_type.clone(), self.memory.buffer_size.unwrap_or(0)),
+            define_multiplier(app_type, self.memory.memory_scale.unwrap_or(0)),
+        );
+
+        Ok(ResolvedProfile {
+            core,
+            memory,
+            database: self.database_type()?,
+        })
+    }
+}
+
+/// Configurazione di progetto salvata in `arx.toml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+impl ProjectConfig {
+    /// Carica la configurazione di progetto dal file indicato.
+    ///
+    /// # Ritorna
+    /// Una configurazione vuota se il file non esiste, oppure un errore se il file non è valido.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(ProjectConfig::default());
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Errore nella lettura di {}: {}", path.display(), e))?;
+        toml::from_str(&content)
+            .map_err(|e| format!("Errore nel parsing di {}: {}", path.display(), e))
+    }
+
+    /// Salva la configurazione di progetto nel file indicato.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| format!("Errore nella serializzazione della configurazione: {}", e))?;
+        fs::write(path, content)
+            .map_err(|e| format!("Errore nella scrittura di {}: {}", path.display(), e))?;
+        info!("Configurazione di progetto salvata in {}", path.display());
+        Ok(())
+    }
+
+    /// Restituisce il profilo con il nome indicato.
+    pub fn profile(&self, name: &str) -> Result<&ProfileConfig, String> {
+        self.profiles.get(name).ok_or_else(|| {
+            format!("Profilo '{}' non trovato. Eseguire 'arx init --profile {}'", name, name)
+        })
+    }
+
+    /// Restituisce il profilo con il nome indicato, creandolo se non esiste.
+    pub fn profile_mut(&mut self, name: &str) -> &mut ProfileConfig {
+        self.profiles.entry(name.to_string()).or_default()
+    }
+}
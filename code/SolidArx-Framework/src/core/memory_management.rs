@@ -17,38 +17,8 @@ use log::{info};
 use std::collections::VecDeque;
 use std::io::{self, Write};
 
-// Importing di lazy static per la creazione di strutture dati globali
-
-
-// struttura globale TASKS_IN_MEMORY che mantiene tutti i Task in memoria
-#[cfg(feature = "crud")]
-use std::sync::Mutex;
-#[cfg(feature = "crud")]
-use lazy_static::lazy_static;
-#[cfg(feature = "crud")]
-use std::collections::HashMap;
-#[cfg(feature = "crud")]
-use crate::crud::models::default::{
-    task::model::Task,
-    device::model::Device,
-    job::model::Job,
-    macro_script::model::Macro,
-    sensor_data::model::SensorData,
-    log_event::model::LogEvent,
-    command::model::Command,
-    configuration::model::Configuration,
-};
-#[cfg(feature = "crud")]
-lazy_static! {
-    pub static ref TASKS_IN_MEMORY: Mutex<HashMap<u32, Task>> = Mutex::new(HashMap::new());
-    pub static ref CONFIGURATIONS_IN_MEMORY: Mutex<HashMap<u32, Configuration>> = Mutex::new(HashMap::new());
-    pub static ref DEVICES_IN_MEMORY: Mutex<HashMap<u32, Device>> = Mutex::new(HashMap::new());
-    pub static ref JOBS_IN_MEMORY: Mutex<HashMap<u32, Job>> = Mutex::new(HashMap::new());
-    pub static ref MACROS_IN_MEMORY: Mutex<HashMap<u32, Macro>> = Mutex::new(HashMap::new());
-    pub static ref SENSOR_DATA_IN_MEMORY: Mutex<HashMap<u32, SensorData>> = Mutex::new(HashMap::new());
-    pub static ref LOG_EVENTS_IN_MEMORY: Mutex<HashMap<u32, LogEvent>> = Mutex::new(HashMap::new());
-    pub static ref COMMANDS_IN_MEMORY: Mutex<HashMap<u32, Command>> = Mutex::new(HashMap::new());
-}
+// Gli store in memoria dei modelli CRUD (prima `TASKS_IN_MEMORY`, `DEVICES_IN_MEMORY`, ecc.)
+// sono generati per ogni modello da `#[derive(ArxModel)]`, vedi `ArxModel::memory_store`
 
 
 /// Enum per rappresentare le diverse strategie di allocazione della memoria.
@@ -261,36 +231,7 @@ pub fn define_pool_size(app_type: ApplicationType, pool_size: usize) -> usize {
 }
 
 pub fn define_multiplier(app_type: ApplicationType, memory_scale: u8) -> u8 {
-    if memory_scale > u8::MAX {
-        println!("Il valore di memory_scale eccede il limite massimo di u8.\n\
-        Vuoi assegnare il valore massimo consentito ({})? [y/n]", u8::MAX);
-
-        let mut input = String::new();
-        io::stdout().flush().unwrap(); // Assicura che il prompt venga mostrato
-        io::stdin().read_line(&mut input).expect("Errore nella lettura dell'input");
-
-        match input.trim().to_lowercase().as_str() {
-            "y" => return u8::MAX,
-            "n" => {
-                println!("Inserisci un nuovo valore:");
-                input.clear();
-                io::stdin().read_line(&mut input).expect("Errore nella lettura dell'input");
-                
-                return match input.trim().parse::<u8>() {
-                    Ok(val) => val,
-                    Err(_) => {
-                        println!("Input non valido. Riprova.");
-                        return define_multiplier(app_type, memory_scale);
-                    }
-                };
-            }
-            _ => {
-                println!("Input non valido. Digita 'y' per accettare il valore \
-                massimo o 'n' per inserire un nuovo valore.");
-                return define_multiplier(app_type, memory_scale);
-            }
-        }
-    } else if memory_scale != 0 {
+    if memory_scale != 0 {
         return memory_scale;
     }
 
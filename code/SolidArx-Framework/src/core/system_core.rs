@@ -15,21 +15,19 @@
 use crate::config::{
     global_config::{CoreConfig, ApplicationType},
     memory_config::MemoryConfig,
-    database_config::DatabaseType,
+    network_config::DatabaseType,
 };
 
 use crate::core::memory_management::MemoryManager;
-use crate::monitoring::logger;
 use log::{info, warn ,error};
 
 
 /// Sezione di importin per la gestione della connessione al database
-// Importa la funzione per la connessione al database
-use crate::network::connection_management::ConnectionManager;
-// Importa la funzione per lo scrapeing delle tabelle dal codice
-use crate::crud::models::table_scraper::scrape;
-// Importa la funzione per la generazione delle tables nel database
-use crate::crud::models::table_generator::generate_tables;
+// Importa la funzione per la connessione al database, senza `crud` utilizzata solo per verificarla
+#[cfg(not(feature = "crud"))]
+use crate::network::connection_management::{ConnectionManager, DatabaseConnection};
+// Lo schema del database non viene più generato all'avvio: è gestito tramite `arx db migrate`
+// (vedi `crud/models/migrations.rs`)
 
 
 /*DEV: IL data base si inizializza in new() ricevendo nel costruttore le configurazione dal cli del main */
@@ -79,12 +77,15 @@ impl std::error::Error for CoreError {}
 /// # Campi
 /// - config: La configurazione principale del sistema, che specifica il tipo di applicazione.
 /// - memory_manager: Gestore della memoria, che implementa strategie di allocazione in base al tipo di applicazione.
+/// - database_config: La configurazione del database, connesso da `run`.
 pub struct CoreSystem {
     config: CoreConfig,
     memory_manager: MemoryManager,
-    connection_manager: Option<ConnectionManager>,
+    database_config: DatabaseType,
 }
 
+// Utilizzata solo dai moduli abilitati dalle feature
+#[allow(unused_macros)]
 macro_rules! init_module {
     ($module_name:expr, $init_func:expr) => {
         {
@@ -93,7 +94,7 @@ macro_rules! init_module {
                 error!("Errore nell'inizializzazione del modulo {}: {}", $module_name, e);
                 return Err(CoreError::InitializationError(format!("{} initialization failed: {}", $module_name, e)));
             }
-            logger::monitor_module_status($module_name, None);
+            crate::monitoring::logger::monitor_module_status($module_name, None);
             Ok(())
         }
     }
@@ -117,22 +118,17 @@ impl CoreSystem {
             error!("Errore nell'inizializzazione del MemoryManager: {}", e);
             CoreError::InitializationError(e.to_string())
         })?;
-        let connection_manager = match database_config.clone() {
-            DatabaseType::None => {
-                warn!("Configurazione del database non impostata per l'applicazione");
-                None
-            }
-            _ => Some({
-                    ConnectionManager::new(database_config).map_err(|e| {
-                        error!("Errore nell'inizializzazione del ConnectionManager: {}", e);
-                        CoreError::InitializationError(e.to_string())
-                    })?;
-                })
+        if matches!(database_config, DatabaseType::None) {
+            warn!("Configurazione del database non impostata per l'applicazione");
         }
-        
 
         info!("CoreSystem inizializzato con app_type: {:?}",app_type);
-        Ok(CoreSystem { config, memory_manager , Some<connection_manager>})
+        Ok(CoreSystem { config, memory_manager, database_config })
+    }
+
+    /// Restituisce il gestore della memoria inizializzato per il tipo di applicazione.
+    pub fn memory_manager(&self) -> &MemoryManager {
+        &self.memory_manager
     }
 
 
@@ -144,31 +140,52 @@ impl CoreSystem {
     ///
     /// # Nota
     /// - Questa funzione utilizza la configurazione fornita in CoreConfig per determinare
-    /// quali moduli devono essere inizializzati.
-    #[allow(unreachable_code)]
+    ///   quali moduli devono essere inizializzati.
+    /// - Se una delle feature richieste da `ApplicationType::required_features` non è compilata
+    ///   viene restituito `CoreError::UnsupportedOperationError`; `file_management` è facoltativa
+    ///   per AutomationScript e viene inizializzata solo se compilata.
     pub fn run(&self) -> Result<(), CoreError> {
         info!("Esecuzione del CoreSystem...");
 
-        
+        // Le feature richieste dal tipo di applicazione sono le stesse verificate da `arx doctor`
+        let missing = self.config.app_type.missing_features();
+        if !missing.is_empty() {
+            error!("Moduli richiesti per {:?} non compilati: [{}]", self.config.app_type, missing.join(", "));
+            return Err(CoreError::UnsupportedOperationError(format!(
+                "Modules required for {:?} are not compiled: [{}]",
+                self.config.app_type,
+                missing.join(", ")
+            )));
+        }
 
-        match self.connection_manager {
-            Some(cm) => {
+        match &self.database_config {
+            DatabaseType::None => {
+                warn!("Configurazione del database non impostata per l'applicazione");
+            }
+            database => {
                 // Inizializzazione della connessione al database
                 info!("Inizializzazione della connessione al database...");
-                cm.initialize_connection().await?;
 
-                let default_path = "src/crud/models/default";
-                let dev_path = "src/crud/models/dev";   
+                // La connessione viene registrata per i modelli CRUD con `AllocType::Database`:
+                // il client MongoDB resta sul runtime di `mongo_store`, attivo per tutto il processo
+                #[cfg(feature = "crud")]
+                crate::crud::database_store::connect(database.clone()).map_err(|e| {
+                    error!("Errore nella connessione al database: {}", e);
+                    CoreError::InitializationError(format!("Database connection failed: {}", e))
+                })?;
+                #[cfg(not(feature = "crud"))]
+                {
+                    let runtime = tokio::runtime::Runtime::new()
+                        .map_err(|e| CoreError::InitializationError(e.to_string()))?;
+                    runtime.block_on(ConnectionManager::new(database.clone()).initialize_connection()).map_err(|e| {
+                        error!("Errore nella connessione al database: {}", e);
+                        CoreError::InitializationError(format!("Database connection failed: {}", e))
+                    })?;
+                }
 
-                // Generazione delle tabelle nel database
-                info!("Generazione delle tabelle nel database...");
-                // Generazione tabelle default
-                generate_tables(scrape(default_path, cm.clone())?, cm,).await?;
-                // Generazione tabelle dev
-                generate_tables(scrape(dev_path, cm.clone())?, cm,).await?;
-            }
-            None => {
-                warn!("Configurazione del database non impostata per l'applicazione");
+                // Le tabelle non vengono generate ad ogni avvio: le modifiche dello schema
+                // sono revisionabili con `arx db plan` e applicate con `arx db migrate`
+                info!("Schema del database gestito tramite `arx db migrate`");
             }
         }
 
@@ -177,76 +194,45 @@ impl CoreSystem {
 
                 info!("Configurazione per WebApp");
                 #[cfg(feature = "auth")]
-                init_module!("Authentication", || auth::initialize())?;
+                init_module!("Authentication", auth::initialize)?;
                 #[cfg(feature = "crud")]
-                init_module!("CRUD", || crud::initialize())?;
+                init_module!("CRUD", crud::initialize)?;
                 #[cfg(feature = "api")]
-                init_module!("API Layer", || api::initialize())?;
+                init_module!("API Layer", api::initialize)?;
                 #[cfg(feature = "frontend")]
-                init_module!("Frontend", || frontend::initialize())?;
-
-                #[cfg(not(feature = "auth"))]
-                return Err(CoreError::UnsupportedOperationError("Authentication module is required for WebApp".to_string()));
-                #[cfg(not(feature = "crud"))]
-                return Err(CoreError::UnsupportedOperationError("CRUD module is required for WebApp".to_string()));
-                #[cfg(not(feature = "api"))]
-                return Err(CoreError::UnsupportedOperationError("API module is required for WebApp".to_string()));
-                #[cfg(not(feature = "frontend"))]
-                return Err(CoreError::UnsupportedOperationError("Frontend module is required for WebApp".to_string()));
+                init_module!("Frontend", frontend::initialize)?;
                 
             }
 
             ApplicationType::ApiBackend => {
                 info!("Configurazione per API Backend");
                 #[cfg(feature = "auth")]
-                init_module!("Authentication", || auth::initialize())?;
+                init_module!("Authentication", auth::initialize)?;
                 #[cfg(feature = "crud")]
-                init_module!("CRUD", || crud::initialize())?;
+                init_module!("CRUD", crud::initialize)?;
                 #[cfg(feature = "api")]
-                init_module!("API Layer", || api::initialize())?;
-
-
-                #[cfg(not(feature = "auth"))]
-                return Err(CoreError::UnsupportedOperationError("Authentication module is required for API Backend".to_string()));
-                #[cfg(not(feature = "crud"))]
-                return Err(CoreError::UnsupportedOperationError("CRUD module is required for API Backend".to_string()));
-                #[cfg(not(feature = "api"))]
-                return Err(CoreError::UnsupportedOperationError("API module is required for API Backend".to_string()));
+                init_module!("API Layer", api::initialize)?;
 
             }
 
             ApplicationType::DesktopApp => {
                 info!("Configurazione per App Desktop");
                 #[cfg(feature = "auth")]
-                init_module!("Authentication", || auth::initialize())?;
+                init_module!("Authentication", auth::initialize)?;
                 #[cfg(feature = "crud")]
-                init_module!("CRUD", || crud::initialize())?;
+                init_module!("CRUD", crud::initialize)?;
                 #[cfg(feature = "file_management")]
-                init_module!("File Management", || file_management::initialize())?;
+                init_module!("File Management", file_management::initialize)?;
                 #[cfg(feature = "frontend")]
-                init_module!("Frontend", || frontend::initialize())?;
-
-                #[cfg(not(feature = "auth"))]
-                return Err(CoreError::UnsupportedOperationError("Authentication module is required for Desktop App".to_string()));
-                #[cfg(not(feature = "crud"))]
-                return Err(CoreError::UnsupportedOperationError("CRUD module is required for Desktop App".to_string()));
-                #[cfg(not(feature = "file_management"))]
-                return Err(CoreError::UnsupportedOperationError("File Management module is required for Desktop App".to_string()));
-                #[cfg(not(feature = "frontend"))]
-                return Err(CoreError::UnsupportedOperationError("Frontend module is required for Desktop App".to_string()));
+                init_module!("Frontend", frontend::initialize)?;
             }
 
             ApplicationType::AutomationScript => {
                 info!("Configurazione per Automazione e Scripting");
                 #[cfg(feature = "task_automation")]
-                init_module!("Task Automation", || task_automation::initialize())?;
+                init_module!("Task Automation", task_automation::initialize)?;
                 #[cfg(feature = "file_management")]
-                init_module!("File Management", || file_management::initialize())?;
-
-                #[cfg(not(feature = "task_automation"))]
-                return Err(CoreError::UnsupportedOperationError("Task Automation module is required for Automation Script".to_string()));
-                #[cfg(not(feature = "file_management"))]
-                return Err(CoreError::UnsupportedOperationError("File Management module is required for Automation Script".to_string()));
+                init_module!("File Management", file_management::initialize)?;
             }
 
             ApplicationType::EmbeddedSystem => {
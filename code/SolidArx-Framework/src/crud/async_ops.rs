@@ -0,0 +1,193 @@
+//! Modulo per le operazioni CRUD asincrone.
+//!
+//! Le funzioni di `crud_ops.rs` sono sincrone: le scritture su database (Diesel e `mongo_store::block_on`)
+//! bloccano il thread chiamante. I trait di questo modulo (`AsyncCreate`, `AsyncRead`, ...) sono le
+//! controparti asincrone di quelli di `crud_ops.rs`, implementati per tutti i modelli `ArxModel`,
+//! e possono essere attesi dagli handler del layer API senza bloccare i worker di tokio:
+//! ```rust,ignore
+//! use solid_arx_lib::crud::async_ops::{AsyncCreate, AsyncRead};
+//!
+//! let user = User::create(user).await?;
+//! let article = Article::read(article_id).await?;
+//! ```
+//!
+//! ### Esecuzione per storage:
+//! - `AllocType::Database` e `AllocType::Cached`: L'operazione viene eseguita con `tokio::task::spawn_blocking`.
+//! - `AllocType::InMemory`: L'operazione viene eseguita direttamente, il `Mutex` dello store viene
+//!   rilasciato prima di restituire il controllo e non viene mai mantenuto tra due `await`.
+//!
+//! Al di fuori di un runtime tokio o all'interno di una transazione (`unit_of_work::transaction`, legata
+//! al thread) le operazioni vengono sempre eseguite direttamente.
+//! L'autore registrato nel log di audit si imposta con `with_actor`, valido per tutto il task.
+//!
+//! I metodi hanno gli stessi nomi dei trait sincroni: importare i trait di uno solo dei due moduli.
+
+use std::future::Future;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::runtime::Handle;
+
+use crate::crud::audit;
+use crate::crud::crud_ops::{self, AllocType, ArxModel, CrudError};
+use crate::crud::query::{Page, Query};
+use crate::crud::revocation::Revocation;
+use crate::crud::unit_of_work;
+
+tokio::task_local! {
+    /// Autore delle operazioni CRUD del task corrente.
+    static ACTOR: String;
+}
+
+/// Esegue `future` registrando `actor` come autore delle operazioni CRUD asincrone nel log di audit.
+///
+/// Equivalente asincrono di `audit::with_actor`, che vale solo per il thread corrente.
+pub async fn with_actor<F: Future>(actor: &str, future: F) -> F::Output {
+    ACTOR.scope(actor.to_string(), future).await
+}
+
+/// Autore del task corrente, altrimenti quello del thread (`audit::current_actor`).
+fn current_actor() -> String {
+    ACTOR.try_with(String::clone).unwrap_or_else(|_| audit::current_actor())
+}
+
+/// Esegue un'operazione CRUD sincrona senza bloccare i worker del runtime.
+///
+/// # Parametri
+/// - `store`: Storage del record, le operazioni in memoria vengono eseguite direttamente.
+/// - `work`: L'operazione da eseguire, con le funzioni di `crud_ops.rs`.
+pub(crate) async fn run<T: ArxModel, R: Send + 'static>(
+    store: AllocType,
+    work: impl FnOnce() -> Result<R, CrudError> + Send + 'static,
+) -> Result<R, CrudError> {
+    let actor = current_actor();
+    if store == AllocType::InMemory || unit_of_work::is_active() || Handle::try_current().is_err() {
+        return audit::with_actor(&actor, work);
+    }
+    tokio::task::spawn_blocking(move || audit::with_actor(&actor, work))
+        .await
+        .map_err(|e| CrudError::StorageError(format!("Operazione asincrona su {} interrotta: {}", T::TABLE_NAME, e)))?
+}
+
+/// Controparte asincrona di `Create`.
+#[async_trait]
+pub trait AsyncCreate: ArxModel {
+    async fn create(item: Self) -> Result<Self, CrudError>;
+}
+
+/// Controparte asincrona di `Read`.
+#[async_trait]
+pub trait AsyncRead: ArxModel {
+    async fn read(id: Self::Id) -> Result<Self, CrudError>;
+}
+
+/// Controparte asincrona di `Update`.
+#[async_trait]
+pub trait AsyncUpdate: ArxModel {
+    async fn update(item: Self) -> Result<Self, CrudError>;
+}
+
+/// Controparte asincrona di `Delete`.
+#[async_trait]
+pub trait AsyncDelete: ArxModel {
+    async fn delete(id: Self::Id) -> Result<(), CrudError>;
+}
+
+/// Controparte asincrona di `List`.
+#[async_trait]
+pub trait AsyncList: ArxModel {
+    async fn list(query: &Query) -> Result<Page<Self>, CrudError>;
+}
+
+/// Controparte asincrona di `Search`.
+#[async_trait]
+pub trait AsyncSearch: ArxModel {
+    async fn search(query: &Query) -> Result<Page<Self>, CrudError>;
+}
+
+/// Controparte asincrona di `Revoke`.
+#[async_trait]
+pub trait AsyncRevoke: ArxModel {
+    async fn revoke(id: Self::Id, reason: &str, actor: &str) -> Result<Revocation, CrudError>;
+    async fn is_revoked(id: Self::Id) -> Result<bool, CrudError>;
+}
+
+/// Controparte asincrona di `SoftDelete`.
+#[async_trait]
+pub trait AsyncSoftDelete: ArxModel {
+    async fn restore(id: Self::Id) -> Result<Self, CrudError>;
+    async fn list_trashed(query: &Query) -> Result<Page<Self>, CrudError>;
+    async fn purge(retention: Duration) -> Result<usize, CrudError>;
+}
+
+#[async_trait]
+impl<T: ArxModel> AsyncCreate for T {
+    async fn create(item: T) -> Result<T, CrudError> {
+        run::<T, _>(T::default_store(), move || crud_ops::create_model(item)).await
+    }
+}
+
+#[async_trait]
+impl<T: ArxModel> AsyncRead for T {
+    async fn read(id: T::Id) -> Result<T, CrudError> {
+        run::<T, _>(T::default_store(), move || crud_ops::read_model::<T>(id)).await
+    }
+}
+
+#[async_trait]
+impl<T: ArxModel> AsyncUpdate for T {
+    async fn update(item: T) -> Result<T, CrudError> {
+        run::<T, _>(T::default_store(), move || crud_ops::update_model(item)).await
+    }
+}
+
+#[async_trait]
+impl<T: ArxModel> AsyncDelete for T {
+    async fn delete(id: T::Id) -> Result<(), CrudError> {
+        run::<T, _>(T::default_store(), move || crud_ops::delete_model::<T>(id)).await
+    }
+}
+
+#[async_trait]
+impl<T: ArxModel> AsyncList for T {
+    async fn list(query: &Query) -> Result<Page<T>, CrudError> {
+        let query = query.clone();
+        run::<T, _>(T::default_store(), move || crud_ops::list_models::<T>(&query)).await
+    }
+}
+
+#[async_trait]
+impl<T: ArxModel> AsyncSearch for T {
+    async fn search(query: &Query) -> Result<Page<T>, CrudError> {
+        let query = query.clone();
+        run::<T, _>(T::default_store(), move || crud_ops::search_models::<T>(&query)).await
+    }
+}
+
+#[async_trait]
+impl<T: ArxModel> AsyncRevoke for T {
+    async fn revoke(id: T::Id, reason: &str, actor: &str) -> Result<Revocation, CrudError> {
+        let (reason, actor) = (reason.to_string(), actor.to_string());
+        run::<T, _>(T::default_store(), move || crud_ops::revoke_model::<T>(id, &reason, &actor)).await
+    }
+
+    async fn is_revoked(id: T::Id) -> Result<bool, CrudError> {
+        run::<T, _>(T::default_store(), move || crud_ops::is_revoked_model::<T>(id)).await
+    }
+}
+
+#[async_trait]
+impl<T: ArxModel> AsyncSoftDelete for T {
+    async fn restore(id: T::Id) -> Result<T, CrudError> {
+        run::<T, _>(T::default_store(), move || crud_ops::restore_model::<T>(id)).await
+    }
+
+    async fn list_trashed(query: &Query) -> Result<Page<T>, CrudError> {
+        let query = query.clone();
+        run::<T, _>(T::default_store(), move || crud_ops::list_trashed_models::<T>(&query)).await
+    }
+
+    async fn purge(retention: Duration) -> Result<usize, CrudError> {
+        run::<T, _>(T::default_store(), move || crud_ops::purge_trashed_models::<T>(retention)).await
+    }
+}
@@ -0,0 +1,135 @@
+//! Modulo per lo storico delle modifiche (audit trail) dei modelli CRUD.
+//!
+//! I modelli dichiarati con `#[arx(audit)]` registrano ogni `Create`, `Update`, `Delete`, `Revoke`,
+//! ripristino e purge dal cestino come `AuditEntry`, con:
+//! - I valori dei campi prima e dopo l'operazione (JSON) e l'elenco dei campi modificati.
+//!   I campi dichiarati con `#[arx(secret)]` compaiono solo nell'elenco dei campi modificati, mai con il loro valore.
+//! - L'autore dell'operazione (vedi `with_actor`, di default `system`) e la data (UTC).
+//!
+//! Le voci sono salvate nella tabella `audit_entry` (creata da `arx db migrate`), in sola scrittura:
+//! il modello non consente `Update` e `Delete`. La scrittura del record e quella della voce di audit
+//! avvengono nella stessa transazione (vedi `unit_of_work.rs`).
+//!
+//! ### Consultazione:
+//! - `history`: Tutte le voci di un record, dalla più vecchia.
+//! - `state_at`: Ricostruisce il record com'era in un determinato momento.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+
+use crate::crud::crud_ops::{create_model, list_models, ArxModel, CrudError};
+use crate::crud::model_value::ModelValue;
+use crate::crud::models::default::audit_entry::model::{AuditAction, AuditEntry};
+use crate::crud::query::{Query, SortDirection};
+
+/// Autore registrato quando non è impostato con `with_actor`.
+pub const DEFAULT_ACTOR: &str = "system";
+
+thread_local! {
+    /// Autore delle operazioni eseguite sul thread corrente.
+    static ACTOR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Esegue `work` registrando `actor` come autore delle operazioni CRUD nel log di audit.
+///
+/// Utilizzata ad esempio dal layer API con l'utente autenticato della richiesta.
+/// L'autore precedente viene ripristinato al termine.
+pub fn with_actor<R>(actor: &str, work: impl FnOnce() -> R) -> R {
+    let previous = ACTOR.with(|current| current.replace(Some(actor.to_string())));
+    let result = work();
+    ACTOR.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// Autore delle operazioni sul thread corrente.
+pub fn current_actor() -> String {
+    ACTOR.with(|current| current.borrow().clone()).unwrap_or_else(|| DEFAULT_ACTOR.to_string())
+}
+
+/// Valori dei campi del record e oggetto JSON registrato, senza i campi segreti.
+fn image<T: ArxModel>(item: &T) -> (HashMap<&'static str, ModelValue>, String) {
+    let values: HashMap<&'static str, ModelValue> = item.to_values().into_iter().collect();
+    let json = ModelValue::Map(item.public_values().into_iter().map(|(field, value)| (field.to_string(), value)).collect());
+    (values, json.to_json().to_string())
+}
+
+/// Registra un'operazione sul record nel log di audit, se il modello lo richiede.
+pub(crate) fn record<T: ArxModel>(
+    action: AuditAction,
+    id: &T::Id,
+    before: Option<&T>,
+    after: Option<&T>,
+) -> Result<(), CrudError> {
+    if !T::AUDITED {
+        return Ok(());
+    }
+    let before = before.map(image);
+    let after = after.map(image);
+
+    let mut changed_fields: Vec<String> = T::field_names()
+        .into_iter()
+        .filter(|field| {
+            let old = before.as_ref().and_then(|(values, _)| values.get(field));
+            let new = after.as_ref().and_then(|(values, _)| values.get(field));
+            old != new
+        })
+        .map(String::from)
+        .collect();
+    changed_fields.sort();
+
+    let entry = AuditEntry::new(
+        T::TABLE_NAME,
+        id.to_string(),
+        action,
+        &current_actor(),
+        before.map(|(_, json)| json),
+        after.map(|(_, json)| json),
+        changed_fields,
+    );
+    create_model(entry)?;
+    Ok(())
+}
+
+/// Storico delle modifiche di un record, dalla voce più vecchia.
+pub fn history<T: ArxModel>(id: &T::Id) -> Result<Vec<AuditEntry>, CrudError> {
+    let query = Query::new()
+        .eq("model", T::TABLE_NAME.to_string())
+        .eq("record_id", id.to_string())
+        .order_by("id", SortDirection::Asc);
+    Ok(list_models::<AuditEntry>(&query)?.items)
+}
+
+/// Ricostruisce il record com'era al momento indicato (UTC), dall'ultima voce di audit precedente.
+///
+/// I campi segreti, non registrati, vengono ricostruiti con il valore di default del tipo.
+///
+/// # Ritorna
+/// `None` se il record non esisteva ancora o era già stato eliminato in quel momento.
+pub fn state_at<T: ArxModel>(id: &T::Id, at: NaiveDateTime) -> Result<Option<T>, CrudError> {
+    let query = Query::new()
+        .eq("model", T::TABLE_NAME.to_string())
+        .eq("record_id", id.to_string())
+        .lte("timestamp", at)
+        .order_by("id", SortDirection::Desc)
+        .limit(1);
+    let entry = match list_models::<AuditEntry>(&query)?.items.into_iter().next() {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+    let after = match entry.after {
+        Some(after) => after,
+        None => return Ok(None),
+    };
+
+    let json: serde_json::Value = serde_json::from_str(&after)
+        .map_err(|e| CrudError::StorageError(format!("Voce di audit {} non valida: {}", entry.id, e)))?;
+    let values = match ModelValue::from_json(json) {
+        ModelValue::Map(values) => values.into_iter().collect::<HashMap<String, ModelValue>>(),
+        other => return Err(CrudError::StorageError(format!("Voce di audit {} non valida: {}", entry.id, other))),
+    };
+    T::from_values(&values)
+        .map(Some)
+        .map_err(|e| CrudError::StorageError(format!("{}: {}", T::TABLE_NAME, e)))
+}
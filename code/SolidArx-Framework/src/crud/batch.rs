@@ -0,0 +1,188 @@
+//! Modulo per l'inserimento a blocchi (batch) dei modelli CRUD ad alto volume.
+//!
+//! `Create::create` scrive un record per chiamata: per i picchi di `SensorData`, `LogEvent`, `RequestLog`
+//! o `ExecutionLog` questo modulo raggruppa i record in blocchi di `BatchConfig::batch_size` elementi:
+//! - `insert`: Inserisce i record di un iteratore.
+//! - `ingest`: Inserisce i record ricevuti da un canale `tokio::sync::mpsc`, man mano che arrivano,
+//!   eseguendo le scritture su database con `spawn_blocking` (vedi `async_ops.rs`).
+//!
+//! ### Scrittura dei blocchi:
+//! - `AllocType::InMemory`: Un solo lock sullo store del modello per blocco.
+//! - `AllocType::Database`: Un'unica transazione per blocco, con `INSERT` su più righe su PostgreSQL,
+//!   la stessa istruzione parametrizzata per ogni riga su SQLite e `insert_many` su MongoDB
+//!   (vedi `database_store::insert_batch`).
+//! - `AllocType::Cached`: Ogni record viene scritto tramite la cache del modello.
+//!
+//! Ogni record viene preparato come in `Create` (ID, validazione, chiavi esterne, audit).
+//! I record non validi o in conflitto non interrompono l'inserimento: vengono restituiti in
+//! `BatchReport::failures` con la loro posizione, insieme alle metriche di throughput.
+//!
+//! ### Esempio:
+//! ```rust,ignore
+//! let report = batch::insert::<SensorData>(readings, &BatchConfig::new().batch_size(1000))?;
+//! info!("{} letture inserite ({:.0} record/s)", report.inserted, report.throughput());
+//! ```
+
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use tokio::sync::mpsc::Receiver;
+
+use crate::crud::async_ops;
+use crate::crud::crud_ops::{self, ArxModel, CrudError};
+
+/// Numero di record per blocco di default.
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// Configurazione dell'inserimento a blocchi.
+///
+/// # Campi
+/// - `batch_size`: Numero massimo di record scritti in un'unica transazione.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchConfig {
+    pub batch_size: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        BatchConfig { batch_size: DEFAULT_BATCH_SIZE }
+    }
+}
+
+impl BatchConfig {
+    /// Crea la configurazione di default (`DEFAULT_BATCH_SIZE` record per blocco).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Imposta il numero di record per blocco (almeno 1).
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+/// Record non inserito.
+///
+/// # Campi
+/// - `index`: Posizione del record tra quelli ricevuti, a partire da 0.
+/// - `error`: Motivo dell'errore (es. `ValidationFailed`, `ReferenceViolation`, `Conflict`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchFailure {
+    pub index: usize,
+    pub error: CrudError,
+}
+
+/// Esito di un inserimento a blocchi.
+///
+/// # Campi
+/// - `inserted`: Numero di record inseriti.
+/// - `failures`: Record non inseriti, in ordine di posizione.
+/// - `batches`: Numero di blocchi scritti.
+/// - `elapsed`: Durata complessiva delle scritture.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchReport {
+    pub inserted: usize,
+    pub failures: Vec<BatchFailure>,
+    pub batches: usize,
+    pub elapsed: Duration,
+}
+
+impl BatchReport {
+    /// Numero di record ricevuti.
+    pub fn total(&self) -> usize {
+        self.inserted + self.failures.len()
+    }
+
+    /// Indica se tutti i record sono stati inseriti.
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Record inseriti al secondo.
+    pub fn throughput(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.inserted as f64 / secs,
+            _ => 0.0,
+        }
+    }
+
+    /// Aggiunge al report l'esito di un blocco iniziato alla posizione `offset`.
+    fn record<T>(&mut self, offset: usize, outcomes: Vec<Result<T, CrudError>>, elapsed: Duration) {
+        for (position, outcome) in outcomes.into_iter().enumerate() {
+            match outcome {
+                Ok(_) => self.inserted += 1,
+                Err(error) => self.failures.push(BatchFailure { index: offset + position, error }),
+            }
+        }
+        self.batches += 1;
+        self.elapsed += elapsed;
+    }
+
+    /// Registra nel log il riepilogo dell'inserimento.
+    fn log<T: ArxModel>(&self) {
+        info!(
+            "Batch {}: {} record inseriti su {} in {} blocchi ({:.0} record/s)",
+            T::TABLE_NAME,
+            self.inserted,
+            self.total(),
+            self.batches,
+            self.throughput()
+        );
+        if !self.is_complete() {
+            warn!("Batch {}: {} record non inseriti", T::TABLE_NAME, self.failures.len());
+        }
+    }
+}
+
+/// Scrive un blocco di record, restituendo l'esito di ognuno e la durata della scrittura.
+fn write_chunk<T: ArxModel>(chunk: Vec<T>) -> Result<(Vec<Result<T, CrudError>>, Duration), CrudError> {
+    let started = Instant::now();
+    let outcomes = crud_ops::create_models(chunk)?;
+    Ok((outcomes, started.elapsed()))
+}
+
+/// Inserisce i record dell'iteratore in blocchi di `config.batch_size` elementi.
+///
+/// # Ritorna
+/// Il `BatchReport` con i record non inseriti, oppure un `CrudError` se l'operazione `create`
+/// non è consentita o lo storage non è disponibile (i blocchi già scritti restano inseriti).
+pub fn insert<T: ArxModel>(items: impl IntoIterator<Item = T>, config: &BatchConfig) -> Result<BatchReport, CrudError> {
+    let mut report = BatchReport::default();
+    let mut items = items.into_iter().peekable();
+    while items.peek().is_some() {
+        let chunk: Vec<T> = items.by_ref().take(config.batch_size.max(1)).collect();
+        let offset = report.total();
+        let (outcomes, elapsed) = write_chunk(chunk)?;
+        report.record(offset, outcomes, elapsed);
+    }
+    report.log::<T>();
+    Ok(report)
+}
+
+/// Inserisce i record ricevuti dal canale fino alla sua chiusura.
+///
+/// Un blocco viene scritto appena sono disponibili i record già in coda, fino a `config.batch_size`,
+/// senza attendere che il blocco sia completo.
+///
+/// # Esempio
+/// ```rust,ignore
+/// let (sender, mut receiver) = tokio::sync::mpsc::channel(4096);
+/// tokio::spawn(async move { batch::ingest::<SensorData>(&mut receiver, &BatchConfig::new()).await });
+/// sender.send(reading).await?;
+/// ```
+pub async fn ingest<T: ArxModel>(receiver: &mut Receiver<T>, config: &BatchConfig) -> Result<BatchReport, CrudError> {
+    let mut report = BatchReport::default();
+    let batch_size = config.batch_size.max(1);
+    loop {
+        let mut chunk = Vec::with_capacity(batch_size);
+        if receiver.recv_many(&mut chunk, batch_size).await == 0 {
+            break;
+        }
+        let offset = report.total();
+        let (outcomes, elapsed) = async_ops::run::<T, _>(T::default_store(), move || write_chunk(chunk)).await?;
+        report.record(offset, outcomes, elapsed);
+    }
+    report.log::<T>();
+    Ok(report)
+}
@@ -1,64 +1,39 @@
-use log::{info};
-
-use cfg_if::cfg_if;
-cfg_if! {
-    if #[cfg(feature = "webapp")] {
-        use crate::crud::models::default::{
-            user::model::User, article::model::Article, comment::model::Comment, category::model::Category,
-            tag::model::Tag, file::model::File, page::model::Page,
-        };
-    } else if #[cfg(feature = "api_backend")] {
-        use crate::crud::models::default::{
-            user::model::User, api_key::model::ApiKey, token::model::Token, request_log::model::RequestLog,
-            endpoint::model::Endpoint, permission::model::Permission, rate_limit_rule::model::RateLimitRule,
-        };
-    } else if #[cfg(feature = "desktop")] {
-        use crate::crud::models::default::{
-            user::model::User, settings::model::Settings, document::model::Document, file::model::File,
-            preferences::model::Preferences, task::model::Task, project::model::Project,
-        };
-    } else if #[cfg(feature = "automation")] {
-        use crate::crud::models::default::{
-            script::model::Script, task::model::Task, execution_log::model::ExecutionLog,
-            schedule::model::Schedule, configuration::model::Configuration, job::model::Job,
-            macro_script::model::MacroScript,
-        };
-    } else if #[cfg(feature = "embedded")] {
-        use crate::crud::models::default::{
-            device::model::Device, sensor_data::model::SensorData, configuration::model::Configuration,
-            firmware_version::model::FirmwareVersion, log_event::model::LogEvent, command::model::Command, task::model::Task,
-        };
-    }
-}
-
-use crate::core::memory_management::{AllocationStrategy, MemoryManager};
-use crate::config::memory_config::MemoryConfig;
-
-//DATABASE
-//use diesel::prelude::*;
-//use crate::::connection; 
-
-
-
-
-// Importazione variabili statiche per mantenere i modelli in memoria
-use crate::core::memory_management::{
-    TASKS_IN_MEMORY, 
-    CONFIGURATIONS_IN_MEMORY, 
-    DEVICES_IN_MEMORY, 
-    JOBS_IN_MEMORY, 
-    MACROS_IN_MEMORY, 
-    SENSOR_DATA_IN_MEMORY, 
-    LOG_EVENTS_IN_MEMORY, 
-    COMMANDS_IN_MEMORY
-};
-
-#[derive(Debug,Clone)]
+use chrono::{NaiveDateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::crud::audit;
+use crate::crud::database_store;
+use crate::crud::id_generator::{self, IdStrategy, ModelKey};
+use crate::crud::model_cache::{self, CacheConfig, ModelCache};
+use crate::crud::model_value::ModelValue;
+use crate::crud::models::default::audit_entry::model::AuditAction;
+use crate::crud::query::{FilterOp, Page, Query};
+use crate::crud::relations::Relation;
+use crate::crud::revocation::{self, Revocation};
+use crate::crud::unit_of_work;
+use crate::crud::validation::FieldError;
+use crate::crud::watch;
+
+// I modelli implementano i trait CRUD tramite `#[derive(ArxModel)]` (vedi `arx_derive`):
+// le implementazioni generate delegano alle funzioni generiche di questo modulo,
+// che scelgono lo storage in base all'`AllocType` del modello (memoria, `database_store.rs` o `model_cache.rs`).
+// Le controparti asincrone dei trait, per gli handler del layer API, sono in `async_ops.rs`.
+pub use arx_derive::ArxModel;
+
+/// Storage di un modello, serializzato con il nome della variante (es. `"InMemory"`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AllocType {
     InMemory,
     Database,
+    /// Database con una cache in memoria davanti allo storage (vedi `model_cache.rs`).
+    Cached,
 }
-#[derive(Debug,Clone)]
+/// Operazioni CRUD abilitate per un modello, serializzate come oggetto JSON (`{"create": true, ...}`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CrudOperations {
     pub create: bool,
     pub read: bool,
@@ -69,219 +44,884 @@ pub struct CrudOperations {
     pub revoke: bool,
 }
 
+/// Singola operazione CRUD, utilizzata per i controlli dei permessi e negli errori.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrudOperation {
+    Create,
+    Read,
+    Update,
+    Delete,
+    List,
+    Search,
+    Revoke,
+}
+
+impl CrudOperation {
+    /// Tutte le operazioni nell'ordine dei campi di `CrudOperations`.
+    pub const ALL: [CrudOperation; 7] = [
+        CrudOperation::Create,
+        CrudOperation::Read,
+        CrudOperation::Update,
+        CrudOperation::Delete,
+        CrudOperation::List,
+        CrudOperation::Search,
+        CrudOperation::Revoke,
+    ];
+
+    /// Nome dell'operazione, come in `#[arx(ops = "...")]` e `arx model new --ops`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CrudOperation::Create => "create",
+            CrudOperation::Read => "read",
+            CrudOperation::Update => "update",
+            CrudOperation::Delete => "delete",
+            CrudOperation::List => "list",
+            CrudOperation::Search => "search",
+            CrudOperation::Revoke => "revoke",
+        }
+    }
+}
+
+impl std::fmt::Display for CrudOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl CrudOperations {
+    /// Verifica se l'operazione è abilitata.
+    pub fn allows(&self, operation: CrudOperation) -> bool {
+        match operation {
+            CrudOperation::Create => self.create,
+            CrudOperation::Read => self.read,
+            CrudOperation::Update => self.update,
+            CrudOperation::Delete => self.delete,
+            CrudOperation::List => self.list,
+            CrudOperation::Search => self.search,
+            CrudOperation::Revoke => self.revoke,
+        }
+    }
+
+    /// Elenco delle operazioni abilitate, nell'ordine di `CrudOperation::ALL`.
+    pub fn enabled(&self) -> Vec<CrudOperation> {
+        CrudOperation::ALL.iter().copied().filter(|op| self.allows(*op)).collect()
+    }
+}
+
+/// Errori restituiti dalle operazioni CRUD.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CrudError {
+    /// L'operazione è disabilitata nelle `CrudOperations` del modello.
+    OperationNotPermitted { model: &'static str, operation: CrudOperation },
+    /// Nessun record con l'ID indicato.
+    NotFound { model: &'static str, id: String },
+    /// Esiste già un record con l'ID indicato.
+    Conflict { model: &'static str, id: String },
+    /// Il record è stato modificato da un'altra operazione dopo la lettura (vedi `ArxModel::VERSIONED`).
+    VersionConflict { model: &'static str, id: String, current: u64 },
+    /// Il record è stato revocato e non viene restituito dalle letture.
+    Revoked { model: &'static str, id: String },
+    /// Il record collegato non esiste o l'eliminazione è impedita da record dipendenti (vedi `relations.rs`).
+    ReferenceViolation { model: &'static str, id: String, related: &'static str },
+    /// Uno o più campi non soddisfano le regole di validazione del modello (vedi `validation.rs`).
+    ValidationFailed { model: &'static str, errors: Vec<FieldError> },
+    /// Query non valida per il modello (es. campo inesistente).
+    InvalidQuery(String),
+    /// Errore dello storage (lock, database, conversione dei valori).
+    StorageError(String),
+}
+
+impl std::fmt::Display for CrudError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CrudError::OperationNotPermitted { model, operation } => {
+                write!(f, "OperationNotPermitted: operazione '{}' non consentita per il modello {}", operation, model)
+            }
+            CrudError::NotFound { model, id } => write!(f, "NotFound: {} con ID {} non trovato", model, id),
+            CrudError::Conflict { model, id } => write!(f, "Conflict: {} con ID {} già esistente", model, id),
+            CrudError::VersionConflict { model, id, current } => write!(
+                f,
+                "VersionConflict: {} con ID {} modificato da un'altra operazione, versione corrente {}",
+                model, id, current
+            ),
+            CrudError::Revoked { model, id } => write!(f, "Revoked: {} con ID {} revocato", model, id),
+            CrudError::ReferenceViolation { model, id, related } => {
+                write!(f, "ReferenceViolation: {} con ID {} viola la relazione con {}", model, id, related)
+            }
+            CrudError::ValidationFailed { model, errors } => {
+                let errors: Vec<String> = errors.iter().map(FieldError::to_string).collect();
+                write!(f, "ValidationFailed: {} non valido, {}", model, errors.join("; "))
+            }
+            CrudError::InvalidQuery(msg) => write!(f, "InvalidQuery: {}", msg),
+            CrudError::StorageError(msg) => write!(f, "StorageError: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CrudError {}
+
+impl CrudError {
+    /// Errore `NotFound` per un record del modello.
+    pub fn not_found<T: ArxModel>(id: &T::Id) -> Self {
+        CrudError::NotFound { model: T::TABLE_NAME, id: id.to_string() }
+    }
+
+    /// Errore `Conflict` per un record del modello.
+    pub fn conflict<T: ArxModel>(id: &T::Id) -> Self {
+        CrudError::Conflict { model: T::TABLE_NAME, id: id.to_string() }
+    }
+
+    /// Errore `VersionConflict` per un record del modello, con la versione memorizzata.
+    pub fn version_conflict<T: ArxModel>(id: &T::Id, current: u64) -> Self {
+        CrudError::VersionConflict { model: T::TABLE_NAME, id: id.to_string(), current }
+    }
+
+    /// Errore `Revoked` per un record del modello.
+    pub fn revoked<T: ArxModel>(id: &T::Id) -> Self {
+        CrudError::Revoked { model: T::TABLE_NAME, id: id.to_string() }
+    }
+
+    /// Errore `ReferenceViolation` per un record del modello e la tabella collegata.
+    pub fn reference_violation<T: ArxModel>(id: &T::Id, related: &'static str) -> Self {
+        CrudError::ReferenceViolation { model: T::TABLE_NAME, id: id.to_string(), related }
+    }
+}
+
+/// Politica applicata a un modello dipendente prima della rimozione del record con l'ID indicato.
+pub type DependentPolicy<Id> = fn(&Id) -> Result<(), CrudError>;
+
+/// Trait implementato da `#[derive(ArxModel)]` che descrive un modello CRUD.
+///
+/// Fornisce le informazioni necessarie alle funzioni generiche di questo modulo per gestire
+/// il modello senza conoscerne il tipo concreto: tabella, storage, operazioni dichiarate,
+/// store in memoria dedicato e conversione dei campi in `ModelValue`.
+pub trait ArxModel: Clone + Send + Sized + 'static {
+    /// Nome della tabella (o collezione) del modello.
+    const TABLE_NAME: &'static str;
+    /// Strategia di generazione dell'ID, dichiarata con `#[arx(id = "...")]`.
+    const ID_STRATEGY: IdStrategy;
+    /// Tipo della chiave primaria, dal tipo del campo `id` (`u32`, `u64` o `Uuid`).
+    type Id: ModelKey;
+
+    /// ID del record.
+    fn id(&self) -> Self::Id;
+    /// Imposta l'ID del record.
+    fn set_id(&mut self, id: Self::Id);
+    /// Storage del record, letto dal campo `store` del modello.
+    ///
+    /// Le operazioni CRUD utilizzano sempre `default_store`, anche se il campo è stato modificato.
+    fn store(&self) -> AllocType;
+    /// Storage di default del modello, dichiarato con `#[arx(store = "...")]`.
+    fn default_store() -> AllocType;
+    /// Operazioni CRUD dichiarate con `#[arx(ops = "...")]`.
+    fn declared_operations() -> CrudOperations;
+    /// Store in memoria dedicato al modello, utilizzato con `AllocType::InMemory`.
+    fn memory_store() -> &'static Mutex<HashMap<Self::Id, Self>>;
+    /// Configurazione della cache, dichiarata con `#[arx(cache = "...", capacity = ..., ttl = ...)]`.
+    fn cache_config() -> CacheConfig;
+    /// Cache dedicata al modello, utilizzata con `AllocType::Cached`.
+    fn model_cache() -> &'static Mutex<ModelCache<Self>>;
+    /// Nomi dei campi persistiti, incluso `id`.
+    fn field_names() -> Vec<&'static str>;
+    /// Valori dei campi persistiti, incluso `id`.
+    fn to_values(&self) -> Vec<(&'static str, ModelValue)>;
+    /// Ricostruisce il modello dai valori dei campi persistiti.
+    fn from_values(values: &HashMap<String, ModelValue>) -> Result<Self, String>;
+    /// Campi `String` e `Option<String>` della ricerca testuale (`Query::text`), esclusi i campi segreti.
+    fn text_fields() -> Vec<&'static str>;
+
+    /// Indica se il modello ha un campo `revocation: Option<Revocation>`.
+    const REVOCABLE: bool = false;
+    /// Revoca del record, `None` se non revocato o se il modello non è revocabile.
+    fn revocation(&self) -> Option<Revocation> {
+        None
+    }
+    /// Imposta la revoca del record, ignorata se il modello non è revocabile.
+    fn set_revocation(&mut self, _revocation: Option<Revocation>) {}
+
+    /// Indica se il modello ha un campo `version: u64` per il controllo ottimistico della concorrenza.
+    ///
+    /// `Update` riesce solo se la versione del record è quella memorizzata, che viene incrementata,
+    /// altrimenti restituisce `CrudError::VersionConflict`.
+    const VERSIONED: bool = false;
+    /// Versione del record, 0 se il modello non ha versione.
+    fn version(&self) -> u64 {
+        0
+    }
+    /// Imposta la versione del record, ignorata se il modello non ha versione.
+    fn set_version(&mut self, _version: u64) {}
+
+    /// Indica se il modello ha un campo `deleted_at: Option<NaiveDateTime>` per l'eliminazione nel cestino.
+    ///
+    /// `Delete` imposta `deleted_at` invece di rimuovere il record, escluso da `Read`, `List` e `Search`
+    /// fino a `SoftDelete::restore` o alla rimozione definitiva con `SoftDelete::purge`.
+    const SOFT_DELETE: bool = false;
+    /// Data di eliminazione del record, `None` se non è nel cestino o il modello non lo supporta.
+    fn deleted_at(&self) -> Option<NaiveDateTime> {
+        None
+    }
+    /// Imposta la data di eliminazione del record, ignorata se il modello non supporta il cestino.
+    fn set_deleted_at(&mut self, _deleted_at: Option<NaiveDateTime>) {}
+
+    /// Indica se le modifiche del modello vengono registrate nel log di audit, dichiarato con `#[arx(audit)]`.
+    ///
+    /// La scrittura del record e la voce di audit avvengono nella stessa transazione (vedi `audit.rs`).
+    const AUDITED: bool = false;
+
+    /// Campi dichiarati con `#[arx(secret)]`, esclusi da esportazioni, log di audit ed eventi di watch.
+    fn secret_fields() -> Vec<&'static str> {
+        Vec::new()
+    }
+    /// Valori dei campi persistiti senza i campi segreti.
+    fn public_values(&self) -> Vec<(&'static str, ModelValue)> {
+        let secret = Self::secret_fields();
+        self.to_values().into_iter().filter(|(field, _)| !secret.contains(field)).collect()
+    }
+    /// Sostituisce i campi segreti con il valore di default del tipo.
+    fn redact(&mut self) {}
+
+    /// Verifica le regole dichiarate con `#[arx(validate(...))]`, prima di `Create` e `Update`.
+    ///
+    /// # Ritorna
+    /// Tutti i campi non validi, non solo il primo.
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Ok(())
+    }
+
+    /// Relazioni `belongs_to` dichiarate sui campi chiave esterna del modello.
+    fn relations() -> Vec<Relation> {
+        Vec::new()
+    }
+    /// Verifica che esistano i record collegati dalle chiavi esterne, prima di `Create` e `Update`.
+    fn check_references(&self) -> Result<(), CrudError> {
+        Ok(())
+    }
+    /// Politiche di eliminazione dei modelli dipendenti dichiarati con `has_many`,
+    /// applicate prima della rimozione definitiva del record.
+    fn dependents() -> Vec<DependentPolicy<Self::Id>> {
+        Vec::new()
+    }
+}
+
 /// Trait che definisce l'operazione di creazione per un generico tipo `T`.
-/// 
+///
 /// Questo trait implementa la logica per creare un nuovo elemento di tipo `T`,
 /// utilizzando la memoria o il database a seconda della configurazione di allocazione.
+/// L'elemento restituito contiene l'ID assegnato secondo l'`IdStrategy` del modello.
 pub trait Create<T> {
-    fn create(item: T) -> Result<T, String>;
+    fn create(item: T) -> Result<T, CrudError>;
 }
 
 /// Trait che definisce l'operazione di lettura per un generico tipo `T`.
-/// 
+///
 /// Permette di leggere un elemento dal database o dalla memoria in base al suo ID.
-pub trait Read<T> {
-    fn read(id: u32) -> Result<T, String>;
+pub trait Read<T: ArxModel> {
+    fn read(id: T::Id) -> Result<T, CrudError>;
 }
 
 /// Trait che definisce l'operazione di aggiornamento per un generico tipo `T`.
-/// 
+///
 /// Aggiorna un elemento esistente nel database o in memoria.
 pub trait Update<T> {
-    fn update(item: T) -> Result<T, String>;
+    fn update(item: T) -> Result<T, CrudError>;
 }
 
 /// Trait che definisce l'operazione di eliminazione.
-/// 
+///
 /// Elimina un elemento dal database o dalla memoria in base al suo ID.
-pub trait Delete {
-    fn delete(id: u32) -> Result<(), String>;
+pub trait Delete: ArxModel {
+    fn delete(id: Self::Id) -> Result<(), CrudError>;
 }
 
 /// Trait che definisce l'operazione di elencazione per un generico tipo `T`.
-/// 
-/// Elenca tutti gli elementi presenti in memoria o nel database.
+///
+/// Elenca gli elementi presenti in memoria o nel database, con ordinamento e paginazione
+/// descritti da una `Query` (`Query::new()` restituisce tutti gli elementi).
 pub trait List<T> {
-    fn list() -> Vec<T>;
+    fn list(query: &Query) -> Result<Page<T>, CrudError>;
 }
 
 /// Trait che definisce l'operazione di ricerca per un generico tipo `T`.
-/// 
-/// Effettua una ricerca tra gli elementi in base a una query specifica.
+///
+/// Effettua una ricerca tra gli elementi in base ai filtri di una `Query`.
 pub trait Search<T> {
-    fn search(query: &str) -> Vec<T>;
+    fn search(query: &Query) -> Result<Page<T>, CrudError>;
 }
 
 /// Trait che definisce l'operazione di revoca.
-/// 
-/// Revoca un elemento specifico in base al suo ID, come ad esempio un token o un permesso.
-pub trait Revoke {
-    fn revoke(id: u32) -> Result<(), String>;
-}
-
-/// Macro per implementare le operazioni CRUD comuni.
-/// 
-/// Questa macro implementa automaticamente i metodi CRUD (Create, Read, Update, Delete)
-/// per un tipo specificato. Ogni modello gestisce sia l'allocazione in memoria che
-/// l'inserimento nel database, in base alle preferenze definite per ciascun modello.
-macro_rules! impl_crud_ops {
-    ($model:ty) => {
-        impl Create<$model> for $model {
-            /// Crea una nuova istanza del modello specificato.
-            ///
-            /// La funzione `create` permette di creare un nuovo record per il modello specificato.
-            /// La memoria allocata per il nuovo record viene determinata in base alla configurazione
-            /// del tipo di modello e della strategia di allocazione, e utilizza il valore di `memory_scale`
-            /// per calcolare la quantità di memoria da allocare.
-            ///
-            /// # Parametri
-            ///
-            /// - `item`: Un'istanza del modello che si desidera creare.
-            /// - `memory_scale`: Un valore `u8` che rappresenta il moltiplicatore di memoria configurato.
-            ///   Questo valore viene utilizzato per determinare quanta memoria deve essere allocata
-            ///   per il modello creato. Più alto è il valore, più grande sarà l'allocazione.
-            ///
-            /// # Restituisce
-            ///
-            /// - `Result<$model, String>`: Restituisce un `Ok($model)` se la creazione è avvenuta con successo,
-            ///   oppure un `Err(String)` con un messaggio di errore nel caso in cui si verifichi un problema.
-            fn create(item: $model, memory_scale: u8) -> Result<$model, String> {
-                match std::any::type_name::<$model>() {
-                    // Task temporanei, quindi la memoria standard va bene per velocità e semplicità
-                    "modules::default::task_model::Task" => {
-                        info!("Allocazione in memoria per Task");
-                
-                        // Determina la dimensione da allocare. Supponiamo di voler allocare 1024 byte.
-                        let size: usize = (1024 * memory_scale).into();
-                
-                        // Allocazione della memoria per il Task
-                        let task_memory = MemoryManager::allocate(,Some(AllocationStrategy::Standard), size);
-                
-                        // Creazione del Task con i dati ricevuti
-                        let mut task = Task::new(
-                            item.id,
-                            item.description, 
-                            #[cfg(feature = "automation")]
-                            item.schedule,
-                            #[cfg(feature = "desktop")]
-                            item.completed,
-                            #[cfg(feature = "embedded")]
-                            item.device_id,
-                            task_memory,
-                        );
-                
-                        // Persistenza del Task
-                        match task.store {
-                            AllocType::InMemory => {
-                                // Memorizza il Task in memoria
-                                let mut tasks = TASKS_IN_MEMORY.lock().map_err(|e| format!("Errore di lock sul mutex: {}", e))?;
-                                tasks.insert(task.id, task.clone());
-                            }
-                            AllocType::Database => {
-                                // Memorizza il Task nel database
-                                
-                            }
-                        }
-                
-                        info!("Allocata memoria di {} byte per il Task con ID: {}", size, task.id);
-                        return Ok(task);
-                    }
+///
+/// Revoca un elemento specifico in base al suo ID, come ad esempio un token o un permesso,
+/// registrando data, motivo e autore. Gli elementi revocati non vengono più restituiti dalle letture.
+pub trait Revoke: ArxModel {
+    fn revoke(id: Self::Id, reason: &str, actor: &str) -> Result<Revocation, CrudError>;
+    fn is_revoked(id: Self::Id) -> Result<bool, CrudError>;
+}
+
+/// Trait che definisce il cestino dei modelli con `ArxModel::SOFT_DELETE`.
+///
+/// I record eliminati restano nello storage con `deleted_at` impostato e possono essere ripristinati,
+/// elencati o rimossi definitivamente dopo un periodo di conservazione. Le operazioni richiedono
+/// i permessi `delete` (`restore`, `purge`) e `list` (`list_trashed`).
+pub trait SoftDelete: ArxModel {
+    fn restore(id: Self::Id) -> Result<Self, CrudError>;
+    fn list_trashed(query: &Query) -> Result<Page<Self>, CrudError>;
+    fn purge(retention: Duration) -> Result<usize, CrudError>;
+}
 
-                    // Implementazione per altri modelli
+/// Restituisce le operazioni CRUD dichiarate dal modello.
+///
+/// Utilizzata dal layer API per esporre solo le route consentite.
+pub fn operations<T: ArxModel>() -> CrudOperations {
+    T::declared_operations()
+}
 
-                    _ => {
-                        info!("Allocazione in memoria di Default per modello non gestito specificamente");
-                        let size = (1024 * memory_scale).into(); // Size per modelli non gestiti dedicati 1024 byte per range applicativo di media 
-                        MemoryManager::allocate(,None, size)?;
-                        return Ok(item);
-                    }
-                }
-            }
+/// Verifica se l'operazione è consentita per il modello.
+pub fn is_permitted<T: ArxModel>(operation: CrudOperation) -> bool {
+    T::declared_operations().allows(operation)
+}
+
+/// Restituisce un errore `OperationNotPermitted` se l'operazione è disabilitata per il modello.
+fn ensure_permitted<T: ArxModel>(operation: CrudOperation) -> Result<(), CrudError> {
+    if is_permitted::<T>(operation) {
+        Ok(())
+    } else {
+        warn!("Operazione '{}' non consentita per {}", operation, T::TABLE_NAME);
+        Err(CrudError::OperationNotPermitted { model: T::TABLE_NAME, operation })
+    }
+}
+
+/// Restituisce `CrudError::ValidationFailed` con tutti i campi non validi del record.
+fn ensure_valid<T: ArxModel>(item: &T) -> Result<(), CrudError> {
+    item.validate().map_err(|errors| CrudError::ValidationFailed { model: T::TABLE_NAME, errors })
+}
+
+/// Acquisisce il lock sullo store in memoria del modello.
+fn lock_memory<T: ArxModel>() -> Result<MutexGuard<'static, HashMap<T::Id, T>>, CrudError> {
+    T::memory_store().lock().map_err(|e| CrudError::StorageError(format!("Errore di lock sul mutex: {}", e)))
+}
+
+/// Ripristina il record in memoria al valore precedente se la transazione attiva viene annullata.
+fn restore_on_rollback<T: ArxModel>(id: T::Id, previous: Option<T>) {
+    unit_of_work::record_undo(move || {
+        if let Ok(mut records) = lock_memory::<T>() {
+            match previous {
+                Some(item) => records.insert(id.clone(), item),
+                None => records.remove(&id),
+            };
         }
+        revocation::invalidate::<T>(&id);
+    });
+}
 
-        impl Read<$model> for $model {
-            /// Legge un elemento di tipo `$model` in base al suo ID.
-            ///
-            /// La funzione `read` cerca prima l'elemento nella memoria (InMemory), se applicabile,
-            /// e se non lo trova, tenta di recuperarlo dal database.
-            ///
-            /// # Parametri
-            /// - `id`: L'ID dell'elemento che si desidera leggere.
-            ///
-            /// # Ritorna
-            /// - `Ok($model)` se l'elemento è stato trovato in memoria o nel database.
-            /// - `Err(String)` se l'elemento non è stato trovato né in memoria né nel database.
-            fn read(id: u32) -> Result<$model, String> {
-                match std::any::type_name::<$model>() {
-                    // Task (InMemory)
-                    "modules::default::task_model::Task" => {
-                        let tasks = TASKS_IN_MEMORY.lock().map_err(|e| format!("Errore di lock sul mutex: {}", e))?;
-                        if let Some(task) = tasks.get(&id) {
-                            return Ok(task.clone());
-                        } else {
-                            return Err(format!("Task con ID {} non trovato", id));
-                        }
-                    }
+/// Esegue `work` in una transazione per i modelli con audit, così il record e la sua voce di audit
+/// vengono scritti insieme.
+fn audited<T: ArxModel, R>(work: impl FnOnce() -> Result<R, CrudError>) -> Result<R, CrudError> {
+    match T::AUDITED {
+        true => unit_of_work::transaction(work),
+        false => work(),
+    }
+}
 
-                    // Implementazione per altri modelli
+/// Stato del record prima di una modifica, letto solo per i modelli con audit.
+fn audit_before<T: ArxModel>(id: &T::Id) -> Result<Option<T>, CrudError> {
+    match T::AUDITED {
+        true => match find_model::<T>(id) {
+            Ok(item) => Ok(Some(item)),
+            Err(CrudError::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        },
+        false => Ok(None),
+    }
+}
 
-                    _ => Err(format!("Elemento con ID {} non trovato", id)),
+/// Registra la modifica nel log di audit e la notifica agli osservatori del modello (vedi `watch.rs`).
+fn record_change<T: ArxModel>(
+    action: AuditAction,
+    id: &T::Id,
+    before: Option<&T>,
+    after: Option<&T>,
+) -> Result<(), CrudError> {
+    audit::record::<T>(action, id, before, after)?;
+    watch::publish::<T>(action.into(), id, after.or(before));
+    Ok(())
+}
+
+/// Crea un nuovo record del modello nello storage indicato dal suo `AllocType`.
+///
+/// # Parametri
+/// - `item`: Il record da creare. Se l'ID non è impostato viene generato secondo l'`IdStrategy` del modello.
+///
+/// # Ritorna
+/// Il record creato con l'ID assegnato, `CrudError::Conflict` se l'ID esiste già
+/// oppure un altro `CrudError`, anche se l'operazione `create` non è consentita.
+pub fn create_model<T: ArxModel>(mut item: T) -> Result<T, CrudError> {
+    ensure_permitted::<T>(CrudOperation::Create)?;
+    id_generator::assign(&mut item)?;
+    if T::VERSIONED && item.version() == 0 {
+        item.set_version(1);
+    }
+    ensure_valid(&item)?;
+    item.check_references()?;
+    audited::<T, _>(|| {
+        let created = insert_model(item)?;
+        record_change::<T>(AuditAction::Create, &created.id(), None, Some(&created))?;
+        Ok(created)
+    })
+}
+
+/// Crea più record del modello, utilizzata da `batch.rs`.
+///
+/// Ogni record viene preparato come in `create_model` (ID, versione, validazione e chiavi esterne),
+/// poi i record validi vengono scritti insieme: con un solo lock per lo store in memoria e con
+/// `database_store::insert_batch` per il database.
+///
+/// # Ritorna
+/// L'esito di ogni record nell'ordine di `items`, oppure un errore se l'operazione `create` non è
+/// consentita o lo storage non è disponibile.
+pub(crate) fn create_models<T: ArxModel>(items: Vec<T>) -> Result<Vec<Result<T, CrudError>>, CrudError> {
+    ensure_permitted::<T>(CrudOperation::Create)?;
+    let mut outcomes: Vec<Result<T, CrudError>> = items
+        .into_iter()
+        .map(|mut item| {
+            id_generator::assign(&mut item)?;
+            if T::VERSIONED && item.version() == 0 {
+                item.set_version(1);
+            }
+            ensure_valid(&item)?;
+            item.check_references()?;
+            Ok(item)
+        })
+        .collect();
+
+    audited::<T, _>(|| {
+        let mut memory = Vec::new();
+        let mut database = Vec::new();
+        for (position, outcome) in outcomes.iter_mut().enumerate() {
+            if let Ok(item) = outcome {
+                match T::default_store() {
+                    AllocType::InMemory => memory.push(position),
+                    AllocType::Database => database.push(position),
+                    AllocType::Cached => *outcome = insert_model(item.clone()),
                 }
             }
         }
 
-
-        impl Update<$model> for $model {
-            fn update(item: $model) -> Result<$model, String> {
-                // Simulazione della logica di aggiornamento (modifica nel database) per ogni modello che implementa il trait con `match`statement
-                Ok(item)
+        if !memory.is_empty() {
+            let mut records = lock_memory::<T>()?;
+            for &position in &memory {
+                if let Ok(item) = &outcomes[position] {
+                    if records.contains_key(&item.id()) {
+                        outcomes[position] = Err(CrudError::conflict::<T>(&item.id()));
+                        continue;
+                    }
+                    records.insert(item.id(), item.clone());
+                    restore_on_rollback::<T>(item.id(), None);
+                    id_generator::observe::<T>(&item.id());
+                    revocation::invalidate::<T>(&item.id());
+                }
             }
         }
 
-        impl Delete for $model {
-            fn delete(_id: u32) -> Result<(), String> {
-                // Simulazione della logica di eliminazione (rimozione dal database) per ogni modello che implementa il trait con `match`statement
-                Ok(())
+        if !database.is_empty() {
+            let mut written: Vec<T> = database.iter().filter_map(|&position| outcomes[position].clone().ok()).collect();
+            let results = database_store::insert_batch(&mut written)?;
+            for ((&position, item), result) in database.iter().zip(written).zip(results) {
+                revocation::invalidate::<T>(&item.id());
+                outcomes[position] = result.map(|()| item);
             }
         }
 
-        impl List<$model> for $model {
-            fn list() -> Vec<$model> {
-                // Simulazione della logica di elenco di tutti gli elementi  per ogni modello che implementa il trait con `match`statement
-                vec![]
-            }
+        for item in outcomes.iter().flatten() {
+            record_change::<T>(AuditAction::Create, &item.id(), None, Some(item))?;
         }
-        impl Search<$model> for $model {
-            fn search(_query: &str) -> Vec<$model> {
-                // Simulazione della logica di ricerca nel database o in memoria.
-                vec![]
+        Ok(())
+    })?;
+    info!(
+        "{} record su {} creati per {}",
+        outcomes.iter().filter(|outcome| outcome.is_ok()).count(),
+        outcomes.len(),
+        T::TABLE_NAME
+    );
+    Ok(outcomes)
+}
+
+/// Inserisce il record nello storage del modello senza controllare i permessi.
+fn insert_model<T: ArxModel>(mut item: T) -> Result<T, CrudError> {
+    match T::default_store() {
+        AllocType::InMemory => {
+            let mut records = lock_memory::<T>()?;
+            if records.contains_key(&item.id()) {
+                return Err(CrudError::conflict::<T>(&item.id()));
             }
+            records.insert(item.id(), item.clone());
+            restore_on_rollback::<T>(item.id(), None);
+            id_generator::observe::<T>(&item.id());
+            revocation::invalidate::<T>(&item.id());
+            info!("Record {} creato in memoria per {}", item.id(), T::TABLE_NAME);
+            Ok(item)
+        }
+        AllocType::Database => {
+            database_store::insert(&mut item)?;
+            revocation::invalidate::<T>(&item.id());
+            info!("Record {} creato nel database per {}", item.id(), T::TABLE_NAME);
+            Ok(item)
+        }
+        AllocType::Cached => {
+            model_cache::insert(&mut item)?;
+            revocation::invalidate::<T>(&item.id());
+            info!("Record {} creato nella cache per {}", item.id(), T::TABLE_NAME);
+            Ok(item)
         }
+    }
+}
 
-        impl Revoke for $model {
-            fn revoke(_id: u32) -> Result<(), String> {
-                // Simulazione della logica di revoca, ad esempio per chiavi API o token.
-                Ok(())
+/// Legge un record del modello in base al suo ID.
+///
+/// # Parametri
+/// - `id`: L'ID del record da leggere.
+///
+/// # Ritorna
+/// Il record trovato o un `CrudError` se non esiste, è revocato o l'operazione `read` non è consentita.
+pub fn read_model<T: ArxModel>(id: T::Id) -> Result<T, CrudError> {
+    ensure_permitted::<T>(CrudOperation::Read)?;
+    find_active::<T>(&id)
+}
+
+/// Legge un record che non è nel cestino né revocato, utilizzata da `Read` e `Update`.
+fn find_active<T: ArxModel>(id: &T::Id) -> Result<T, CrudError> {
+    let item = find_model::<T>(id)?;
+    if item.deleted_at().is_some() {
+        return Err(CrudError::not_found::<T>(id));
+    }
+    match item.revocation() {
+        Some(_) => Err(CrudError::revoked::<T>(id)),
+        None => Ok(item),
+    }
+}
+
+/// Legge un record senza controllare i permessi, utilizzata dalle operazioni che verificano l'esistenza.
+pub(crate) fn find_model<T: ArxModel>(id: &T::Id) -> Result<T, CrudError> {
+    let item = match T::default_store() {
+        AllocType::InMemory => lock_memory::<T>()?.get(id).cloned(),
+        AllocType::Database => database_store::select::<T>(id)?,
+        AllocType::Cached => model_cache::select::<T>(id)?,
+    };
+    item.ok_or_else(|| CrudError::not_found::<T>(id))
+}
+
+/// Aggiorna un record esistente del modello.
+///
+/// # Parametri
+/// - `item`: Il record con i valori aggiornati.
+///
+/// # Ritorna
+/// Il record aggiornato o un `CrudError` se il record non esiste, è nel cestino, è revocato
+/// o l'operazione `update` non è consentita.
+pub fn update_model<T: ArxModel>(item: T) -> Result<T, CrudError> {
+    ensure_permitted::<T>(CrudOperation::Update)?;
+    ensure_valid(&item)?;
+    item.check_references()?;
+    audited::<T, _>(|| {
+        let before = find_active::<T>(&item.id())?;
+        let updated = write_model(item)?;
+        record_change::<T>(AuditAction::Update, &updated.id(), Some(&before), Some(&updated))?;
+        Ok(updated)
+    })
+}
+
+/// Sovrascrive un record esistente senza controllare i permessi, utilizzata anche dalla revoca.
+///
+/// Per i modelli con versione la scrittura riesce solo se la versione del record è quella memorizzata.
+fn write_model<T: ArxModel>(mut item: T) -> Result<T, CrudError> {
+    revocation::invalidate::<T>(&item.id());
+    let expected = T::VERSIONED.then(|| item.version());
+    if let Some(version) = expected {
+        item.set_version(version + 1);
+    }
+    let updated = match T::default_store() {
+        AllocType::InMemory => {
+            let mut records = lock_memory::<T>()?;
+            match records.get_mut(&item.id()) {
+                Some(record) if expected.is_some_and(|version| record.version() != version) => {
+                    return Err(CrudError::version_conflict::<T>(&item.id(), record.version()));
+                }
+                Some(record) => {
+                    let previous = std::mem::replace(record, item.clone());
+                    restore_on_rollback(item.id(), Some(previous));
+                    true
+                }
+                None => false,
             }
         }
+        AllocType::Database => database_store::update(&item, expected)?,
+        AllocType::Cached => model_cache::update(&item, expected)?,
     };
+    match (updated, expected) {
+        (true, _) => Ok(item),
+        // Il record esiste ma con una versione diversa da quella letta
+        (false, Some(_)) => Err(CrudError::version_conflict::<T>(&item.id(), find_model::<T>(&item.id())?.version())),
+        (false, None) => Err(CrudError::not_found::<T>(&item.id())),
+    }
 }
 
-// Applicazione delle macro ai modelli
+/// Numero massimo di tentativi di `update_with_merge`.
+const MAX_MERGE_ATTEMPTS: usize = 5;
+
+/// Aggiorna un record risolvendo i conflitti di versione con `merge`.
+///
+/// # Parametri
+/// - `item`: Il record con i valori aggiornati e la versione letta.
+/// - `merge`: Riceve il record memorizzato e quello proposto, restituisce il record unito da riprovare
+///   oppure `None` per rinunciare all'aggiornamento.
+///
+/// # Ritorna
+/// Il record aggiornato, oppure `CrudError::VersionConflict` se `merge` rinuncia o i tentativi
+/// (`MAX_MERGE_ATTEMPTS`) si esauriscono. Per i modelli senza versione equivale a `update_model`.
+pub fn update_with_merge<T: ArxModel>(item: T, mut merge: impl FnMut(T, &T) -> Option<T>) -> Result<T, CrudError> {
+    let mut proposed = item;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match update_model(proposed.clone()) {
+            Err(CrudError::VersionConflict { .. }) if attempts < MAX_MERGE_ATTEMPTS => {
+                let stored = find_model::<T>(&proposed.id())?;
+                let current = stored.version();
+                match merge(stored, &proposed) {
+                    Some(mut merged) => {
+                        merged.set_version(current);
+                        proposed = merged;
+                    }
+                    None => return Err(CrudError::version_conflict::<T>(&proposed.id(), current)),
+                }
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Elimina un record del modello in base al suo ID.
+///
+/// Per i modelli con `ArxModel::SOFT_DELETE` il record viene spostato nel cestino.
+///
+/// # Parametri
+/// - `id`: L'ID del record da eliminare.
+pub fn delete_model<T: ArxModel>(id: T::Id) -> Result<(), CrudError> {
+    ensure_permitted::<T>(CrudOperation::Delete)?;
+    if !T::SOFT_DELETE {
+        let remove = || {
+            let before = audit_before::<T>(&id)?;
+            match remove_model::<T>(&id)? {
+                true => {
+                    record_change::<T>(AuditAction::Delete, &id, before.as_ref(), None)?;
+                    info!("Record {} eliminato per {}", id, T::TABLE_NAME);
+                    Ok(())
+                }
+                false => Err(CrudError::not_found::<T>(&id)),
+            }
+        };
+        // I record dipendenti vengono eliminati o verificati nella stessa transazione
+        return match T::AUDITED || !T::dependents().is_empty() {
+            true => unit_of_work::transaction(remove),
+            false => remove(),
+        };
+    }
+
+    let mut item = find_model::<T>(&id)?;
+    if item.deleted_at().is_some() {
+        return Err(CrudError::not_found::<T>(&id));
+    }
+    let before = item.clone();
+    item.set_deleted_at(Some(Utc::now().naive_utc()));
+    audited::<T, _>(|| {
+        let trashed = write_model(item)?;
+        record_change::<T>(AuditAction::Delete, &id, Some(&before), Some(&trashed))
+    })?;
+    info!("Record {} spostato nel cestino per {}", id, T::TABLE_NAME);
+    Ok(())
+}
+
+/// Rimuove definitivamente un record dallo storage senza controllare i permessi,
+/// dopo aver applicato le politiche di eliminazione dei modelli dipendenti.
+///
+/// # Ritorna
+/// `false` se il record non esiste.
+fn remove_model<T: ArxModel>(id: &T::Id) -> Result<bool, CrudError> {
+    for dependent in T::dependents() {
+        dependent(id)?;
+    }
+    revocation::invalidate::<T>(id);
+    match T::default_store() {
+        AllocType::InMemory => match lock_memory::<T>()?.remove(id) {
+            Some(previous) => {
+                restore_on_rollback(id.clone(), Some(previous));
+                Ok(true)
+            }
+            None => Ok(false),
+        },
+        AllocType::Database => database_store::delete::<T>(id),
+        AllocType::Cached => model_cache::delete::<T>(id),
+    }
+}
+
+/// Rimuove definitivamente un record dipendente eliminato a cascata (vedi `relations.rs`).
+pub(crate) fn remove_dependent<T: ArxModel>(id: &T::Id) -> Result<(), CrudError> {
+    let before = audit_before::<T>(id)?;
+    if remove_model::<T>(id)? {
+        record_change::<T>(AuditAction::Delete, id, before.as_ref(), None)?;
+        info!("Record {} eliminato a cascata per {}", id, T::TABLE_NAME);
+    }
+    Ok(())
+}
+
+/// Restituisce `CrudError::InvalidQuery` se il modello non supporta il cestino.
+fn ensure_soft_delete<T: ArxModel>() -> Result<(), CrudError> {
+    match T::SOFT_DELETE {
+        true => Ok(()),
+        false => Err(CrudError::InvalidQuery(format!(
+            "Il modello {} non ha un campo `deleted_at` e non supporta il cestino",
+            T::TABLE_NAME
+        ))),
+    }
+}
+
+/// Ripristina un record dal cestino.
+///
+/// # Ritorna
+/// Il record ripristinato, invariato se non era nel cestino.
+pub fn restore_model<T: ArxModel>(id: T::Id) -> Result<T, CrudError> {
+    ensure_permitted::<T>(CrudOperation::Delete)?;
+    ensure_soft_delete::<T>()?;
+    let mut item = find_model::<T>(&id)?;
+    if item.deleted_at().is_none() {
+        return Ok(item);
+    }
+    let before = item.clone();
+    item.set_deleted_at(None);
+    let item = audited::<T, _>(|| {
+        let restored = write_model(item)?;
+        record_change::<T>(AuditAction::Restore, &id, Some(&before), Some(&restored))?;
+        Ok(restored)
+    })?;
+    info!("Record {} ripristinato dal cestino per {}", id, T::TABLE_NAME);
+    Ok(item)
+}
+
+/// Elenca i record nel cestino secondo filtri, ordinamento e paginazione della query.
+pub fn list_trashed_models<T: ArxModel>(query: &Query) -> Result<Page<T>, CrudError> {
+    ensure_permitted::<T>(CrudOperation::List)?;
+    ensure_soft_delete::<T>()?;
+    query_models::<T>(&query.clone().include_trashed().ne("deleted_at", ModelValue::Null))
+}
 
-impl_crud_ops!(Task);
+/// Rimuove definitivamente i record nel cestino da più del periodo di conservazione.
+///
+/// La rimozione avviene in un'unica transazione (vedi `unit_of_work.rs`).
+///
+/// # Ritorna
+/// Il numero di record rimossi.
+pub fn purge_trashed_models<T: ArxModel>(retention: Duration) -> Result<usize, CrudError> {
+    ensure_permitted::<T>(CrudOperation::Delete)?;
+    ensure_soft_delete::<T>()?;
+    let retention = chrono::Duration::from_std(retention)
+        .map_err(|e| CrudError::InvalidQuery(format!("Periodo di conservazione non valido: {}", e)))?;
+    let cutoff = Utc::now().naive_utc() - retention;
+    let query = Query::new().include_trashed().include_revoked().lte("deleted_at", cutoff);
+
+    let purged = unit_of_work::transaction(|| {
+        let expired = query_models::<T>(&query)?;
+        for item in &expired.items {
+            remove_model::<T>(&item.id())?;
+            record_change::<T>(AuditAction::Purge, &item.id(), Some(item), None)?;
+        }
+        Ok(expired.items.len())
+    })?;
+    info!("Rimossi dal cestino {} record di {}", purged, T::TABLE_NAME);
+    Ok(purged)
+}
 
+/// Elenca i record del modello secondo ordinamento e paginazione della query.
+pub fn list_models<T: ArxModel>(query: &Query) -> Result<Page<T>, CrudError> {
+    ensure_permitted::<T>(CrudOperation::List)?;
+    query_models::<T>(query)
+}
 
+/// Cerca i record del modello che soddisfano i filtri della query.
+///
+/// La ricerca testuale (`Query::text`) considera i campi `String` del modello (`ArxModel::text_fields`)
+/// e non distingue tra maiuscole e minuscole.
+pub fn search_models<T: ArxModel>(query: &Query) -> Result<Page<T>, CrudError> {
+    ensure_permitted::<T>(CrudOperation::Search)?;
+    query_models::<T>(query)
+}
 
-// Aggiungi qui ulteriori modelli per l'applicazione CRUD generica
+/// Esegue la query sullo storage del modello senza controllare i permessi.
+///
+/// I record revocati vengono esclusi, salvo `Query::include_revoked`,
+/// così come i record nel cestino, salvo `Query::include_trashed`.
+pub(crate) fn query_models<T: ArxModel>(query: &Query) -> Result<Page<T>, CrudError> {
+    query.validate(&T::field_names()).map_err(CrudError::InvalidQuery)?;
+    let mut query = match T::REVOCABLE && !query.include_revoked {
+        true => query.clone().filter("revocation", FilterOp::Eq, ModelValue::Null),
+        false => query.clone(),
+    };
+    if T::SOFT_DELETE && !query.include_trashed {
+        query = query.filter("deleted_at", FilterOp::Eq, ModelValue::Null);
+    }
+    match T::default_store() {
+        AllocType::InMemory => {
+            let items: Vec<T> = lock_memory::<T>()?.values().cloned().collect();
+            Ok(query.apply(items))
+        }
+        AllocType::Database => database_store::query::<T>(&query),
+        AllocType::Cached => model_cache::query::<T>(&query),
+    }
+}
 
-// Esempio di utilizzo per i modelli con Search e Revoke
-//impl_search_and_revoke!(User);
-//impl_search_and_revoke!(Article);
+/// Revoca un record del modello in base al suo ID.
+///
+/// # Parametri
+/// - `id`: L'ID del record da revocare.
+/// - `reason`: Motivo della revoca.
+/// - `actor`: Utente o servizio che esegue la revoca.
+///
+/// # Ritorna
+/// La revoca registrata. Se il record è già revocato viene restituita la revoca esistente.
+///
+/// # Note
+/// La revoca non richiede l'operazione `update`, così anche i modelli non modificabili (es. `ApiKey`) sono revocabili.
+pub fn revoke_model<T: ArxModel>(id: T::Id, reason: &str, actor: &str) -> Result<Revocation, CrudError> {
+    ensure_permitted::<T>(CrudOperation::Revoke)?;
+    let mut item = find_model::<T>(&id)?;
+    if let Some(existing) = item.revocation() {
+        return Ok(existing);
+    }
 
-// Aggiungi ulteriori modelli se necessario
+    let before = item.clone();
+    let revocation = Revocation::new(reason, actor);
+    item.set_revocation(Some(revocation.clone()));
+    audited::<T, _>(|| {
+        let revoked = write_model(item)?;
+        audit::with_actor(actor, || record_change::<T>(AuditAction::Revoke, &id, Some(&before), Some(&revoked)))
+    })?;
+    revocation::remember::<T>(&id, true);
+    let revoked = id.clone();
+    unit_of_work::record_undo(move || revocation::invalidate::<T>(&revoked));
+    info!("Record {} revocato per {} da {}: {}", id, T::TABLE_NAME, actor, reason);
+    Ok(revocation)
+}
 
-//Note sviluppo
-// variabile: connection è definita in api_server::connection
-// variabile: STATIC_MEMORY_SCALE è definita in questo file in maniera temporanea
-//            Sarà definita in una variabile di configurazione nel main creata tramite il CLI 
+/// Verifica se un record è revocato, utilizzando la cache delle revoche quando possibile.
+///
+/// # Ritorna
+/// `false` per i modelli non revocabili, `CrudError::NotFound` se il record non esiste.
+pub fn is_revoked_model<T: ArxModel>(id: T::Id) -> Result<bool, CrudError> {
+    if !T::REVOCABLE {
+        return Ok(false);
+    }
+    if let Some(revoked) = revocation::cached::<T>(&id) {
+        return Ok(revoked);
+    }
+    let revoked = find_model::<T>(&id)?.revocation().is_some();
+    revocation::remember::<T>(&id, revoked);
+    Ok(revoked)
+}
@@ -0,0 +1,391 @@
+//! Modulo per l'esportazione e l'importazione dei record dei modelli CRUD (JSON e CSV).
+//!
+//! Utilizzato dai comandi `arx data export` e `arx data import` per spostare i dati tra ambienti
+//! (es. da staging a produzione), oppure direttamente da codice:
+//! ```rust,ignore
+//! let transfer = data_transfer::model("configuration")?;
+//! let exported = transfer.export(DataFormat::Csv, &mut File::create("configuration.csv")?)?;
+//! let report = transfer.import(DataFormat::Csv, &mut File::open("configuration.csv")?, &BatchConfig::new())?;
+//! ```
+//!
+//! ### Formati:
+//! - `json`: Array di oggetti, serializzati con le derive `Serialize`/`Deserialize` dei modelli
+//!   (senza i campi `store`, `ops` e `memory`).
+//! - `csv`: Una riga di intestazione con i campi persistiti (`ArxModel::field_names`) e un record per riga,
+//!   con i valori nella stessa rappresentazione degli storage SQL (`ModelValue`): timestamp nel formato
+//!   `TIMESTAMP_FORMAT`, revoche, metadati e liste come testo JSON. Come in `COPY ... CSV` di PostgreSQL,
+//!   una cella vuota senza virgolette è `NULL` mentre `""` è un testo vuoto.
+//!
+//! I campi dichiarati con `#[arx(secret)]` (es. `User::password`, `ApiKey::key`) non vengono mai esportati:
+//! per importare i record di questi modelli il file deve includerli.
+//!
+//! ### Esportazione e importazione:
+//! - L'esportazione legge tutti i record a pagine di `DEFAULT_BATCH_SIZE`, compresi quelli revocati
+//!   e nel cestino, senza controllare le operazioni dichiarate dal modello.
+//! - L'importazione verifica prima l'intero file, poi inserisce i record con `batch::insert` (ID esistenti
+//!   mantenuti, validazione, chiavi esterne e audit come in `Create`): i record non inseriti vengono
+//!   restituiti nel `BatchReport` con la loro posizione nel file.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::crud::batch::{self, BatchConfig, BatchReport, DEFAULT_BATCH_SIZE};
+use crate::crud::crud_ops::{self, AllocType, ArxModel, CrudError};
+use crate::crud::model_cache;
+use crate::crud::model_value::ModelValue;
+use crate::crud::query::Query;
+
+/// Formato dei file di esportazione.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    Json,
+    Csv,
+}
+
+impl FromStr for DataFormat {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "json" => Ok(DataFormat::Json),
+            "csv" => Ok(DataFormat::Csv),
+            _ => Err(format!("Formato non supportato: {} (json o csv)", input)),
+        }
+    }
+}
+
+/// Errori dell'esportazione e dell'importazione.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferError {
+    /// Nessun modello compilato con il nome indicato.
+    UnknownModel(String),
+    /// Lettura o scrittura del file fallita.
+    Io(String),
+    /// File non conforme al formato (JSON non valido, intestazione CSV errata).
+    InvalidFormat(String),
+    /// Record non convertibile nel modello, numerato a partire da 1.
+    InvalidRecord { record: usize, message: String },
+    /// Errore dello storage del modello.
+    Crud(CrudError),
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferError::UnknownModel(model) => write!(f, "UnknownModel: modello {} non disponibile", model),
+            TransferError::Io(message) => write!(f, "Io: {}", message),
+            TransferError::InvalidFormat(message) => write!(f, "InvalidFormat: {}", message),
+            TransferError::InvalidRecord { record, message } => {
+                write!(f, "InvalidRecord: record {} non valido, {}", record, message)
+            }
+            TransferError::Crud(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for TransferError {}
+
+impl From<io::Error> for TransferError {
+    fn from(error: io::Error) -> Self {
+        TransferError::Io(error.to_string())
+    }
+}
+
+impl From<CrudError> for TransferError {
+    fn from(error: CrudError) -> Self {
+        TransferError::Crud(error)
+    }
+}
+
+type ExportFn = fn(DataFormat, &mut dyn Write) -> Result<usize, TransferError>;
+type ImportFn = fn(DataFormat, &mut dyn Read, &BatchConfig) -> Result<BatchReport, TransferError>;
+
+/// Esportazione e importazione di un modello, ottenuta con `model`.
+///
+/// # Campi
+/// - `table`: Nome della tabella del modello.
+/// - `store`: Storage di default del modello.
+#[derive(Debug, Clone)]
+pub struct ModelTransfer {
+    pub table: &'static str,
+    pub store: AllocType,
+    export: ExportFn,
+    import: ImportFn,
+}
+
+impl ModelTransfer {
+    fn of<T: ArxModel + Serialize + DeserializeOwned>() -> Self {
+        ModelTransfer {
+            table: T::TABLE_NAME,
+            store: T::default_store(),
+            export: export::<T>,
+            import: import::<T>,
+        }
+    }
+
+    /// Scrive tutti i record del modello, restituendo il numero di record esportati.
+    pub fn export(&self, format: DataFormat, writer: &mut dyn Write) -> Result<usize, TransferError> {
+        (self.export)(format, writer)
+    }
+
+    /// Inserisce i record letti, restituendo l'esito dell'inserimento.
+    pub fn import(
+        &self,
+        format: DataFormat,
+        reader: &mut dyn Read,
+        config: &BatchConfig,
+    ) -> Result<BatchReport, TransferError> {
+        (self.import)(format, reader, config)
+    }
+}
+
+/// Registra i modelli di `crud/models/default`, con le stesse feature dei file dei modelli.
+macro_rules! model_transfers {
+    ($($(#[$cfg:meta])* $model:ty),* $(,)?) => {
+        #[allow(clippy::vec_init_then_push)]
+        fn registry() -> Vec<ModelTransfer> {
+            #[allow(unused_mut)]
+            let mut transfers = Vec::new();
+            $($(#[$cfg])* transfers.push(ModelTransfer::of::<$model>());)*
+            transfers
+        }
+    };
+}
+
+model_transfers! {
+    #[cfg(feature = "api")] crate::crud::models::default::api_key::model::ApiKey,
+    #[cfg(feature = "webapp")] crate::crud::models::default::article::model::Article,
+    #[cfg(feature = "webapp")] crate::crud::models::default::article_category::model::ArticleCategory,
+    #[cfg(feature = "webapp")] crate::crud::models::default::article_tag::model::ArticleTag,
+    crate::crud::models::default::audit_entry::model::AuditEntry,
+    #[cfg(feature = "webapp")] crate::crud::models::default::category::model::Category,
+    #[cfg(feature = "embedded")] crate::crud::models::default::command::model::Command,
+    #[cfg(feature = "webapp")] crate::crud::models::default::comment::model::Comment,
+    #[cfg(any(feature = "automation", feature = "embedded"))]
+    crate::crud::models::default::configuration::model::Configuration,
+    #[cfg(feature = "embedded")] crate::crud::models::default::device::model::Device,
+    #[cfg(feature = "desktop")] crate::crud::models::default::document::model::Document,
+    #[cfg(feature = "api")] crate::crud::models::default::endpoint::model::Endpoint,
+    #[cfg(feature = "automation")] crate::crud::models::default::exe_log::model::ExecutionLog,
+    #[cfg(any(feature = "webapp", feature = "desktop"))] crate::crud::models::default::file::model::File,
+    #[cfg(feature = "embedded")] crate::crud::models::default::firmware_version::model::FirmwareVersion,
+    #[cfg(feature = "automation")] crate::crud::models::default::job::model::Job,
+    #[cfg(feature = "embedded")] crate::crud::models::default::log_event::model::LogEvent,
+    #[cfg(feature = "automation")] crate::crud::models::default::macro_script::model::Macro,
+    #[cfg(feature = "webapp")] crate::crud::models::default::page::model::Page,
+    #[cfg(feature = "api")] crate::crud::models::default::permission::model::Permission,
+    #[cfg(feature = "desktop")] crate::crud::models::default::preferences::model::Preferences,
+    #[cfg(feature = "automation")] crate::crud::models::default::project::model::Project,
+    #[cfg(feature = "api")] crate::crud::models::default::rate_limit::model::RateLimitRule,
+    #[cfg(feature = "api")] crate::crud::models::default::request_log::model::RequestLog,
+    #[cfg(feature = "automation")] crate::crud::models::default::schedule::model::Schedule,
+    #[cfg(feature = "automation")] crate::crud::models::default::script::model::Script,
+    #[cfg(feature = "embedded")] crate::crud::models::default::sensor_data::model::SensorData,
+    #[cfg(feature = "desktop")] crate::crud::models::default::settings::model::Settings,
+    #[cfg(feature = "webapp")] crate::crud::models::default::tag::model::Tag,
+    #[cfg(any(feature = "automation", feature = "desktop", feature = "embedded"))]
+    crate::crud::models::default::task::model::Task,
+    #[cfg(feature = "api")] crate::crud::models::default::token::model::Token,
+    #[cfg(any(feature = "webapp", feature = "api", feature = "desktop", feature = "automation", feature = "embedded"))]
+    crate::crud::models::default::user::model::User,
+}
+
+/// Nomi delle tabelle dei modelli disponibili con le feature attive.
+pub fn models() -> Vec<&'static str> {
+    registry().iter().map(|transfer| transfer.table).collect()
+}
+
+/// Restituisce l'esportazione e l'importazione del modello con il nome di tabella indicato (es. `sensor_data`).
+pub fn model(name: &str) -> Result<ModelTransfer, TransferError> {
+    registry()
+        .into_iter()
+        .find(|transfer| transfer.table.eq_ignore_ascii_case(name))
+        .ok_or_else(|| TransferError::UnknownModel(format!("{} (disponibili: {})", name, models().join(", "))))
+}
+
+/// Scrive tutti i record del modello nel formato indicato.
+pub fn export<T: ArxModel + Serialize>(format: DataFormat, writer: &mut dyn Write) -> Result<usize, TransferError> {
+    let mut query = Query::new().include_revoked().include_trashed().limit(DEFAULT_BATCH_SIZE);
+    let mut exported = 0;
+    let secret = T::secret_fields();
+    match format {
+        DataFormat::Json => write!(writer, "[")?,
+        DataFormat::Csv => {
+            let header: Vec<Option<String>> = T::field_names()
+                .into_iter()
+                .filter(|field| !secret.contains(field))
+                .map(|field| Some(field.to_string()))
+                .collect();
+            write_csv_row(writer, &header)?
+        }
+    }
+    loop {
+        let page = crud_ops::query_models::<T>(&query)?;
+        for item in &page.items {
+            match format {
+                DataFormat::Json => {
+                    write!(writer, "{}\n  ", if exported == 0 { "" } else { "," })?;
+                    let mut record = serde_json::to_value(item).map_err(|e| TransferError::Io(e.to_string()))?;
+                    if let Some(record) = record.as_object_mut() {
+                        for field in &secret {
+                            record.remove(*field);
+                        }
+                    }
+                    serde_json::to_writer(&mut *writer, &record).map_err(|e| TransferError::Io(e.to_string()))?;
+                }
+                DataFormat::Csv => {
+                    let cells: Vec<Option<String>> = item.public_values().into_iter().map(|(_, value)| to_cell(value)).collect();
+                    write_csv_row(writer, &cells)?;
+                }
+            }
+            exported += 1;
+        }
+        match page.next_cursor {
+            Some(cursor) => query = query.after(cursor),
+            None => break,
+        }
+    }
+    if format == DataFormat::Json {
+        writeln!(writer, "{}]", if exported == 0 { "" } else { "\n" })?;
+    }
+    Ok(exported)
+}
+
+/// Legge i record del modello nel formato indicato e li inserisce a blocchi.
+///
+/// # Ritorna
+/// Il `BatchReport` dell'inserimento, con `BatchFailure::index` pari alla posizione del record nel file
+/// a partire da 0, oppure `TransferError::InvalidRecord` senza inserire nessun record se il file non è valido.
+pub fn import<T: ArxModel + DeserializeOwned>(
+    format: DataFormat,
+    reader: &mut dyn Read,
+    config: &BatchConfig,
+) -> Result<BatchReport, TransferError> {
+    let items = match format {
+        DataFormat::Json => read_json::<T>(reader)?,
+        DataFormat::Csv => read_csv::<T>(reader)?,
+    };
+    let report = batch::insert(items, config)?;
+    // Le scritture in coda della cache write-behind vengono completate prima di restituire il controllo
+    if T::default_store() == AllocType::Cached {
+        model_cache::flush::<T>()?;
+    }
+    Ok(report)
+}
+
+fn read_json<T: ArxModel + DeserializeOwned>(reader: &mut dyn Read) -> Result<Vec<T>, TransferError> {
+    let records: Vec<serde_json::Value> = serde_json::from_reader(reader)
+        .map_err(|e| TransferError::InvalidFormat(format!("Atteso un array JSON di record di {}: {}", T::TABLE_NAME, e)))?;
+    records
+        .into_iter()
+        .enumerate()
+        .map(|(position, record)| {
+            serde_json::from_value(record)
+                .map_err(|e| TransferError::InvalidRecord { record: position + 1, message: e.to_string() })
+        })
+        .collect()
+}
+
+fn read_csv<T: ArxModel>(reader: &mut dyn Read) -> Result<Vec<T>, TransferError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    let mut rows = parse_csv(&input)?.into_iter();
+
+    let header: Vec<String> = rows.next().unwrap_or_default().into_iter().map(Option::unwrap_or_default).collect();
+    let fields = T::field_names();
+    if let Some(column) = header.iter().find(|column| !fields.contains(&column.as_str())) {
+        return Err(TransferError::InvalidFormat(format!("Colonna {} non presente in {}", column, T::TABLE_NAME)));
+    }
+    // I campi segreti mancanti verrebbero ricostruiti vuoti da `from_values`
+    if let Some(field) = T::secret_fields().into_iter().find(|field| !header.iter().any(|column| column == field)) {
+        return Err(TransferError::InvalidFormat(format!("Colonna {} obbligatoria per {}", field, T::TABLE_NAME)));
+    }
+
+    rows.enumerate()
+        .map(|(position, cells)| {
+            if cells.len() != header.len() {
+                return Err(TransferError::InvalidRecord {
+                    record: position + 1,
+                    message: format!("{} celle invece di {}", cells.len(), header.len()),
+                });
+            }
+            let values: HashMap<String, ModelValue> = header
+                .iter()
+                .cloned()
+                .zip(cells.into_iter().map(|cell| cell.map_or(ModelValue::Null, ModelValue::Text)))
+                .collect();
+            T::from_values(&values).map_err(|message| TransferError::InvalidRecord { record: position + 1, message })
+        })
+        .collect()
+}
+
+/// Converte un valore in una cella CSV, `None` per `NULL`.
+fn to_cell(value: ModelValue) -> Option<String> {
+    match value.to_json() {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(text) => Some(text),
+        json => Some(json.to_string()),
+    }
+}
+
+/// Scrive una riga CSV: `NULL` come cella vuota, i testi vuoti o con separatori tra virgolette.
+fn write_csv_row(writer: &mut dyn Write, cells: &[Option<String>]) -> io::Result<()> {
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|cell| match cell {
+            None => String::new(),
+            Some(text) if text.is_empty() || text.contains([',', '"', '\n', '\r']) => {
+                format!("\"{}\"", text.replace('"', "\"\""))
+            }
+            Some(text) => text.clone(),
+        })
+        .collect();
+    writeln!(writer, "{}", cells.join(","))
+}
+
+/// Legge le righe di un CSV, con `None` per le celle vuote senza virgolette. Le righe vuote vengono ignorate.
+fn parse_csv(input: &str) -> Result<Vec<Vec<Option<String>>>, TransferError> {
+    let mut rows = Vec::new();
+    let mut chars = input.chars().peekable();
+    while chars.peek().is_some() {
+        let mut row = Vec::new();
+        loop {
+            let mut cell = String::new();
+            let quoted = chars.next_if_eq(&'"').is_some();
+            if quoted {
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.next_if_eq(&'"').is_some() => cell.push('"'),
+                        Some('"') => break,
+                        Some(c) => cell.push(c),
+                        None => return Err(TransferError::InvalidFormat(format!("Virgolette non chiuse alla riga {}", rows.len() + 1))),
+                    }
+                }
+            }
+            while let Some(&c) = chars.peek() {
+                match c {
+                    ',' | '\n' => break,
+                    '\r' => {}
+                    _ if quoted => {
+                        return Err(TransferError::InvalidFormat(format!("Testo dopo le virgolette alla riga {}", rows.len() + 1)))
+                    }
+                    _ => cell.push(c),
+                }
+                chars.next();
+            }
+            row.push((quoted || !cell.is_empty()).then_some(cell));
+            if chars.next() != Some(',') {
+                break;
+            }
+        }
+        if row != [None] {
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
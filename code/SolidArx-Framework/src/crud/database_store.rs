@@ -0,0 +1,688 @@
+//! Modulo per lo storage su database dei modelli CRUD con `AllocType::Database`.
+//!
+//! Le funzioni generiche di `crud_ops.rs` delegano a questo modulo le operazioni sui modelli
+//! persistiti su SQLite o PostgreSQL tramite diesel, utilizzando la connessione ottenuta
+//! da `ConnectionManager` e registrata con `install`.
+//!
+//! ### Lettura dei record:
+//! Dato che i modelli non hanno uno schema diesel statico, ogni riga viene letta come oggetto JSON
+//! (`json_object` su SQLite, `row_to_json` su PostgreSQL) e convertita in `ModelValue`,
+//! da cui `ArxModel::from_values` ricostruisce il modello.
+//!
+//! ### Scrittura dei record:
+//! I valori vengono passati come parametri della query in base al tipo del `ModelValue`,
+//! i valori strutturati (`List`, `Map`) come testo JSON per le colonne `JSON` generate da `table_scraper.rs`.
+//!
+//! ### Transazioni:
+//! `transaction` esegue più operazioni in un'unica transazione sulla connessione registrata,
+//! utilizzata da `unit_of_work.rs`. Con MongoDB viene utilizzata una sessione, se supportata dal server.
+//!
+//! ### Notifiche:
+//! Su PostgreSQL ogni scrittura invia una notifica sul canale `arx_changes` (`NOTIFY`, consegnata al commit),
+//! ricevuta dagli altri processi con `listen_notifications` (vedi `watch.rs`).
+//!
+//! Con una connessione MongoDB le operazioni vengono delegate a `mongo_store.rs`.
+//!
+//! Le tabelle vengono create da `arx db migrate` a partire dai modelli.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use diesel::connection::{AnsiTransactionManager, TransactionManager};
+use diesel::prelude::*;
+use diesel::query_builder::{BoxedSqlQuery, SqlQuery};
+use diesel::result::DatabaseErrorKind;
+use diesel::sql_types::{BigInt, Bool, Double, Nullable, Text, Timestamp};
+use diesel::{pg::Pg, sqlite::Sqlite};
+use log::{error, info, warn};
+use once_cell::sync::Lazy;
+
+use crate::config::network_config::DatabaseType;
+use crate::crud::crud_ops::{ArxModel, CrudError};
+use crate::crud::id_generator::{IdStrategy, ModelKey};
+use crate::crud::model_value::{field_value, ModelValue, ToModelValue};
+use crate::crud::mongo_store;
+use crate::crud::query::{Page, Query, SqlDialect};
+use crate::network::connection_management::{ConnectionManager, DatabaseConnection, DbConnection};
+
+/// Numero massimo di parametri di un'istruzione PostgreSQL, per gli `INSERT` su più righe.
+const MAX_PG_PARAMETERS: usize = 65535;
+
+/// Canale PostgreSQL delle notifiche di modifica dei modelli.
+pub(crate) const NOTIFY_CHANNEL: &str = "arx_changes";
+/// Intervallo di attesa delle notifiche e dei tentativi di riconnessione del listener.
+const LISTEN_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Connessione utilizzata dai modelli con `AllocType::Database`.
+static CONNECTION: Lazy<Mutex<Option<DbConnection>>> = Lazy::new(|| Mutex::new(None));
+
+thread_local! {
+    /// Lock sulla connessione mantenuto dalla transazione attiva sul thread, fino a commit o rollback.
+    static TRANSACTION: RefCell<Option<MutexGuard<'static, Option<DbConnection>>>> = const { RefCell::new(None) };
+}
+
+/// Riga letta come oggetto JSON.
+#[derive(QueryableByName)]
+struct JsonRow {
+    #[diesel(sql_type = Text)]
+    row: String,
+}
+
+/// Risultato di `SELECT COUNT(*)`.
+#[derive(QueryableByName)]
+struct CountRow {
+    #[diesel(sql_type = BigInt)]
+    total: i64,
+}
+
+/// Registra la connessione utilizzata dai modelli con `AllocType::Database`.
+///
+/// Chiamata da `CoreSystem::run` dopo `ConnectionManager::initialize_connection`,
+/// sostituisce l'eventuale connessione registrata in precedenza.
+pub fn install(connection: DbConnection) {
+    match CONNECTION.lock() {
+        Ok(mut current) => {
+            *current = Some(connection);
+            info!("Connessione al database registrata per i modelli CRUD");
+        }
+        Err(e) => error!("Errore di lock sulla connessione dei modelli CRUD: {}", e),
+    }
+}
+
+/// Stabilisce la connessione tramite `ConnectionManager` e la registra con `install`.
+///
+/// Utile al di fuori di `CoreSystem::run`, ad esempio nei comandi del CLI e nei test.
+pub fn connect(database: DatabaseType) -> Result<(), CrudError> {
+    // Il runtime deve restare attivo finché il client MongoDB viene utilizzato
+    let connection = mongo_store::block_on(ConnectionManager::new(database).initialize_connection())?
+        .map_err(|e| CrudError::StorageError(format!("Connessione al database fallita: {}", e)))?;
+    install(connection);
+    Ok(())
+}
+
+/// Rimuove la connessione registrata, restituendola se presente.
+pub fn uninstall() -> Option<DbConnection> {
+    CONNECTION.lock().ok().and_then(|mut current| current.take())
+}
+
+/// Esegue un'operazione sulla connessione registrata.
+///
+/// Durante una transazione viene utilizzata la connessione già acquisita dal thread.
+fn with_connection<R>(operation: impl FnOnce(&mut DbConnection) -> Result<R, CrudError>) -> Result<R, CrudError> {
+    TRANSACTION.with(|transaction| match transaction.borrow_mut().as_mut() {
+        Some(connection) => registered(connection.as_mut(), operation),
+        None => {
+            let mut connection = lock_connection()?;
+            registered(connection.as_mut(), operation)
+        }
+    })
+}
+
+fn lock_connection() -> Result<MutexGuard<'static, Option<DbConnection>>, CrudError> {
+    CONNECTION
+        .lock()
+        .map_err(|e| CrudError::StorageError(format!("Errore di lock sulla connessione: {}", e)))
+}
+
+fn registered<R>(
+    connection: Option<&mut DbConnection>,
+    operation: impl FnOnce(&mut DbConnection) -> Result<R, CrudError>,
+) -> Result<R, CrudError> {
+    match connection {
+        Some(connection) => operation(connection),
+        None => Err(CrudError::StorageError(
+            "Nessuna connessione al database registrata, configurare il database con `arx database`".to_string(),
+        )),
+    }
+}
+
+/// Annulla la transazione rimasta attiva sul thread, ad esempio in caso di panic durante `transaction`.
+struct TransactionScope;
+
+impl Drop for TransactionScope {
+    fn drop(&mut self) {
+        if let Ok(Some(mut connection)) = TRANSACTION.try_with(|transaction| transaction.borrow_mut().take()) {
+            if let Some(Err(e)) = connection.as_mut().map(|sql| finish(sql, false)) {
+                error!("Rollback della transazione fallito: {}", e);
+            }
+        }
+    }
+}
+
+/// Avvia una transazione sulla connessione SQL.
+fn begin(connection: &mut DbConnection) -> QueryResult<()> {
+    match connection {
+        DbConnection::Postgres(conn) => AnsiTransactionManager::begin_transaction(conn),
+        DbConnection::SQLite(conn) => AnsiTransactionManager::begin_transaction(conn),
+        DbConnection::MongoDB(_) => Ok(()),
+    }
+}
+
+/// Conferma (`commit`) o annulla la transazione sulla connessione SQL.
+fn finish(connection: &mut DbConnection, commit: bool) -> QueryResult<()> {
+    match (connection, commit) {
+        (DbConnection::Postgres(conn), true) => AnsiTransactionManager::commit_transaction(conn),
+        (DbConnection::Postgres(conn), false) => AnsiTransactionManager::rollback_transaction(conn),
+        (DbConnection::SQLite(conn), true) => AnsiTransactionManager::commit_transaction(conn),
+        (DbConnection::SQLite(conn), false) => AnsiTransactionManager::rollback_transaction(conn),
+        (DbConnection::MongoDB(_), _) => Ok(()),
+    }
+}
+
+/// Esegue `work` in un'unica transazione sulla connessione registrata.
+///
+/// Il commit viene eseguito se `work` restituisce `Ok`, il rollback in caso di errore.
+/// Per tutta la durata della transazione il lock sulla connessione resta al thread corrente:
+/// le operazioni di `work` utilizzano la stessa connessione, quelle degli altri thread attendono il commit.
+///
+/// # Note
+/// - Una transazione già attiva sul thread viene riutilizzata.
+/// - Senza connessione registrata `work` viene eseguita direttamente (es. solo modelli in memoria).
+/// - Con MongoDB la transazione è gestita da `mongo_store::transaction`.
+pub(crate) fn transaction<R>(work: impl FnOnce() -> Result<R, CrudError>) -> Result<R, CrudError> {
+    if TRANSACTION.with(|transaction| transaction.borrow().is_some()) {
+        return work();
+    }
+    let mut connection = lock_connection()?;
+    if let Some(DbConnection::MongoDB(client)) = connection.as_ref() {
+        let client = client.clone();
+        drop(connection);
+        return mongo_store::transaction(&client, work);
+    }
+    match connection.as_mut() {
+        Some(sql) => begin(sql).map_err(storage_error)?,
+        None => {
+            drop(connection);
+            return work();
+        }
+    }
+
+    TRANSACTION.with(|transaction| *transaction.borrow_mut() = Some(connection));
+    let _scope = TransactionScope;
+    let result = work();
+
+    let outcome = match TRANSACTION.with(|transaction| transaction.borrow_mut().take()) {
+        Some(mut connection) => match connection.as_mut() {
+            Some(sql) => finish(sql, result.is_ok()),
+            None => Ok(()),
+        },
+        None => Ok(()),
+    };
+    match (result, outcome) {
+        (Ok(value), Ok(())) => {
+            info!("Transazione confermata");
+            Ok(value)
+        }
+        (Ok(_), Err(e)) => Err(storage_error(e)),
+        (Err(e), outcome) => {
+            if let Err(rollback) = outcome {
+                error!("Rollback della transazione fallito: {}", rollback);
+            }
+            warn!("Transazione annullata: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Client MongoDB registrato, se la connessione è `DbConnection::MongoDB`.
+///
+/// Il client viene clonato per non mantenere il lock sulla connessione durante le operazioni asincrone.
+pub(crate) fn mongo_client() -> Result<Option<mongodb::Client>, CrudError> {
+    with_connection(|connection| match connection {
+        DbConnection::MongoDB(client) => Ok(Some(client.clone())),
+        _ => Ok(None),
+    })
+}
+
+/// Invia una notifica sul canale `NOTIFY_CHANNEL`, solo con una connessione PostgreSQL.
+///
+/// La notifica fa parte della transazione attiva e viene consegnata solo al commit.
+/// Un errore non annulla la scrittura e viene solo registrato nel log.
+pub(crate) fn notify(payload: impl FnOnce() -> String) {
+    let result = with_connection(|connection| match connection {
+        DbConnection::Postgres(conn) => diesel::sql_query("SELECT pg_notify($1, $2)")
+            .bind::<Text, _>(NOTIFY_CHANNEL)
+            .bind::<Text, _>(payload())
+            .execute(conn)
+            .map(|_| ())
+            .map_err(storage_error),
+        _ => Ok(()),
+    });
+    if let Err(e) = result {
+        warn!("Notifica della modifica non inviata: {}", e);
+    }
+}
+
+/// Connessione dedicata in ascolto sul canale `NOTIFY_CHANNEL`.
+fn listen_connection(database_url: &str) -> Result<PgConnection, CrudError> {
+    let mut connection = PgConnection::establish(database_url)
+        .map_err(|e| CrudError::StorageError(format!("Connessione per le notifiche fallita: {}", e)))?;
+    diesel::sql_query(format!("LISTEN {}", NOTIFY_CHANNEL)).execute(&mut connection).map_err(storage_error)?;
+    Ok(connection)
+}
+
+/// Avvia un thread che riceve le notifiche di `notify` con una connessione PostgreSQL dedicata,
+/// passando il contenuto di ognuna a `on_notification`.
+///
+/// In caso di errore la connessione viene ristabilita: le notifiche inviate nel frattempo vanno perse.
+pub(crate) fn listen_notifications(database_url: &str, on_notification: fn(&str)) -> Result<(), CrudError> {
+    let mut connection = listen_connection(database_url)?;
+    let database_url = database_url.to_string();
+    std::thread::Builder::new()
+        .name("arx-listen".to_string())
+        .spawn(move || loop {
+            // La query legge dal server le notifiche in attesa
+            match diesel::sql_query("SELECT 1").execute(&mut connection) {
+                Ok(_) => {
+                    for notification in connection.notifications_iter() {
+                        match notification {
+                            Ok(notification) => on_notification(&notification.payload),
+                            Err(e) => warn!("Notifica non valida sul canale {}: {}", NOTIFY_CHANNEL, e),
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Connessione per le notifiche interrotta: {}", e);
+                    connection = loop {
+                        match listen_connection(&database_url) {
+                            Ok(listening) => break listening,
+                            Err(e) => warn!("{}", e),
+                        }
+                        std::thread::sleep(LISTEN_INTERVAL);
+                    };
+                    info!("Connessione per le notifiche ristabilita");
+                }
+            }
+            std::thread::sleep(LISTEN_INTERVAL);
+        })
+        .map_err(|e| CrudError::StorageError(format!("Avvio del listener delle notifiche fallito: {}", e)))?;
+    info!("In ascolto delle notifiche sul canale {}", NOTIFY_CHANNEL);
+    Ok(())
+}
+
+fn storage_error(e: diesel::result::Error) -> CrudError {
+    CrudError::StorageError(e.to_string())
+}
+
+/// Errore di una scrittura, `CrudError::Conflict` se l'ID del record esiste già.
+fn write_error<T: ArxModel>(e: diesel::result::Error, id: &T::Id) -> CrudError {
+    match e {
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => CrudError::conflict::<T>(id),
+        e => storage_error(e),
+    }
+}
+
+/// Aggiunge i parametri alla query in base al tipo di ogni `ModelValue`.
+///
+/// Nelle scritture i valori `Null` vengono inseriti come `NULL` letterale (vedi `write_placeholder`),
+/// dato che su PostgreSQL un parametro nullo di tipo testo non è assegnabile a colonne di altro tipo.
+macro_rules! bind_values {
+    ($query:expr, $values:expr) => {{
+        let mut query = $query;
+        for value in $values {
+            query = match value {
+                ModelValue::Bool(value) => query.bind::<Bool, _>(*value),
+                ModelValue::Int(value) => query.bind::<BigInt, _>(*value),
+                ModelValue::Float(value) => query.bind::<Double, _>(*value),
+                ModelValue::Text(value) => query.bind::<Text, _>(value.clone()),
+                ModelValue::Timestamp(value) => query.bind::<Timestamp, _>(*value),
+                ModelValue::List(_) | ModelValue::Map(_) => query.bind::<Text, _>(value.to_json().to_string()),
+                ModelValue::Null => query.bind::<Nullable<Text>, _>(None::<String>),
+            };
+        }
+        query
+    }};
+}
+
+fn boxed_pg(sql: &str, values: &[ModelValue]) -> BoxedSqlQuery<'static, Pg, SqlQuery> {
+    bind_values!(diesel::sql_query(sql.to_string()).into_boxed::<Pg>(), values)
+}
+
+fn boxed_sqlite(sql: &str, values: &[ModelValue]) -> BoxedSqlQuery<'static, Sqlite, SqlQuery> {
+    bind_values!(diesel::sql_query(sql.to_string()).into_boxed::<Sqlite>(), values)
+}
+
+/// Segnaposto del parametro per la scrittura di un valore.
+///
+/// `Null` viene inserito come letterale, i valori strutturati vengono convertiti in JSON su PostgreSQL.
+fn write_placeholder(dialect: SqlDialect, value: &ModelValue, index: &mut usize) -> String {
+    if *value == ModelValue::Null {
+        return "NULL".to_string();
+    }
+    *index += 1;
+    match (dialect, value) {
+        (SqlDialect::PostgreSQL, ModelValue::List(_) | ModelValue::Map(_)) => format!("CAST(${} AS JSON)", index),
+        (SqlDialect::PostgreSQL, _) => format!("${}", index),
+        (SqlDialect::SQLite, _) => "?".to_string(),
+    }
+}
+
+/// Valori non nulli, nell'ordine dei segnaposto generati da `write_placeholder`.
+fn bound_values(values: &[ModelValue]) -> Vec<ModelValue> {
+    values.iter().filter(|value| **value != ModelValue::Null).cloned().collect()
+}
+
+/// Espressione che restituisce la riga come oggetto JSON.
+fn json_projection<T: ArxModel>(dialect: SqlDialect) -> String {
+    match dialect {
+        SqlDialect::PostgreSQL => format!("row_to_json({})::text AS row", T::TABLE_NAME),
+        SqlDialect::SQLite => {
+            let fields: Vec<String> = T::field_names().iter().map(|field| format!("'{}', {}", field, field)).collect();
+            format!("json_object({}) AS row", fields.join(", "))
+        }
+    }
+}
+
+/// Converte una riga letta come JSON nei valori dei campi.
+fn parse_json<T: ArxModel>(row: &str) -> Result<HashMap<String, ModelValue>, CrudError> {
+    let json: serde_json::Value = serde_json::from_str(row)
+        .map_err(|e| CrudError::StorageError(format!("Riga non valida per {}: {}", T::TABLE_NAME, e)))?;
+    match ModelValue::from_json(json) {
+        ModelValue::Map(values) => Ok(values.into_iter().collect()),
+        other => Err(CrudError::StorageError(format!("Riga non valida per {}: {}", T::TABLE_NAME, other))),
+    }
+}
+
+/// Ricostruisce il modello da una riga letta come JSON.
+fn parse_row<T: ArxModel>(row: JsonRow) -> Result<T, CrudError> {
+    let values = parse_json::<T>(&row.row)?;
+    T::from_values(&values).map_err(|e| CrudError::StorageError(format!("{}: {}", T::TABLE_NAME, e)))
+}
+
+/// Indica se l'ID del record viene assegnato dal database (`IdStrategy::AutoIncrement` e ID non impostato).
+fn assigned_by_database<T: ArxModel>(item: &T) -> bool {
+    T::ID_STRATEGY == IdStrategy::AutoIncrement && item.id().is_unset()
+}
+
+/// Istruzione `INSERT` per uno o più record del modello, con i parametri nell'ordine dei segnaposto.
+///
+/// Gli ID assegnati dal database (colonna `AUTOINCREMENT` o `IDENTITY`, vedi `migrations::create_table_sql`)
+/// vengono restituiti da `RETURNING`: con un solo record la colonna `id` viene omessa, con più record
+/// (solo PostgreSQL, SQLite non supporta `DEFAULT` in `VALUES`) viene utilizzato `DEFAULT`.
+fn insert_statement<T: ArxModel>(dialect: SqlDialect, items: &[T]) -> (String, Vec<ModelValue>) {
+    let omit_id = items.len() == 1 && assigned_by_database(&items[0]);
+    let mut columns = Vec::new();
+    let mut index = 0;
+    let mut params = Vec::new();
+    let mut tuples = Vec::new();
+    for item in items {
+        let assign_id = assigned_by_database(item);
+        let mut placeholders = Vec::new();
+        for (column, value) in item.to_values() {
+            if tuples.is_empty() && !(omit_id && column == "id") {
+                columns.push(column);
+            }
+            match column {
+                "id" if omit_id => {}
+                "id" if assign_id => placeholders.push("DEFAULT".to_string()),
+                _ => {
+                    placeholders.push(write_placeholder(dialect, &value, &mut index));
+                    if value != ModelValue::Null {
+                        params.push(value);
+                    }
+                }
+            }
+        }
+        tuples.push(format!("({})", placeholders.join(", ")));
+    }
+    let returning = match dialect {
+        SqlDialect::PostgreSQL => "json_build_object('id', id)::text AS row",
+        SqlDialect::SQLite => "json_object('id', id) AS row",
+    };
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES {} RETURNING {}",
+        T::TABLE_NAME,
+        columns.join(", "),
+        tuples.join(", "),
+        returning
+    );
+    (sql, params)
+}
+
+/// Imposta sul record l'ID restituito da `RETURNING`.
+fn set_returned_id<T: ArxModel>(item: &mut T, row: &JsonRow) -> Result<(), CrudError> {
+    let values = parse_json::<T>(&row.row)?;
+    let id = field_value::<T::Id>(&values, "id").map_err(|e| CrudError::StorageError(format!("{}: {}", T::TABLE_NAME, e)))?;
+    item.set_id(id);
+    Ok(())
+}
+
+/// Allinea la sequenza `IDENTITY` di PostgreSQL all'ID esplicito più alto inserito, così che gli ID
+/// assegnati in seguito dal database non lo riutilizzino. Su SQLite `AUTOINCREMENT` ne tiene già conto.
+///
+/// Le tabelle senza colonna `IDENTITY` (es. create prima di `arx db migrate`) vengono ignorate.
+fn advance_identity<T: ArxModel>(connection: &mut DbConnection, last: u64) -> Result<(), CrudError> {
+    if let DbConnection::Postgres(conn) = connection {
+        let sql = format!(
+            "SELECT setval(seq::regclass, GREATEST(nextval(seq::regclass), $1)) \
+             FROM pg_get_serial_sequence('{}', 'id') AS seq WHERE seq IS NOT NULL",
+            T::TABLE_NAME
+        );
+        diesel::sql_query(sql).bind::<BigInt, _>(last as i64).execute(conn).map_err(storage_error)?;
+    }
+    Ok(())
+}
+
+/// ID esplicito più alto tra i record con `IdStrategy::AutoIncrement`, `None` se sono tutti assegnati dal database.
+fn explicit_sequence<T: ArxModel>(items: &[T]) -> Option<u64> {
+    if T::ID_STRATEGY != IdStrategy::AutoIncrement {
+        return None;
+    }
+    items.iter().filter(|item| !item.id().is_unset()).filter_map(|item| item.id().to_sequence()).max()
+}
+
+/// Esegue l'`INSERT` del record sulla connessione SQL, impostando l'ID assegnato dal database.
+fn insert_row<T: ArxModel>(connection: &mut DbConnection, item: &mut T) -> Result<(), CrudError> {
+    let items = std::slice::from_mut(item);
+    let result = match connection {
+        DbConnection::Postgres(conn) => {
+            let (sql, params) = insert_statement(SqlDialect::PostgreSQL, items);
+            boxed_pg(&sql, &params).get_result::<JsonRow>(conn)
+        }
+        DbConnection::SQLite(conn) => {
+            let (sql, params) = insert_statement(SqlDialect::SQLite, items);
+            boxed_sqlite(&sql, &params).get_result::<JsonRow>(conn)
+        }
+        DbConnection::MongoDB(_) => unreachable!("scrittura MongoDB delegata a mongo_store"),
+    };
+    let item = &mut items[0];
+    let row = result.map_err(|e| write_error::<T>(e, &item.id()))?;
+
+    match explicit_sequence(std::slice::from_ref(item)) {
+        Some(last) => advance_identity::<T>(connection, last),
+        None if assigned_by_database(item) => set_returned_id(item, &row),
+        None => Ok(()),
+    }
+}
+
+/// Inserisce un nuovo record nella tabella del modello.
+///
+/// Con `IdStrategy::AutoIncrement` e ID non impostato, l'ID viene assegnato dal database
+/// (`AUTOINCREMENT` su SQLite, `IDENTITY` su PostgreSQL) e impostato sul record.
+/// Un ID già esistente restituisce `CrudError::Conflict`.
+pub(crate) fn insert<T: ArxModel>(item: &mut T) -> Result<(), CrudError> {
+    if let Some(client) = mongo_client()? {
+        return mongo_store::insert(&client, item);
+    }
+    with_connection(|connection| insert_row(connection, item))
+}
+
+/// Esegue `operation` in un savepoint della transazione attiva: in caso di errore vengono annullate
+/// solo le sue scritture e la transazione resta utilizzabile (necessario su PostgreSQL).
+fn savepoint<R>(
+    connection: &mut DbConnection,
+    operation: impl FnOnce(&mut DbConnection) -> Result<R, CrudError>,
+) -> Result<R, CrudError> {
+    begin(connection).map_err(storage_error)?;
+    let result = operation(connection);
+    match (finish(connection, result.is_ok()), result) {
+        (Ok(()), result) => result,
+        (Err(e), Ok(_)) => Err(storage_error(e)),
+        (Err(e), Err(error)) => {
+            error!("Rollback del savepoint fallito: {}", e);
+            Err(error)
+        }
+    }
+}
+
+/// Inserisce più record nella tabella del modello in un'unica transazione.
+///
+/// - PostgreSQL: `INSERT` su più righe, fino a `MAX_PG_PARAMETERS` parametri per istruzione. Se l'istruzione
+///   fallisce, le sue righe vengono inserite singolarmente per individuare quelle non valide.
+/// - SQLite: Un'istruzione per ogni riga, nella stessa transazione.
+/// - MongoDB: `insert_many` non ordinato (vedi `mongo_store::insert_many`).
+///
+/// # Ritorna
+/// L'esito di ogni record nell'ordine di `items`, oppure un errore se la transazione non può essere eseguita.
+pub(crate) fn insert_batch<T: ArxModel>(items: &mut [T]) -> Result<Vec<Result<(), CrudError>>, CrudError> {
+    if let Some(client) = mongo_client()? {
+        return mongo_store::insert_many(&client, items);
+    }
+    transaction(|| {
+        with_connection(|connection| {
+            if !matches!(connection, DbConnection::Postgres(_)) {
+                return Ok(items.iter_mut().map(|item| insert_row(connection, item)).collect());
+            }
+
+            let rows_per_statement = (MAX_PG_PARAMETERS / T::field_names().len().max(1)).max(1);
+            let mut outcomes = Vec::with_capacity(items.len());
+            for chunk in items.chunks_mut(rows_per_statement) {
+                let (sql, params) = insert_statement(SqlDialect::PostgreSQL, chunk);
+                let explicit = explicit_sequence(chunk);
+                let written = savepoint(connection, |connection| match connection {
+                    DbConnection::Postgres(conn) => boxed_pg(&sql, &params).load::<JsonRow>(conn).map_err(storage_error),
+                    _ => unreachable!("INSERT su più righe solo per PostgreSQL"),
+                });
+                match written {
+                    Ok(rows) => {
+                        // `RETURNING` restituisce le righe nell'ordine di `VALUES`
+                        for (item, row) in chunk.iter_mut().zip(&rows) {
+                            if assigned_by_database(item) {
+                                set_returned_id(item, row)?;
+                            }
+                        }
+                        if let Some(last) = explicit {
+                            advance_identity::<T>(connection, last)?;
+                        }
+                        outcomes.extend(chunk.iter().map(|_| Ok(())));
+                    }
+                    Err(e) => {
+                        warn!("INSERT su più righe fallito per {}, inserimento per singola riga: {}", T::TABLE_NAME, e);
+                        for item in chunk.iter_mut() {
+                            outcomes.push(savepoint(connection, |connection| insert_row(connection, item)));
+                        }
+                    }
+                }
+            }
+            Ok(outcomes)
+        })
+    })
+}
+
+/// Legge un record in base al suo ID, `None` se non esiste.
+pub(crate) fn select<T: ArxModel>(id: &T::Id) -> Result<Option<T>, CrudError> {
+    if let Some(client) = mongo_client()? {
+        return mongo_store::select::<T>(&client, id);
+    }
+    let query = Query::new().eq("id", id.clone()).include_revoked().limit(1);
+    Ok(self::query::<T>(&query)?.items.into_iter().next())
+}
+
+/// Sovrascrive un record esistente.
+///
+/// Con `expected` la scrittura avviene solo se la colonna `version` ha il valore indicato.
+///
+/// # Ritorna
+/// `false` se nessun record ha l'ID del modello (e la versione attesa).
+pub(crate) fn update<T: ArxModel>(item: &T, expected: Option<u64>) -> Result<bool, CrudError> {
+    if let Some(client) = mongo_client()? {
+        return mongo_store::update(&client, item, expected);
+    }
+    let (columns, values): (Vec<&str>, Vec<ModelValue>) =
+        item.to_values().into_iter().filter(|(column, _)| *column != "id").unzip();
+    let mut conditions = vec![("id", item.id().to_model_value())];
+    if let Some(version) = expected {
+        conditions.push(("version", version.to_model_value()));
+    }
+    let sql = |dialect| {
+        let mut index = 0;
+        let assignments: Vec<String> = columns
+            .iter()
+            .zip(&values)
+            .map(|(column, value)| format!("{} = {}", column, write_placeholder(dialect, value, &mut index)))
+            .collect();
+        let conditions: Vec<String> = conditions
+            .iter()
+            .map(|(column, value)| format!("{} = {}", column, write_placeholder(dialect, value, &mut index)))
+            .collect();
+        format!("UPDATE {} SET {} WHERE {}", T::TABLE_NAME, assignments.join(", "), conditions.join(" AND "))
+    };
+    let written: Vec<ModelValue> = values.iter().chain(conditions.iter().map(|(_, value)| value)).cloned().collect();
+    let params = bound_values(&written);
+
+    let updated = with_connection(|connection| {
+        let result = match connection {
+            DbConnection::Postgres(conn) => boxed_pg(&sql(SqlDialect::PostgreSQL), &params).execute(conn),
+            DbConnection::SQLite(conn) => boxed_sqlite(&sql(SqlDialect::SQLite), &params).execute(conn),
+            DbConnection::MongoDB(_) => unreachable!("scrittura MongoDB delegata a mongo_store"),
+        };
+        result.map(|updated| updated > 0).map_err(storage_error)
+    })?;
+    Ok(updated)
+}
+
+/// Elimina un record in base al suo ID.
+///
+/// # Ritorna
+/// `false` se il record non esiste.
+pub(crate) fn delete<T: ArxModel>(id: &T::Id) -> Result<bool, CrudError> {
+    if let Some(client) = mongo_client()? {
+        return mongo_store::delete::<T>(&client, id);
+    }
+    let params = [id.to_model_value()];
+    let deleted = with_connection(|connection| {
+        let result = match connection {
+            DbConnection::Postgres(conn) => {
+                boxed_pg(&format!("DELETE FROM {} WHERE id = $1", T::TABLE_NAME), &params).execute(conn)
+            }
+            DbConnection::SQLite(conn) => {
+                boxed_sqlite(&format!("DELETE FROM {} WHERE id = ?", T::TABLE_NAME), &params).execute(conn)
+            }
+            DbConnection::MongoDB(_) => unreachable!("scrittura MongoDB delegata a mongo_store"),
+        };
+        result.map(|deleted| deleted > 0).map_err(storage_error)
+    })?;
+    Ok(deleted)
+}
+
+/// Esegue una query sulla tabella del modello.
+///
+/// La query deve essere già validata con `Query::validate` sui campi del modello.
+pub(crate) fn query<T: ArxModel>(query: &Query) -> Result<Page<T>, CrudError> {
+    let text_fields = T::text_fields();
+    if let Some(client) = mongo_client()? {
+        return mongo_store::query::<T>(&client, query);
+    }
+    let (rows, total) = with_connection(|connection| match connection {
+        DbConnection::Postgres(conn) => {
+            let sql = query.to_sql(T::TABLE_NAME, &text_fields, SqlDialect::PostgreSQL);
+            let select = sql.select.replacen("SELECT *", &format!("SELECT {}", json_projection::<T>(SqlDialect::PostgreSQL)), 1);
+            let rows = boxed_pg(&select, &sql.params).load::<JsonRow>(conn).map_err(storage_error)?;
+            let count = boxed_pg(&sql.count, &sql.count_params).get_result::<CountRow>(conn).map_err(storage_error)?;
+            Ok((rows, count.total))
+        }
+        DbConnection::SQLite(conn) => {
+            let sql = query.to_sql(T::TABLE_NAME, &text_fields, SqlDialect::SQLite);
+            let select = sql.select.replacen("SELECT *", &format!("SELECT {}", json_projection::<T>(SqlDialect::SQLite)), 1);
+            let rows = boxed_sqlite(&select, &sql.params).load::<JsonRow>(conn).map_err(storage_error)?;
+            let count = boxed_sqlite(&sql.count, &sql.count_params).get_result::<CountRow>(conn).map_err(storage_error)?;
+            Ok((rows, count.total))
+        }
+        DbConnection::MongoDB(_) => unreachable!("query MongoDB delegata a mongo_store"),
+    })?;
+
+    let items = rows.into_iter().map(parse_row::<T>).collect::<Result<Vec<T>, CrudError>>()?;
+    Ok(query.into_page(items, total as usize))
+}
@@ -0,0 +1,200 @@
+//! Modulo per la generazione delle chiavi primarie dei modelli CRUD.
+//!
+//! Ogni modello dichiara la strategia con `#[arx(id = "...")]` e il tipo della chiave con il tipo del campo `id`:
+//! - `auto` (default per `u32` e `u64`): ID incrementale, assegnato dal database con `AllocType::Database`
+//!   e `AllocType::Cached` (colonna `AUTOINCREMENT` su SQLite e `IDENTITY` su PostgreSQL, creata da `arx db migrate`),
+//!   oppure da un contatore atomico per modello con `AllocType::InMemory`.
+//! - `uuid_v7` (default per `Uuid`): UUID versione 7, ordinabile per data di creazione.
+//! - `snowflake`: ID a 64 bit (`u64`) composto da timestamp, nodo e sequenza.
+//! - `manual`: ID sempre fornito dal chiamante, anche se 0.
+//!
+//! La chiave viene generata da `create_model` solo se l'ID del record non è impostato (0 o UUID nil),
+//! un ID esplicito già esistente restituisce `CrudError::Conflict`.
+//!
+//! ### Snowflake:
+//! 41 bit di millisecondi dal 2024-01-01, 10 bit di nodo (variabile d'ambiente `ARX_NODE_ID`, di default 0)
+//! e 12 bit di sequenza per millisecondo.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use log::warn;
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+
+use crate::crud::crud_ops::{AllocType, ArxModel, CrudError};
+use crate::crud::model_value::{FromModelValue, ToModelValue};
+
+/// Epoca degli ID Snowflake (2024-01-01T00:00:00Z) in millisecondi.
+const SNOWFLAKE_EPOCH_MS: i64 = 1_704_067_200_000;
+const SNOWFLAKE_NODE_BITS: u32 = 10;
+const SNOWFLAKE_SEQUENCE_BITS: u32 = 12;
+
+/// Strategia di generazione della chiave primaria di un modello.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdStrategy {
+    Manual,
+    AutoIncrement,
+    UuidV7,
+    Snowflake,
+}
+
+/// Tipo utilizzabile come chiave primaria di un modello (`u32`, `u64` o `Uuid`).
+pub trait ModelKey:
+    Clone + Eq + Hash + Debug + Display + Send + Sync + ToModelValue + FromModelValue + 'static
+{
+    /// Indica se la chiave non è impostata e va generata alla creazione.
+    fn is_unset(&self) -> bool;
+    /// Chiave da un valore incrementale o Snowflake, `None` se il tipo non lo supporta.
+    fn from_sequence(value: u64) -> Option<Self>;
+    /// Valore numerico della chiave, `None` per le chiavi non numeriche.
+    fn to_sequence(&self) -> Option<u64>;
+    /// Chiave da un UUID, `None` se il tipo non lo supporta.
+    fn from_uuid(_uuid: Uuid) -> Option<Self> {
+        None
+    }
+}
+
+impl ModelKey for u32 {
+    fn is_unset(&self) -> bool {
+        *self == 0
+    }
+
+    fn from_sequence(value: u64) -> Option<Self> {
+        u32::try_from(value).ok()
+    }
+
+    fn to_sequence(&self) -> Option<u64> {
+        Some(*self as u64)
+    }
+}
+
+impl ModelKey for u64 {
+    fn is_unset(&self) -> bool {
+        *self == 0
+    }
+
+    fn from_sequence(value: u64) -> Option<Self> {
+        Some(value)
+    }
+
+    fn to_sequence(&self) -> Option<u64> {
+        Some(*self)
+    }
+}
+
+impl ModelKey for Uuid {
+    fn is_unset(&self) -> bool {
+        self.is_nil()
+    }
+
+    fn from_sequence(_value: u64) -> Option<Self> {
+        None
+    }
+
+    fn to_sequence(&self) -> Option<u64> {
+        None
+    }
+
+    fn from_uuid(uuid: Uuid) -> Option<Self> {
+        Some(uuid)
+    }
+}
+
+/// Ultimo ID assegnato dal contatore in memoria di ogni modello.
+static SEQUENCES: Lazy<Mutex<HashMap<&'static str, u64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Stato del generatore Snowflake: ultimo millisecondo e sequenza.
+static SNOWFLAKE: Lazy<Mutex<(i64, u64)>> = Lazy::new(|| Mutex::new((0, 0)));
+
+/// Nodo degli ID Snowflake, letto da `ARX_NODE_ID`.
+static NODE_ID: Lazy<u64> = Lazy::new(|| {
+    let max = (1 << SNOWFLAKE_NODE_BITS) - 1;
+    match std::env::var("ARX_NODE_ID").ok().map(|node| node.parse::<u64>()) {
+        Some(Ok(node)) if node <= max => node,
+        Some(_) => {
+            warn!("ARX_NODE_ID non valido, atteso un intero tra 0 e {}: utilizzato il nodo 0", max);
+            0
+        }
+        None => 0,
+    }
+});
+
+/// Genera un UUID versione 7.
+pub fn uuid_v7() -> Uuid {
+    Uuid::now_v7()
+}
+
+/// Genera un ID Snowflake a 64 bit, univoco per nodo e crescente nel tempo.
+pub fn snowflake() -> u64 {
+    let mut state = SNOWFLAKE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut now = Utc::now().timestamp_millis() - SNOWFLAKE_EPOCH_MS;
+    if now <= state.0 {
+        // Stesso millisecondo (o orologio tornato indietro): si incrementa la sequenza
+        now = state.0;
+        state.1 = (state.1 + 1) & ((1 << SNOWFLAKE_SEQUENCE_BITS) - 1);
+        if state.1 == 0 {
+            // Sequenza esaurita, si passa al millisecondo successivo
+            now += 1;
+            while Utc::now().timestamp_millis() - SNOWFLAKE_EPOCH_MS < now {
+                std::hint::spin_loop();
+            }
+        }
+    } else {
+        state.1 = 0;
+    }
+    state.0 = now;
+    ((now as u64) << (SNOWFLAKE_NODE_BITS + SNOWFLAKE_SEQUENCE_BITS))
+        | (*NODE_ID << SNOWFLAKE_SEQUENCE_BITS)
+        | state.1
+}
+
+/// Registra un ID esplicito, così che il contatore in memoria non lo assegni di nuovo.
+pub(crate) fn observe<T: ArxModel>(id: &T::Id) {
+    if let (Some(value), Ok(mut sequences)) = (id.to_sequence(), SEQUENCES.lock()) {
+        let last = sequences.entry(T::TABLE_NAME).or_insert(0);
+        *last = (*last).max(value);
+    }
+}
+
+/// Prossimo valore del contatore in memoria del modello.
+fn next_sequence<T: ArxModel>() -> Result<u64, CrudError> {
+    let mut sequences = SEQUENCES
+        .lock()
+        .map_err(|e| CrudError::StorageError(format!("Errore di lock sui contatori degli ID: {}", e)))?;
+    let last = sequences.entry(T::TABLE_NAME).or_insert(0);
+    *last += 1;
+    Ok(*last)
+}
+
+/// Genera la chiave del record se non impostata, secondo la strategia del modello.
+///
+/// Con `AutoIncrement` su database la chiave resta non impostata e viene assegnata dallo storage.
+pub(crate) fn assign<T: ArxModel>(item: &mut T) -> Result<(), CrudError> {
+    if !item.id().is_unset() {
+        return Ok(());
+    }
+    let id = match T::ID_STRATEGY {
+        IdStrategy::Manual => return Ok(()),
+        IdStrategy::AutoIncrement => match T::default_store() {
+            AllocType::InMemory => T::Id::from_sequence(next_sequence::<T>()?),
+            AllocType::Database | AllocType::Cached => return Ok(()),
+        },
+        IdStrategy::UuidV7 => T::Id::from_uuid(uuid_v7()),
+        IdStrategy::Snowflake => T::Id::from_sequence(snowflake()),
+    };
+    match id {
+        Some(id) => {
+            item.set_id(id);
+            Ok(())
+        }
+        None => Err(CrudError::StorageError(format!(
+            "Strategia {:?} non supportata dal tipo della chiave di {}",
+            T::ID_STRATEGY,
+            T::TABLE_NAME
+        ))),
+    }
+}
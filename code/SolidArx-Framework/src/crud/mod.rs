@@ -1,9 +1,23 @@
+pub mod async_ops;
+pub mod audit;
+pub mod batch;
 pub mod crud_ops;
+pub mod data_transfer;
+pub mod database_store;
+pub mod id_generator;
+pub mod model_cache;
+pub mod model_value;
 pub mod models;
+pub mod mongo_store;
+pub mod query;
+pub mod relations;
+pub mod revocation;
+pub mod unit_of_work;
+pub mod validation;
+pub mod watch;
 
 pub fn initialize() -> Result<(), String> {
     // Logica di inizializzazione per CRUD
-    println!("Initializing CRUD module...");
+    log::info!("Initializing CRUD module...");
     Ok(())
 }
-
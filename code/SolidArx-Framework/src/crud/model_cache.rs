@@ -0,0 +1,412 @@
+//! Modulo per la cache in memoria dei modelli CRUD con `AllocType::Cached`.
+//!
+//! I modelli con `AllocType::Cached` sono persistiti su database tramite `database_store.rs`,
+//! con una cache limitata in memoria davanti allo storage per le letture frequenti
+//! (es. `Configuration` e `Device`).
+//!
+//! ### Politiche di scrittura:
+//! - `WriteThrough`: Ogni scrittura viene eseguita subito sul database. `Update` e `Delete` invalidano
+//!   il record in cache, che viene ricaricato alla lettura successiva.
+//! - `WriteBehind`: Le scritture aggiornano la cache e vengono accodate, per essere eseguite sul database
+//!   con `flush`, automaticamente quando le scritture in attesa raggiungono la capacità della cache
+//!   oppure prima di ogni `List` e `Search`. Le scritture non ancora eseguite vanno perse al riavvio.
+//!
+//! ### Evizione:
+//! - LRU: Superata la capacità viene rimosso il record utilizzato meno di recente.
+//! - TTL: I record più vecchi della durata configurata vengono ricaricati dal database.
+//!
+//! I record con scritture in attesa non vengono mai rimossi dalla cache fino al `flush`.
+//!
+//! ### Transazioni:
+//! Durante una transazione di `unit_of_work.rs` tutte le scritture vengono eseguite subito sul database,
+//! dopo il `flush` di quelle in attesa. Se la transazione viene annullata, le scritture in attesa tornano
+//! in coda e restano in cache solo i record con scritture in attesa.
+//!
+//! La configurazione viene dichiarata sul modello con `#[arx(store = "cached", cache = "...", capacity = ..., ttl = ...)]`,
+//! le statistiche di hit e miss sono disponibili con `stats`.
+
+use std::collections::HashMap;
+use std::sync::MutexGuard;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+
+use crate::crud::crud_ops::{ArxModel, CrudError};
+use crate::crud::database_store;
+use crate::crud::id_generator::{IdStrategy, ModelKey};
+use crate::crud::query::{Page, Query};
+use crate::crud::unit_of_work;
+
+/// Politica di scrittura della cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    WriteThrough,
+    WriteBehind,
+}
+
+/// Configurazione della cache di un modello.
+///
+/// # Campi
+/// - `policy`: Politica di scrittura.
+/// - `capacity`: Numero massimo di record in cache.
+/// - `ttl`: Durata massima di un record in cache, `None` per non applicare scadenze.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheConfig {
+    pub policy: CachePolicy,
+    pub capacity: usize,
+    pub ttl: Option<Duration>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            policy: CachePolicy::WriteThrough,
+            capacity: 256,
+            ttl: Some(Duration::from_secs(300)),
+        }
+    }
+}
+
+/// Statistiche della cache di un modello.
+///
+/// # Campi
+/// - `hits`: Letture servite dalla cache.
+/// - `misses`: Letture eseguite sul database.
+/// - `evictions`: Record rimossi per capacità o scadenza del TTL.
+/// - `entries`: Record attualmente in cache.
+/// - `pending_writes`: Scritture in attesa di `flush` (solo `WriteBehind`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub pending_writes: usize,
+}
+
+impl CacheStats {
+    /// Percentuale di letture servite dalla cache, tra 0 e 1.
+    pub fn hit_ratio(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+/// Scrittura accodata con la politica `WriteBehind`.
+#[derive(Debug, Clone)]
+enum PendingWrite<T: ArxModel> {
+    Insert(T),
+    Update(T),
+    Delete(T::Id),
+}
+
+impl<T: ArxModel> PendingWrite<T> {
+    fn id(&self) -> T::Id {
+        match self {
+            PendingWrite::Insert(item) | PendingWrite::Update(item) => item.id(),
+            PendingWrite::Delete(id) => id.clone(),
+        }
+    }
+}
+
+/// Record in cache con le informazioni per l'evizione.
+#[derive(Debug, Clone)]
+struct CacheEntry<T> {
+    item: T,
+    loaded_at: Instant,
+    last_used: u64,
+}
+
+/// Cache di un modello, ottenuta con `ArxModel::model_cache`.
+#[derive(Debug)]
+pub struct ModelCache<T: ArxModel> {
+    config: CacheConfig,
+    entries: HashMap<T::Id, CacheEntry<T>>,
+    pending: Vec<PendingWrite<T>>,
+    /// Contatore degli accessi, utilizzato per l'ordinamento LRU.
+    clock: u64,
+    stats: CacheStats,
+}
+
+impl<T: ArxModel> ModelCache<T> {
+    /// Crea una cache vuota con la configurazione indicata.
+    pub fn new(config: CacheConfig) -> Self {
+        ModelCache {
+            config,
+            entries: HashMap::new(),
+            pending: Vec::new(),
+            clock: 0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Indica se il record ha scritture in attesa.
+    fn is_dirty(&self, id: &T::Id) -> bool {
+        self.pending.iter().any(|write| write.id() == *id)
+    }
+
+    /// Indica se l'ultima scrittura in attesa del record è un'eliminazione.
+    fn is_pending_delete(&self, id: &T::Id) -> bool {
+        matches!(self.pending.iter().rev().find(|write| write.id() == *id), Some(PendingWrite::Delete(_)))
+    }
+
+    /// Legge un record dalla cache, rimuovendolo se scaduto.
+    fn get(&mut self, id: &T::Id) -> Option<T> {
+        let expired = match (self.entries.get(id), self.config.ttl) {
+            (Some(entry), Some(ttl)) => entry.loaded_at.elapsed() > ttl && !self.is_dirty(id),
+            _ => false,
+        };
+        if expired {
+            self.entries.remove(id);
+            self.stats.evictions += 1;
+        }
+
+        self.clock += 1;
+        match self.entries.get_mut(id) {
+            Some(entry) => {
+                entry.last_used = self.clock;
+                self.stats.hits += 1;
+                Some(entry.item.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Inserisce o sostituisce un record, rimuovendo i meno recenti oltre la capacità.
+    fn put(&mut self, item: T) {
+        self.clock += 1;
+        let entry = CacheEntry { item, loaded_at: Instant::now(), last_used: self.clock };
+        self.entries.insert(entry.item.id(), entry);
+
+        while self.entries.len() > self.config.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .filter(|(id, _)| !self.is_dirty(id))
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(id, _)| id.clone());
+            match oldest {
+                Some(id) => {
+                    self.entries.remove(&id);
+                    self.stats.evictions += 1;
+                }
+                // Tutti i record hanno scritture in attesa, vengono rimossi dopo il `flush`
+                None => break,
+            }
+        }
+    }
+
+    /// Esegue sul database le scritture in attesa, nell'ordine in cui sono state accodate.
+    ///
+    /// In caso di errore le scritture non eseguite restano in coda.
+    fn flush(&mut self) -> Result<usize, CrudError> {
+        let mut pending = std::mem::take(&mut self.pending);
+        let total = pending.len();
+        let mut failed = None;
+        for (index, write) in pending.iter().enumerate() {
+            let result = match write {
+                PendingWrite::Insert(item) => database_store::insert(&mut item.clone()),
+                PendingWrite::Update(item) => database_store::update(item, None).map(|_| ()),
+                PendingWrite::Delete(id) => database_store::delete::<T>(id).map(|_| ()),
+            };
+            if let Err(e) = result {
+                warn!("Flush della cache di {} interrotto: {}", T::TABLE_NAME, e);
+                failed = Some((index, e));
+                break;
+            }
+        }
+        if let Some((index, _)) = &failed {
+            self.pending = pending.split_off(*index);
+        }
+        if !pending.is_empty() && unit_of_work::is_active() {
+            // Le scritture eseguite tornano in coda se la transazione viene annullata
+            unit_of_work::record_undo(move || {
+                if let Ok(mut cache) = lock_cache::<T>() {
+                    cache.pending.splice(0..0, pending);
+                }
+            });
+        }
+        if let Some((_, e)) = failed {
+            return Err(e);
+        }
+        if total > 0 {
+            info!("Eseguite {} scritture in attesa per {}", total, T::TABLE_NAME);
+        }
+        Ok(total)
+    }
+
+    /// Politica delle scritture, `WriteThrough` durante una transazione dopo il `flush` delle scritture in attesa.
+    fn write_policy(&mut self) -> Result<CachePolicy, CrudError> {
+        if !unit_of_work::is_active() {
+            return Ok(self.config.policy);
+        }
+        // In caso di rollback restano in cache solo i record con scritture in attesa, come prima del flush
+        let dirty: HashMap<T::Id, CacheEntry<T>> = self
+            .entries
+            .iter()
+            .filter(|(id, _)| self.is_dirty(id))
+            .map(|(id, entry)| (id.clone(), entry.clone()))
+            .collect();
+        unit_of_work::record_undo(move || {
+            if let Ok(mut cache) = lock_cache::<T>() {
+                cache.entries = dirty;
+            }
+        });
+        self.flush()?;
+        Ok(CachePolicy::WriteThrough)
+    }
+
+    /// Accoda una scrittura, eseguendo il `flush` al raggiungimento della capacità.
+    fn enqueue(&mut self, write: PendingWrite<T>) -> Result<(), CrudError> {
+        self.pending.push(write);
+        if self.pending.len() >= self.config.capacity {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Legge un record dalla cache o dal database, senza considerare le eliminazioni in attesa.
+    fn load(&mut self, id: &T::Id) -> Result<Option<T>, CrudError> {
+        if let Some(item) = self.get(id) {
+            return Ok(Some(item));
+        }
+        if self.is_pending_delete(id) {
+            return Ok(None);
+        }
+        let item = database_store::select::<T>(id)?;
+        if let Some(item) = &item {
+            self.put(item.clone());
+        }
+        Ok(item)
+    }
+}
+
+/// Acquisisce il lock sulla cache del modello.
+fn lock_cache<T: ArxModel>() -> Result<MutexGuard<'static, ModelCache<T>>, CrudError> {
+    T::model_cache()
+        .lock()
+        .map_err(|e| CrudError::StorageError(format!("Errore di lock sulla cache di {}: {}", T::TABLE_NAME, e)))
+}
+
+/// Inserisce un nuovo record.
+///
+/// Gli inserimenti con ID assegnato dal database vengono eseguiti subito anche con `WriteBehind`,
+/// dopo il `flush` delle scritture in attesa.
+pub(crate) fn insert<T: ArxModel>(item: &mut T) -> Result<(), CrudError> {
+    let mut cache = lock_cache::<T>()?;
+    let assigned_by_storage = item.id().is_unset() && T::ID_STRATEGY == IdStrategy::AutoIncrement;
+    match cache.write_policy()? {
+        CachePolicy::WriteThrough => database_store::insert(item)?,
+        CachePolicy::WriteBehind if assigned_by_storage => {
+            cache.flush()?;
+            database_store::insert(item)?;
+        }
+        CachePolicy::WriteBehind => {
+            if cache.load(&item.id())?.is_some() {
+                return Err(CrudError::conflict::<T>(&item.id()));
+            }
+            cache.enqueue(PendingWrite::Insert(item.clone()))?;
+        }
+    }
+    cache.put(item.clone());
+    Ok(())
+}
+
+/// Legge un record dalla cache o, in caso di miss, dal database.
+pub(crate) fn select<T: ArxModel>(id: &T::Id) -> Result<Option<T>, CrudError> {
+    lock_cache::<T>()?.load(id)
+}
+
+/// Sovrascrive un record esistente.
+///
+/// Con `expected` la scrittura avviene solo se il record ha la versione indicata: con `WriteBehind`
+/// viene confrontata con la versione in cache, le scritture accodate non vengono più verificate al `flush`.
+///
+/// # Ritorna
+/// `false` se il record non esiste (o ha una versione diversa).
+pub(crate) fn update<T: ArxModel>(item: &T, expected: Option<u64>) -> Result<bool, CrudError> {
+    let mut cache = lock_cache::<T>()?;
+    match cache.write_policy()? {
+        CachePolicy::WriteThrough => {
+            let updated = database_store::update(item, expected)?;
+            cache.entries.remove(&item.id());
+            Ok(updated)
+        }
+        CachePolicy::WriteBehind => {
+            match cache.load(&item.id())? {
+                Some(current) if expected.is_none_or(|version| current.version() == version) => {}
+                _ => return Ok(false),
+            }
+            cache.put(item.clone());
+            cache.enqueue(PendingWrite::Update(item.clone()))?;
+            Ok(true)
+        }
+    }
+}
+
+/// Elimina un record.
+///
+/// # Ritorna
+/// `false` se il record non esiste.
+pub(crate) fn delete<T: ArxModel>(id: &T::Id) -> Result<bool, CrudError> {
+    let mut cache = lock_cache::<T>()?;
+    match cache.write_policy()? {
+        CachePolicy::WriteThrough => {
+            let deleted = database_store::delete::<T>(id)?;
+            cache.entries.remove(id);
+            Ok(deleted)
+        }
+        CachePolicy::WriteBehind => {
+            if cache.load(id)?.is_none() {
+                return Ok(false);
+            }
+            cache.entries.remove(id);
+            cache.enqueue(PendingWrite::Delete(id.clone()))?;
+            Ok(true)
+        }
+    }
+}
+
+/// Esegue una query sul database, dopo il `flush` delle scritture in attesa.
+///
+/// I risultati delle query non vengono inseriti in cache.
+pub(crate) fn query<T: ArxModel>(query: &Query) -> Result<Page<T>, CrudError> {
+    lock_cache::<T>()?.flush()?;
+    database_store::query::<T>(query)
+}
+
+/// Esegue sul database le scritture in attesa del modello.
+///
+/// # Ritorna
+/// Il numero di scritture eseguite, sempre 0 con la politica `WriteThrough`.
+pub fn flush<T: ArxModel>() -> Result<usize, CrudError> {
+    lock_cache::<T>()?.flush()
+}
+
+/// Restituisce le statistiche della cache del modello.
+pub fn stats<T: ArxModel>() -> CacheStats {
+    match lock_cache::<T>() {
+        Ok(cache) => CacheStats {
+            entries: cache.entries.len(),
+            pending_writes: cache.pending.len(),
+            ..cache.stats.clone()
+        },
+        Err(e) => {
+            warn!("{}", e);
+            CacheStats::default()
+        }
+    }
+}
+
+/// Svuota la cache del modello dopo aver eseguito le scritture in attesa, mantenendo le statistiche.
+pub fn clear<T: ArxModel>() -> Result<(), CrudError> {
+    let mut cache = lock_cache::<T>()?;
+    cache.flush()?;
+    cache.entries.clear();
+    Ok(())
+}
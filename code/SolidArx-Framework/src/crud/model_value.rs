@@ -0,0 +1,430 @@
+//! Modulo per la rappresentazione neutra dei valori dei campi dei modelli.
+//!
+//! `ModelValue` è il formato intermedio tra i campi dei modelli e gli storage (memoria, SQL, MongoDB).
+//! Le implementazioni di `ArxModel` generate da `#[derive(ArxModel)]` convertono ogni campo persistito
+//! tramite `ToModelValue` e lo ricostruiscono tramite `FromModelValue`.
+//!
+//! ### Tipi supportati:
+//! - Numeri interi e decimali, `bool`, `String`, `chrono::NaiveDateTime`, `Uuid` (come testo).
+//! - `Option<T>` e `Vec<T>` dei tipi supportati.
+//! - Enum senza dati tramite la macro `impl_model_value_enum!`, rappresentati come testo.
+//!
+//! Per tipi personalizzati (es. `ProjectMetadata`) è sufficiente implementare i due trait nel file del modello.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use chrono::NaiveDateTime;
+use mongodb::bson::Bson;
+use uuid::Uuid;
+
+/// Formato dei timestamp rappresentati come testo (SQL e MongoDB).
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+/// Valore di un campo di un modello in formato indipendente dallo storage.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Timestamp(NaiveDateTime),
+    List(Vec<ModelValue>),
+    Map(BTreeMap<String, ModelValue>),
+}
+
+impl ModelValue {
+    /// Restituisce il nome del tipo del valore, utilizzato nei messaggi di errore.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ModelValue::Null => "null",
+            ModelValue::Bool(_) => "bool",
+            ModelValue::Int(_) => "int",
+            ModelValue::Float(_) => "float",
+            ModelValue::Text(_) => "text",
+            ModelValue::Timestamp(_) => "timestamp",
+            ModelValue::List(_) => "list",
+            ModelValue::Map(_) => "map",
+        }
+    }
+
+    /// Restituisce il valore come testo, se di tipo `Text`.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            ModelValue::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Confronta due valori dello stesso tipo, utilizzata per filtri e ordinamenti in memoria.
+    ///
+    /// `Null` precede ogni altro valore, interi e decimali sono confrontabili tra loro.
+    /// Restituisce `None` per valori di tipo diverso.
+    pub fn compare(&self, other: &ModelValue) -> Option<Ordering> {
+        match (self, other) {
+            (ModelValue::Null, ModelValue::Null) => Some(Ordering::Equal),
+            (ModelValue::Null, _) => Some(Ordering::Less),
+            (_, ModelValue::Null) => Some(Ordering::Greater),
+            (ModelValue::Bool(a), ModelValue::Bool(b)) => Some(a.cmp(b)),
+            (ModelValue::Int(a), ModelValue::Int(b)) => Some(a.cmp(b)),
+            (ModelValue::Float(a), ModelValue::Float(b)) => a.partial_cmp(b),
+            (ModelValue::Int(a), ModelValue::Float(b)) => (*a as f64).partial_cmp(b),
+            (ModelValue::Float(a), ModelValue::Int(b)) => a.partial_cmp(&(*b as f64)),
+            (ModelValue::Text(a), ModelValue::Text(b)) => Some(a.cmp(b)),
+            (ModelValue::Timestamp(a), ModelValue::Timestamp(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+
+    /// Converte il valore in JSON, utilizzato per le colonne JSON degli storage SQL.
+    ///
+    /// I timestamp vengono rappresentati come testo nel formato `TIMESTAMP_FORMAT`.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            ModelValue::Null => serde_json::Value::Null,
+            ModelValue::Bool(value) => serde_json::Value::from(*value),
+            ModelValue::Int(value) => serde_json::Value::from(*value),
+            ModelValue::Float(value) => serde_json::Value::from(*value),
+            ModelValue::Text(value) => serde_json::Value::from(value.as_str()),
+            ModelValue::Timestamp(value) => serde_json::Value::from(value.format(TIMESTAMP_FORMAT).to_string()),
+            ModelValue::List(values) => serde_json::Value::Array(values.iter().map(ModelValue::to_json).collect()),
+            ModelValue::Map(values) => serde_json::Value::Object(
+                values.iter().map(|(key, value)| (key.clone(), value.to_json())).collect(),
+            ),
+        }
+    }
+
+    /// Converte un valore JSON letto da uno storage SQL.
+    ///
+    /// I timestamp restano testo e vengono convertiti da `FromModelValue` in base al tipo del campo.
+    pub fn from_json(value: serde_json::Value) -> ModelValue {
+        match value {
+            serde_json::Value::Null => ModelValue::Null,
+            serde_json::Value::Bool(value) => ModelValue::Bool(value),
+            serde_json::Value::Number(number) => match number.as_i64() {
+                Some(value) => ModelValue::Int(value),
+                None => ModelValue::Float(number.as_f64().unwrap_or_default()),
+            },
+            serde_json::Value::String(value) => ModelValue::Text(value),
+            serde_json::Value::Array(values) => ModelValue::List(values.into_iter().map(ModelValue::from_json).collect()),
+            serde_json::Value::Object(values) => ModelValue::Map(
+                values.into_iter().map(|(key, value)| (key, ModelValue::from_json(value))).collect(),
+            ),
+        }
+    }
+
+    /// Converte in `List` o `Map` un testo JSON, restituendo il valore invariato negli altri casi.
+    ///
+    /// Alcuni storage (es. SQLite) restituiscono le colonne JSON come testo: va utilizzata
+    /// nelle implementazioni di `FromModelValue` dei tipi strutturati.
+    pub fn into_structured(self) -> ModelValue {
+        match &self {
+            ModelValue::Text(text) if text.starts_with('{') || text.starts_with('[') => {
+                match serde_json::from_str::<serde_json::Value>(text) {
+                    Ok(json) => ModelValue::from_json(json),
+                    Err(_) => self,
+                }
+            }
+            _ => self,
+        }
+    }
+
+    /// Converte il valore in BSON, con i tipi BSON corrispondenti per numeri, booleani, liste e mappe.
+    ///
+    /// I timestamp vengono salvati come testo nel formato `TIMESTAMP_FORMAT`.
+    pub fn to_bson(&self) -> Bson {
+        match self {
+            ModelValue::Null => Bson::Null,
+            ModelValue::Bool(value) => Bson::Boolean(*value),
+            ModelValue::Int(value) => Bson::Int64(*value),
+            ModelValue::Float(value) => Bson::Double(*value),
+            ModelValue::Text(value) => Bson::String(value.clone()),
+            ModelValue::Timestamp(value) => Bson::String(value.format(TIMESTAMP_FORMAT).to_string()),
+            ModelValue::List(values) => Bson::Array(values.iter().map(ModelValue::to_bson).collect()),
+            ModelValue::Map(values) => Bson::Document(
+                values.iter().map(|(key, value)| (key.clone(), value.to_bson())).collect(),
+            ),
+        }
+    }
+
+    /// Converte un valore BSON letto da MongoDB.
+    ///
+    /// I tipi BSON senza corrispondenza (es. `ObjectId`) vengono rappresentati come testo.
+    pub fn from_bson(value: Bson) -> ModelValue {
+        match value {
+            Bson::Null | Bson::Undefined => ModelValue::Null,
+            Bson::Boolean(value) => ModelValue::Bool(value),
+            Bson::Int32(value) => ModelValue::Int(value as i64),
+            Bson::Int64(value) => ModelValue::Int(value),
+            Bson::Double(value) => ModelValue::Float(value),
+            Bson::String(value) => ModelValue::Text(value),
+            Bson::DateTime(value) => ModelValue::Timestamp(value.to_chrono().naive_utc()),
+            Bson::Array(values) => ModelValue::List(values.into_iter().map(ModelValue::from_bson).collect()),
+            Bson::Document(values) => ModelValue::Map(
+                values.into_iter().map(|(key, value)| (key, ModelValue::from_bson(value))).collect(),
+            ),
+            other => ModelValue::Text(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for ModelValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelValue::Null => write!(f, "null"),
+            ModelValue::Bool(value) => write!(f, "{}", value),
+            ModelValue::Int(value) => write!(f, "{}", value),
+            ModelValue::Float(value) => write!(f, "{}", value),
+            ModelValue::Text(value) => write!(f, "{}", value),
+            ModelValue::Timestamp(value) => write!(f, "{}", value),
+            ModelValue::List(values) => {
+                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "[{}]", values.join(", "))
+            }
+            ModelValue::Map(values) => {
+                let values: Vec<String> = values.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+                write!(f, "{{{}}}", values.join(", "))
+            }
+        }
+    }
+}
+
+/// Conversione di un campo in `ModelValue`.
+pub trait ToModelValue {
+    fn to_model_value(&self) -> ModelValue;
+}
+
+/// Ricostruzione di un campo da un `ModelValue`.
+pub trait FromModelValue: Sized {
+    fn from_model_value(value: ModelValue) -> Result<Self, String>;
+}
+
+impl ToModelValue for ModelValue {
+    fn to_model_value(&self) -> ModelValue {
+        self.clone()
+    }
+}
+
+/// Legge il campo `name` dai valori di un record e lo converte nel tipo richiesto.
+///
+/// Un campo assente viene trattato come `ModelValue::Null`, così i campi `Option` aggiunti
+/// dopo la creazione del record vengono letti come `None`.
+pub fn field_value<T: FromModelValue>(values: &HashMap<String, ModelValue>, name: &str) -> Result<T, String> {
+    let value = values.get(name).cloned().unwrap_or(ModelValue::Null);
+    T::from_model_value(value).map_err(|e| format!("Campo '{}': {}", name, e))
+}
+
+fn type_error<T>(expected: &str, value: &ModelValue) -> Result<T, String> {
+    Err(format!("atteso {}, trovato {}", expected, value.type_name()))
+}
+
+/// Implementa le conversioni per i tipi interi, con controllo dei limiti del tipo.
+macro_rules! impl_model_value_int {
+    ($($ty:ty),*) => {
+        $(
+            impl ToModelValue for $ty {
+                fn to_model_value(&self) -> ModelValue {
+                    ModelValue::Int(*self as i64)
+                }
+            }
+
+            impl FromModelValue for $ty {
+                fn from_model_value(value: ModelValue) -> Result<Self, String> {
+                    match value {
+                        ModelValue::Int(n) => <$ty>::try_from(n)
+                            .map_err(|_| format!("{} fuori dai limiti di {}", n, stringify!($ty))),
+                        // Alcuni storage (es. MongoDB) restituiscono i numeri come double
+                        ModelValue::Float(n) if n.fract() == 0.0 => <$ty>::try_from(n as i64)
+                            .map_err(|_| format!("{} fuori dai limiti di {}", n, stringify!($ty))),
+                        ModelValue::Text(text) => text.parse::<$ty>().map_err(|e| e.to_string()),
+                        other => type_error(stringify!($ty), &other),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_model_value_int!(u8, u16, u32, i32, i64);
+
+impl ToModelValue for u64 {
+    fn to_model_value(&self) -> ModelValue {
+        // I valori oltre i64::MAX vengono rappresentati come testo per non perdere precisione
+        i64::try_from(*self).map(ModelValue::Int).unwrap_or_else(|_| ModelValue::Text(self.to_string()))
+    }
+}
+
+impl FromModelValue for u64 {
+    fn from_model_value(value: ModelValue) -> Result<Self, String> {
+        match value {
+            ModelValue::Int(n) => u64::try_from(n).map_err(|_| format!("{} fuori dai limiti di u64", n)),
+            ModelValue::Text(text) => text.parse::<u64>().map_err(|e| e.to_string()),
+            other => type_error("u64", &other),
+        }
+    }
+}
+
+impl ToModelValue for f32 {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Float(*self as f64)
+    }
+}
+
+impl FromModelValue for f32 {
+    fn from_model_value(value: ModelValue) -> Result<Self, String> {
+        f64::from_model_value(value).map(|n| n as f32)
+    }
+}
+
+impl ToModelValue for f64 {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Float(*self)
+    }
+}
+
+impl FromModelValue for f64 {
+    fn from_model_value(value: ModelValue) -> Result<Self, String> {
+        match value {
+            ModelValue::Float(n) => Ok(n),
+            ModelValue::Int(n) => Ok(n as f64),
+            ModelValue::Text(text) => text.parse::<f64>().map_err(|e| e.to_string()),
+            other => type_error("f64", &other),
+        }
+    }
+}
+
+impl ToModelValue for bool {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Bool(*self)
+    }
+}
+
+impl FromModelValue for bool {
+    fn from_model_value(value: ModelValue) -> Result<Self, String> {
+        match value {
+            ModelValue::Bool(b) => Ok(b),
+            // SQLite rappresenta i BOOLEAN come INTEGER
+            ModelValue::Int(n) => Ok(n != 0),
+            // Es. le celle CSV di `data_transfer.rs`
+            ModelValue::Text(text) => text.parse::<bool>().map_err(|e| e.to_string()),
+            other => type_error("bool", &other),
+        }
+    }
+}
+
+impl ToModelValue for String {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Text(self.clone())
+    }
+}
+
+// Es. i valori letterali dei filtri di `Query` (`Query::new().eq("name", "Garage")`)
+impl ToModelValue for &str {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Text(self.to_string())
+    }
+}
+
+impl FromModelValue for String {
+    fn from_model_value(value: ModelValue) -> Result<Self, String> {
+        match value {
+            ModelValue::Text(text) => Ok(text),
+            other => type_error("String", &other),
+        }
+    }
+}
+
+impl ToModelValue for Uuid {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Text(self.to_string())
+    }
+}
+
+impl FromModelValue for Uuid {
+    fn from_model_value(value: ModelValue) -> Result<Self, String> {
+        match value {
+            ModelValue::Text(text) => Uuid::parse_str(&text).map_err(|e| format!("UUID non valido '{}': {}", text, e)),
+            other => type_error("Uuid", &other),
+        }
+    }
+}
+
+impl ToModelValue for NaiveDateTime {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Timestamp(*self)
+    }
+}
+
+impl FromModelValue for NaiveDateTime {
+    fn from_model_value(value: ModelValue) -> Result<Self, String> {
+        match value {
+            ModelValue::Timestamp(ts) => Ok(ts),
+            // Gli storage SQL restituiscono i TIMESTAMP come testo
+            ModelValue::Text(text) => NaiveDateTime::parse_from_str(&text, TIMESTAMP_FORMAT)
+                .or_else(|_| NaiveDateTime::parse_from_str(&text, "%Y-%m-%dT%H:%M:%S%.f"))
+                .map_err(|e| format!("Timestamp non valido '{}': {}", text, e)),
+            other => type_error("NaiveDateTime", &other),
+        }
+    }
+}
+
+impl<T: ToModelValue> ToModelValue for Option<T> {
+    fn to_model_value(&self) -> ModelValue {
+        match self {
+            Some(value) => value.to_model_value(),
+            None => ModelValue::Null,
+        }
+    }
+}
+
+impl<T: FromModelValue> FromModelValue for Option<T> {
+    fn from_model_value(value: ModelValue) -> Result<Self, String> {
+        match value {
+            ModelValue::Null => Ok(None),
+            value => T::from_model_value(value).map(Some),
+        }
+    }
+}
+
+impl<T: ToModelValue> ToModelValue for Vec<T> {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::List(self.iter().map(ToModelValue::to_model_value).collect())
+    }
+}
+
+impl<T: FromModelValue> FromModelValue for Vec<T> {
+    fn from_model_value(value: ModelValue) -> Result<Self, String> {
+        match value.into_structured() {
+            ModelValue::List(values) => values.into_iter().map(T::from_model_value).collect(),
+            other => type_error("Vec", &other),
+        }
+    }
+}
+
+/// Implementa `ToModelValue` e `FromModelValue` per un enum senza dati, rappresentato come testo.
+///
+/// # Esempio
+/// ```rust,ignore
+/// impl_model_value_enum!(ProjectStatus { Active, Disabled, Completed });
+/// ```
+#[macro_export]
+macro_rules! impl_model_value_enum {
+    ($enum:ident { $($variant:ident),* $(,)? }) => {
+        impl $crate::crud::model_value::ToModelValue for $enum {
+            fn to_model_value(&self) -> $crate::crud::model_value::ModelValue {
+                match self {
+                    $($enum::$variant => $crate::crud::model_value::ModelValue::Text(stringify!($variant).to_string()),)*
+                }
+            }
+        }
+
+        impl $crate::crud::model_value::FromModelValue for $enum {
+            fn from_model_value(value: $crate::crud::model_value::ModelValue) -> Result<Self, String> {
+                match value.as_text() {
+                    $(Some(stringify!($variant)) => Ok($enum::$variant),)*
+                    _ => Err(format!("Valore non valido per {}: {}", stringify!($enum), value)),
+                }
+            }
+        }
+    };
+}
@@ -11,13 +11,23 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::models::default::user::model::User;
+            use crate::crud::revocation::Revocation;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,delete,list,revoke")]
             pub struct ApiKey {
                 pub id: u32,
+                #[arx(secret)]
                 pub key: String,
+                #[arx(belongs_to = "User", on_delete = "cascade")]
                 pub user_id: u32,
+                pub revocation: Option<Revocation>,
+                #[serde(skip, default = "ApiKey::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "ApiKey::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -27,6 +37,7 @@ pub mod model {
                         id,
                         key,
                         user_id,
+                        revocation: None,
                         store: AllocType::Database,
                         ops:CrudOperations{
                             create: true,
@@ -12,14 +12,30 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::crud_ops::CrudError;
+            use crate::crud::relations;
+            use crate::crud::models::default::article_category::model::ArticleCategory;
+            use crate::crud::models::default::article_tag::model::ArticleTag;
+            use crate::crud::models::default::category::model::Category;
+            use crate::crud::models::default::comment::model::Comment;
+            use crate::crud::models::default::tag::model::Tag;
+            use crate::crud::models::default::user::model::User;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list,search")]
+            #[arx(has_many = "Comment", has_many = "ArticleTag", has_many = "ArticleCategory")]
             pub struct Article {
                 pub id: u32,
                 pub title: String,
                 pub content: String,
+                #[arx(belongs_to = "User")]
                 pub author_id: u32,
+                pub deleted_at: Option<chrono::NaiveDateTime>,  // Data di eliminazione, `None` se non è nel cestino
+                #[serde(skip, default = "Article::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Article::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -30,6 +46,7 @@ pub mod model {
                         title,
                         content,
                         author_id,
+                        deleted_at: None,
                         store: AllocType::Database,
                         ops: CrudOperations{
                             create: true,
@@ -42,6 +59,21 @@ pub mod model {
                         },
                     }
                 }
+
+                /// Legge l'articolo insieme ai suoi commenti.
+                pub fn with_comments(id: u32) -> Result<(Article, Vec<Comment>), CrudError> {
+                    relations::load_with::<Article, Comment>(id)
+                }
+
+                /// Tag collegati all'articolo.
+                pub fn tags(&self) -> Result<Vec<Tag>, CrudError> {
+                    relations::related::<Article, ArticleTag, Tag>(self)
+                }
+
+                /// Categorie collegate all'articolo.
+                pub fn categories(&self) -> Result<Vec<Category>, CrudError> {
+                    relations::related::<Article, ArticleCategory, Category>(self)
+                }
             }
         }
     }
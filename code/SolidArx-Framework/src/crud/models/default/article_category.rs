@@ -0,0 +1,56 @@
+pub mod model {
+    
+    // Usato per incapsulare i blocchi in relazione al `cfg` attivo per la generazione di codice in compile time
+    use cfg_if::cfg_if; 
+
+    cfg_if! {
+        /* 
+        #[cfg] Seppur ridondante in relazione al `crud_ops` è necessario per rendere 
+        la generazione delle tables selettiva per `table_scraper.rs` 
+        */
+        if #[cfg(feature = "webapp")] {
+
+            use crate::crud::crud_ops::AllocType;
+            use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::models::default::article::model::Article;
+            use crate::crud::models::default::category::model::Category;
+
+            // Collegamento molti a molti tra `Article` e `Category` (vedi `crud/relations.rs`)
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,delete,list")]
+            pub struct ArticleCategory {
+                pub id: u32,
+                #[arx(belongs_to = "Article", on_delete = "cascade")]
+                pub article_id: u32,
+                #[arx(belongs_to = "Category", on_delete = "cascade")]
+                pub category_id: u32,
+                #[serde(skip, default = "ArticleCategory::default_store")]
+                pub store: AllocType,
+                #[serde(skip, default = "ArticleCategory::declared_operations")]
+                pub ops: CrudOperations,
+            }
+
+            impl ArticleCategory {
+                pub fn new(id: u32, article_id: u32, category_id: u32) -> Self {
+                    ArticleCategory {
+                        id,
+                        article_id,
+                        category_id,
+                        store: AllocType::Database,
+                        ops: CrudOperations{
+                            create: true,
+                            read: true,
+                            update: false,
+                            delete: true,
+                            list: true,
+                            search: false,
+                            revoke: false,
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
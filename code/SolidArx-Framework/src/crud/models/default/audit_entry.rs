@@ -0,0 +1,86 @@
+pub mod model {
+
+    // Usato per incapsulare i blocchi in relazione al `cfg` attivo per la generazione di codice in compile time
+    use cfg_if::cfg_if;
+
+    cfg_if! {
+        /*
+        #[cfg] Seppur ridondante in relazione al `crud_ops` è necessario per rendere
+        la generazione delle tables selettiva per `table_scraper.rs`
+        */
+        if #[cfg(feature = "crud")] {
+
+            use crate::crud::crud_ops::AllocType;
+            use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use chrono;
+
+            // Operazione registrata nel log di audit
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+            pub enum AuditAction {
+                Create,
+                Update,
+                Delete,
+                Revoke,
+                Restore,
+                Purge,
+            }
+
+            crate::impl_model_value_enum!(AuditAction { Create, Update, Delete, Revoke, Restore, Purge });
+
+            // Voce del log di audit (vedi `crud/audit.rs`), solo in scrittura: non sono previsti update e delete
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,list,search")]
+            pub struct AuditEntry {
+                pub id: u64,
+                pub model: String,                   // Tabella del modello modificato
+                pub record_id: String,               // ID del record modificato, come testo
+                pub action: AuditAction,
+                pub actor: String,                   // Utente o servizio che ha eseguito l'operazione
+                pub timestamp: chrono::NaiveDateTime, // Data e ora dell'operazione (UTC)
+                pub before: Option<String>,          // Valori dei campi prima dell'operazione, come JSON
+                pub after: Option<String>,           // Valori dei campi dopo l'operazione, come JSON
+                pub changed_fields: Vec<String>,     // Campi con valori diversi tra `before` e `after`
+                #[serde(skip, default = "AuditEntry::default_store")]
+                pub store: AllocType,
+                #[serde(skip, default = "AuditEntry::declared_operations")]
+                pub ops: CrudOperations,
+            }
+
+            impl AuditEntry {
+                pub fn new(
+                    model: &str,
+                    record_id: String,
+                    action: AuditAction,
+                    actor: &str,
+                    before: Option<String>,
+                    after: Option<String>,
+                    changed_fields: Vec<String>,
+                ) -> Self {
+                    AuditEntry {
+                        id: 0,
+                        model: model.to_string(),
+                        record_id,
+                        action,
+                        actor: actor.to_string(),
+                        timestamp: chrono::Utc::now().naive_utc(),
+                        before,
+                        after,
+                        changed_fields,
+                        store: AllocType::Database,
+                        ops: CrudOperations{
+                            create: true,
+                            read: true,
+                            update: false,
+                            delete: false,
+                            list: true,
+                            search: true,
+                            revoke: false,
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
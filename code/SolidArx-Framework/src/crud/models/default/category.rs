@@ -12,12 +12,18 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::models::default::article_category::model::ArticleCategory;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list", has_many = "ArticleCategory")]
             pub struct Category {
                 pub id: u32,
                 pub name: String,
+                #[serde(skip, default = "Category::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Category::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -13,15 +13,23 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::revocation::Revocation;
             
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "memory", ops = "create,read,update,delete,list,search,revoke")]
             pub struct Command {
                 pub id: u32,
                 pub device_id: u32,
                 pub command_type: String,
                 pub issued_at: String,
+                pub revocation: Option<Revocation>,
+                #[serde(skip, default = "Command::default_store")]
                 pub store: AllocType,
+                #[serde(skip)]
                 pub memory: Box<[u8]>,
+                #[serde(skip, default = "Command::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -39,6 +47,7 @@ pub mod model {
                         device_id,
                         command_type,
                         issued_at,
+                        revocation: None,
                         store: AllocType::InMemory,
                         memory,
                         ops: CrudOperations{
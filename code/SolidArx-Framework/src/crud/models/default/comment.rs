@@ -12,14 +12,23 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::models::default::article::model::Article;
+            use crate::crud::models::default::user::model::User;
             
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list,search")]
             pub struct Comment {
                 pub id: u32,
                 pub content: String,
+                #[arx(belongs_to = "User")]
                 pub author_id: u32,
+                #[arx(belongs_to = "Article", on_delete = "cascade")]
                 pub article_id: u32,
+                #[serde(skip, default = "Comment::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Comment::declared_operations")]
                 pub ops: CrudOperations,
             }
 
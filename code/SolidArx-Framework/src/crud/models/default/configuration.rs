@@ -13,8 +13,12 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::revocation::Revocation;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "cached", cache = "write_through", capacity = 128, ttl = 600, ops = "create,read,update,delete,search,revoke", audit)]
             pub struct Configuration {
                 pub id: u32,
                 
@@ -24,8 +28,13 @@ pub mod model {
                 
                 pub key: String,
                 pub value: String,
+                pub version: u64,  // Versione per il controllo ottimistico della concorrenza
+                pub revocation: Option<Revocation>,
+                #[serde(skip, default = "Configuration::default_store")]
                 pub store: AllocType,
+                #[serde(skip)]
                 pub memory: Box<[u8]>,
+                #[serde(skip, default = "Configuration::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -43,7 +52,9 @@ pub mod model {
                         device_id,
                         key,
                         value,
-                        store: AllocType::InMemory,
+                        version: 0,
+                        revocation: None,
+                        store: AllocType::Cached,
                         memory,
                         ops: CrudOperations{
                             create: true,
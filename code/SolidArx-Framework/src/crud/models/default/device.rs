@@ -13,14 +13,25 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::models::default::firmware_version::model::FirmwareVersion;
+            use crate::crud::models::default::sensor_data::model::SensorData;
+            use crate::crud::revocation::Revocation;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "cached", cache = "write_behind", capacity = 512, ttl = 120, ops = "create,read,update,delete,list,search,revoke")]
+            #[arx(has_many = "SensorData", has_many = "FirmwareVersion")]
             pub struct Device {
                 pub id: u32,
                 pub name: String,
                 pub device_type: String,
+                pub revocation: Option<Revocation>,
+                #[serde(skip, default = "Device::default_store")]
                 pub store: AllocType,
+                #[serde(skip)]
                 pub memory: Box<[u8]>,
+                #[serde(skip, default = "Device::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -30,7 +41,8 @@ pub mod model {
                         id, 
                         name,
                         device_type: "default".to_string(),
-                        store: AllocType::InMemory,
+                        revocation: None,
+                        store: AllocType::Cached,
                         memory,
                         ops: CrudOperations{
                             create: true,
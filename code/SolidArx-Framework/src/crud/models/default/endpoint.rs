@@ -13,13 +13,20 @@ pub mod model{
             
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
                     
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list")]
             pub struct Endpoint {
                 pub id: u32,
+                #[arx(validate(length(min = 1, max = 64)))]
                 pub name: String,
+                #[arx(validate(path))]
                 pub path: String,
+                #[serde(skip, default = "Endpoint::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Endpoint::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -13,22 +13,29 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
 
             /// Enum per lo stato delle macro
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize)]
             pub enum ExeLogStatus {
                 Active,
                 Disabled,
                 Completed,
             }
 
-            #[derive(Debug, Clone)]
+            crate::impl_model_value_enum!(ExeLogStatus { Active, Disabled, Completed });
+
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,delete,list,search")]
             pub struct ExecutionLog {
                 pub id: u32,
                 pub script_id: u32,
                 pub execution_time: String,
                 pub status: ExeLogStatus,
+                #[serde(skip, default = "ExecutionLog::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "ExecutionLog::declared_operations")]
                 pub ops: CrudOperations,
             }
             
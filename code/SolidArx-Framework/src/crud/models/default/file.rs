@@ -12,13 +12,18 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list,search")]
             pub struct File {
                 pub id: u32,
                 pub file_name: String,
                 pub file_path: String,
+                #[serde(skip, default = "File::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "File::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -13,14 +13,23 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::models::default::device::model::Device;
+            use crate::crud::revocation::Revocation;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,list,search,revoke")]
             pub struct FirmwareVersion {
                 pub id: u32,
+                #[arx(belongs_to = "Device")]  // Lo storico del firmware impedisce l'eliminazione del dispositivo
                 pub device_id: u32,
                 pub version: String,
                 pub release_date: String,
+                pub revocation: Option<Revocation>,
+                #[serde(skip, default = "FirmwareVersion::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "FirmwareVersion::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -31,6 +40,7 @@ pub mod model {
                         device_id,
                         version,
                         release_date,
+                        revocation: None,
                         store: AllocType::Database,
                         ops: CrudOperations{
                             create: true,
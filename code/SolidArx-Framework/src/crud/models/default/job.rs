@@ -12,14 +12,22 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::revocation::Revocation;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "memory", ops = "create,read,update,delete,list,revoke")]
             pub struct Job {
                 pub id: u32,
                 pub name: String,
                 pub description: String,
+                pub revocation: Option<Revocation>,
+                #[serde(skip, default = "Job::default_store")]
                 pub store: AllocType,
+                #[serde(skip)]
                 pub memory: Box<[u8]>,
+                #[serde(skip, default = "Job::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -34,6 +42,7 @@ pub mod model {
                         id,
                         name,
                         description,
+                        revocation: None,
                         store: AllocType::InMemory,
                         memory,
                         ops: CrudOperations{
@@ -12,16 +12,22 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
             
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "memory", ops = "create,read,search")]
             pub struct LogEvent {
                 pub id: u32,
                 pub device_id: u32,
                 pub event_type: String,
                 pub timestamp: String,
                 pub description: String,
+                #[serde(skip, default = "LogEvent::default_store")]
                 pub store: AllocType,
+                #[serde(skip)]
                 pub memory: Box<[u8]>,
+                #[serde(skip, default = "LogEvent::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -13,9 +13,12 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::model_value::{FromModelValue, ModelValue, ToModelValue};
 
             /// Enum per definire la frequenza di esecuzione delle macro
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize)]
             pub enum ExecutionFrequency {
                 Once,
                 Daily,
@@ -24,24 +27,58 @@ pub mod model {
                 Custom(String), // Per frequenze personalizzate specificate dall'utente
             }
 
+            /// `Custom` viene rappresentata come `Custom:<frequenza>`, le altre varianti con il loro nome
+            impl ToModelValue for ExecutionFrequency {
+                fn to_model_value(&self) -> ModelValue {
+                    ModelValue::Text(match self {
+                        ExecutionFrequency::Once => "Once".to_string(),
+                        ExecutionFrequency::Daily => "Daily".to_string(),
+                        ExecutionFrequency::Weekly => "Weekly".to_string(),
+                        ExecutionFrequency::Monthly => "Monthly".to_string(),
+                        ExecutionFrequency::Custom(frequency) => format!("Custom:{}", frequency),
+                    })
+                }
+            }
+
+            impl FromModelValue for ExecutionFrequency {
+                fn from_model_value(value: ModelValue) -> Result<Self, String> {
+                    match value.as_text() {
+                        Some("Once") => Ok(ExecutionFrequency::Once),
+                        Some("Daily") => Ok(ExecutionFrequency::Daily),
+                        Some("Weekly") => Ok(ExecutionFrequency::Weekly),
+                        Some("Monthly") => Ok(ExecutionFrequency::Monthly),
+                        Some(text) if text.starts_with("Custom:") => {
+                            Ok(ExecutionFrequency::Custom(text["Custom:".len()..].to_string()))
+                        }
+                        _ => Err(format!("Valore non valido per ExecutionFrequency: {}", value)),
+                    }
+                }
+            }
+
             /// Enum per lo stato delle macro
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize)]
             pub enum MacroStatus {
                 Active,
                 Disabled,
                 Completed,
             }
 
+            crate::impl_model_value_enum!(MacroStatus { Active, Disabled, Completed });
+
             
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "memory", ops = "create,read,update,delete,list")]
             pub struct Macro {
                 pub id: u32,
                 pub name: String,
                 pub commands: Vec<String>,
                 pub frequency: ExecutionFrequency,      // Frequenza di esecuzione pianificata
                 pub status: MacroStatus,               // Stato della macro (attiva, disattivata, completata, ecc.)
+                #[serde(skip, default = "Macro::default_store")]
                 pub store: AllocType,
+                #[serde(skip)]
                 pub memory: Box<[u8]>,
+                #[serde(skip, default = "Macro::declared_operations")]
                 pub ops: CrudOperations,
             }
 
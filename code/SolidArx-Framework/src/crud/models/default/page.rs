@@ -13,13 +13,19 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
             
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list,search")]
             pub struct Page {
                 pub id: u32,
                 pub title: String,
                 pub content: String,
+                pub deleted_at: Option<chrono::NaiveDateTime>,  // Data di eliminazione, `None` se non è nel cestino
+                #[serde(skip, default = "Page::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Page::declared_operations")]
                 pub ops: CrudOperations,
             }
             
@@ -29,6 +35,7 @@ pub mod model {
                         id,
                         title,
                         content,
+                        deleted_at: None,
                         store: AllocType::Database,
                         ops: CrudOperations{
                             create: true,
@@ -12,12 +12,17 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
             
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list", audit)]
             pub struct Permission {
                 pub id: u32,
                 pub name: String,
+                #[serde(skip, default = "Permission::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Permission::declared_operations")]
                 pub ops: CrudOperations,
             }
 
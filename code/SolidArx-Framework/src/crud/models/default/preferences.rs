@@ -12,13 +12,18 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list")]
             pub struct Preferences {
                 pub id: u32,
                 pub language: String,
                 pub auto_save: bool,
+                #[serde(skip, default = "Preferences::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Preferences::declared_operations")]
                 pub ops: CrudOperations,
             }
 
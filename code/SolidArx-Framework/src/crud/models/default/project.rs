@@ -12,26 +12,62 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::model_value::{field_value, FromModelValue, ModelValue, ToModelValue};
+            use std::collections::{BTreeMap, HashMap};
             use chrono;
 
             // Struttura opzionale per i metadati del progetto
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize)]
             pub struct ProjectMetadata {
                 pub description: Option<String>,      // Descrizione del progetto
                 pub tags: Option<Vec<String>>,        // Tag o etichette per la classificazione
                 pub version: Option<String>,          // Versione del progetto
                 pub contributors: Option<Vec<String>>, // Nomi o ID dei contributori
             }
+
+            /// I metadati vengono persistiti come un unico campo strutturato (JSON su SQL, documento su MongoDB)
+            impl ToModelValue for ProjectMetadata {
+                fn to_model_value(&self) -> ModelValue {
+                    ModelValue::Map(BTreeMap::from([
+                        ("description".to_string(), self.description.to_model_value()),
+                        ("tags".to_string(), self.tags.to_model_value()),
+                        ("version".to_string(), self.version.to_model_value()),
+                        ("contributors".to_string(), self.contributors.to_model_value()),
+                    ]))
+                }
+            }
+
+            impl FromModelValue for ProjectMetadata {
+                fn from_model_value(value: ModelValue) -> Result<Self, String> {
+                    let value = value.into_structured();
+                    let ModelValue::Map(fields) = value else {
+                        return Err(format!("Valore non valido per ProjectMetadata: {}", value));
+                    };
+                    let fields: HashMap<String, ModelValue> = fields.into_iter().collect();
+                    Ok(ProjectMetadata {
+                        description: field_value(&fields, "description")?,
+                        tags: field_value(&fields, "tags")?,
+                        version: field_value(&fields, "version")?,
+                        contributors: field_value(&fields, "contributors")?,
+                    })
+                }
+            }
+
             // Enum per definire i possibili stati del progetto
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize)]
             pub enum ProjectStatus {
                 Active,
                 Disabled,
                 Completed,
             }
+
+            crate::impl_model_value_enum!(ProjectStatus { Active, Disabled, Completed });
             
             
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list")]
             pub struct Project {
                 pub id: u32,
                 pub name: String,
@@ -40,7 +76,10 @@ pub mod model {
                 pub updated_at: chrono::NaiveDateTime,
                 pub status: ProjectStatus,           // Stato del progetto
                 pub metadata: Option<ProjectMetadata>,
+                pub deleted_at: Option<chrono::NaiveDateTime>,  // Data di eliminazione, `None` se non è nel cestino
+                #[serde(skip, default = "Project::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Project::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -56,6 +95,7 @@ pub mod model {
                         updated_at: chrono::Local::now().naive_local(),
                         status, 
                         metadata,
+                        deleted_at: None,
                         store: AllocType::Database,
                         ops: CrudOperations{
                             create: true,
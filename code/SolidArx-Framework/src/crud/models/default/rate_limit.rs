@@ -13,13 +13,20 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
             
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list")]
             pub struct RateLimitRule {
                 pub id: u32,
+                #[arx(validate(range(min = 1)))]
                 pub limit: u32,
-                pub period: String,
+                #[arx(validate(regex = "^[1-9][0-9]*(s|m|h|d)$"))]
+                pub period: String,  // Finestra temporale, es. `60s`, `15m`, `1h` o `1d`
+                #[serde(skip, default = "RateLimitRule::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "RateLimitRule::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -13,14 +13,19 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,delete,list")]
             pub struct RequestLog {
                 pub id: u32,
                 pub endpoint: String,
                 pub request_time: String,
                 pub response_code: u16,
+                #[serde(skip, default = "RequestLog::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "RequestLog::declared_operations")]
                 pub ops: CrudOperations,
 
             }
@@ -13,13 +13,23 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::models::default::task::model::Task;
+            use crate::crud::revocation::Revocation;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list,search,revoke")]
             pub struct Schedule {
                 pub id: u32,
+                #[arx(belongs_to = "Task", on_delete = "cascade")]
                 pub task_id: u32,
+                #[arx(validate(cron))]
                 pub cron_expression: String,
+                pub revocation: Option<Revocation>,
+                #[serde(skip, default = "Schedule::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Schedule::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -29,6 +39,7 @@ pub mod model {
                         id, 
                         task_id,
                         cron_expression,
+                        revocation: None,
                         store: AllocType::Database,
                         ops: CrudOperations{
                             create: true,
@@ -14,13 +14,18 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,search")]
             pub struct Script {
                 pub id: u32,
                 pub name: String,
                 pub code: String,
+                #[serde(skip, default = "Script::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Script::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -13,15 +13,23 @@ pub mod model {
             
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::models::default::device::model::Device;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "memory", ops = "create,read,delete,list,search")]
             pub struct SensorData {
                 pub id: u32,
+                #[arx(belongs_to = "Device", on_delete = "cascade")]
                 pub device_id: u32,
                 pub timestamp: String,
                 pub data: String,  // Puoi specificare il formato dei dati se necessario
+                #[serde(skip, default = "SensorData::default_store")]
                 pub store: AllocType,
+                #[serde(skip)]
                 pub memory: Box<[u8]>,
+                #[serde(skip, default = "SensorData::declared_operations")]
                 pub ops: CrudOperations,
             }
 
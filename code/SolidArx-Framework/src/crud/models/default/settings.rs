@@ -13,13 +13,18 @@ pub mod model {
             
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list", audit)]
             pub struct Settings {
                 pub id: u32,
                 pub theme: String,
                 pub notifications_enabled: bool,
+                #[serde(skip, default = "Settings::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Settings::declared_operations")]
                 pub ops: CrudOperations,
             }
 
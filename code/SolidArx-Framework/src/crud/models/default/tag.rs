@@ -13,12 +13,18 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::models::default::article_tag::model::ArticleTag;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list", has_many = "ArticleTag")]
             pub struct Tag {
                 pub id: u32,
                 pub name: String,
+                #[serde(skip, default = "Tag::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Tag::declared_operations")]
                 pub ops: CrudOperations,
             }
 
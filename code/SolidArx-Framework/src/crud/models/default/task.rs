@@ -13,8 +13,14 @@ pub mod model {
         
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            #[cfg(feature = "automation")]
+            use crate::crud::models::default::schedule::model::Schedule;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "memory", ops = "create,read,update,delete,list")]
+            #[cfg_attr(feature = "automation", arx(has_many = "Schedule"))]
             pub struct Task {
                 pub id: u32,
                 pub description: String,
@@ -31,8 +37,12 @@ pub mod model {
                 #[cfg(feature = "embedded")]
                 pub device_id: Option<u32>,  // Associa il task a un dispositivo
 
+                pub version: u64,  // Versione per il controllo ottimistico della concorrenza
+                #[serde(skip, default = "Task::default_store")]
                 pub store: AllocType,
+                #[serde(skip)]
                 pub memory: Box<[u8]>,
+                #[serde(skip, default = "Task::declared_operations")]
                 pub ops: CrudOperations,
             } 
 
@@ -55,6 +65,7 @@ pub mod model {
                         completed,
                         #[cfg(feature = "embedded")]
                         device_id,
+                        version: 0,
                         store: AllocType::InMemory,
                         memory,
                         ops:CrudOperations{
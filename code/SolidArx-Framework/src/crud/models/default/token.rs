@@ -13,13 +13,21 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            use crate::crud::revocation::Revocation;
             
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,delete,list,search,revoke")]
             pub struct Token {
                 pub id: u32,
+                #[arx(secret)]
                 pub token: String,
                 pub user_id: u32,
+                pub revocation: Option<Revocation>,
+                #[serde(skip, default = "Token::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "Token::declared_operations")]
                 pub ops: CrudOperations,
             }
 
@@ -29,6 +37,7 @@ pub mod model {
                         id,
                         token,
                         user_id,
+                        revocation: None,
                         store: AllocType::Database,
                         ops: CrudOperations{
                             create: true,
@@ -19,14 +19,30 @@ pub mod model {
 
             use crate::crud::crud_ops::AllocType;
             use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{Deserialize, Serialize};
+            #[cfg(feature = "webapp")]
+            use crate::crud::models::default::article::model::Article;
+            #[cfg(feature = "webapp")]
+            use crate::crud::models::default::comment::model::Comment;
+            #[cfg(feature = "api")]
+            use crate::crud::models::default::api_key::model::ApiKey;
 
-            #[derive(Debug, Clone)]
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = "database", ops = "create,read,update,delete,list")]
+            #[cfg_attr(feature = "webapp", arx(has_many = "Article", has_many = "Comment"))]
+            #[cfg_attr(feature = "api", arx(has_many = "ApiKey"))]
             pub struct User {
                 pub id: u32,
+                #[arx(validate(length(min = 3, max = 32)))]
                 pub username: String,
+                #[arx(validate(email, length(max = 254)))]
                 pub email: String,
+                #[arx(secret)]
                 pub password: String,
+                #[serde(skip, default = "User::default_store")]
                 pub store: AllocType,
+                #[serde(skip, default = "User::declared_operations")]
                 pub ops: CrudOperations,
             }
 
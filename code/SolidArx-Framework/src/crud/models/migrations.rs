@@ -0,0 +1,636 @@
+//! Modulo per la gestione esplicita delle migrazioni dello schema del database.
+//!
+//! Sostituisce la generazione implicita delle tabelle ad ogni avvio del `CoreSystem`.
+//! Lo schema desiderato viene ricavato dai modelli tramite `table_scraper.rs` e confrontato
+//! con l'ultimo snapshot registrato nella tabella di storico `arx_migrations`.
+//! Le differenze generano una migrazione con istruzioni `up` e `down`, in modo che ogni
+//! modifica dello schema sia revisionabile (`arx db plan`), riproducibile (`arx db migrate`)
+//! e reversibile (`arx db rollback <n>`).
+//!
+//! ### Backend supportati:
+//! - PostgreSQL e SQLite: istruzioni DDL eseguite in transazione tramite diesel, con i nomi di
+//!   tabelle e colonne tra doppi apici (es. la colonna `limit` di `RateLimitRule`).
+//! - MongoDB: creazione ed eliminazione delle collezioni, storico salvato nella collezione `arx_migrations`.
+
+use std::collections::BTreeMap;
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Text};
+use diesel::{PgConnection, sqlite::SqliteConnection};
+use mongodb::bson::{doc, Document};
+use log::{info, warn};
+
+use crate::config::network_config::DatabaseType;
+use crate::network::connection_management::{ConnectionManager, DatabaseConnection, DbConnection};
+use crate::crud::models::model_generator::models_path;
+use crate::crud::models::table_scraper::{scrape, table_name, AUTO_INCREMENT};
+
+/// Nome della tabella (o collezione) di storico delle migrazioni.
+pub const MIGRATIONS_TABLE: &str = "arx_migrations";
+/// Nome del database MongoDB in cui `arx db migrate` crea le collezioni dei modelli.
+pub const MONGO_DATABASE: &str = "models";
+/// Cartelle dei modelli da cui ricavare lo schema desiderato, relative a `model_generator::models_path`.
+pub const MODEL_DIRS: [&str; 2] = ["default", "dev"];
+
+/// Errori che possono verificarsi durante la gestione delle migrazioni.
+#[derive(Debug)]
+pub enum MigrationError {
+    ConnectionError(String),
+    DieselError(diesel::result::Error),
+    MongoError(mongodb::error::Error),
+    ScrapeError(String),
+    InvalidRollback(String),
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::ConnectionError(msg) => write!(f, "ConnectionError: {}", msg),
+            MigrationError::DieselError(err) => write!(f, "DieselError: {}", err),
+            MigrationError::MongoError(err) => write!(f, "MongoError: {}", err),
+            MigrationError::ScrapeError(msg) => write!(f, "ScrapeError: {}", msg),
+            MigrationError::InvalidRollback(msg) => write!(f, "InvalidRollback: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<diesel::result::Error> for MigrationError {
+    fn from(err: diesel::result::Error) -> Self {
+        MigrationError::DieselError(err)
+    }
+}
+impl From<diesel::ConnectionError> for MigrationError {
+    fn from(err: diesel::ConnectionError) -> Self {
+        MigrationError::ConnectionError(err.to_string())
+    }
+}
+impl From<mongodb::error::Error> for MigrationError {
+    fn from(err: mongodb::error::Error) -> Self {
+        MigrationError::MongoError(err)
+    }
+}
+
+/// Snapshot dello schema: tabella -> campo -> tipo del database.
+///
+/// Si usa `BTreeMap` per avere un ordine stabile e quindi migrazioni riproducibili.
+pub type SchemaSnapshot = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Migrazione già applicata e registrata nella tabella di storico.
+///
+/// # Campi
+/// - `version`: Numero progressivo della migrazione.
+/// - `name`: Nome descrittivo della migrazione.
+/// - `up_sql`: Istruzioni eseguite per applicare la migrazione.
+/// - `down_sql`: Istruzioni da eseguire per annullare la migrazione.
+/// - `schema_snapshot`: Schema risultante dopo l'applicazione, serializzato con `serialize_snapshot`.
+/// - `applied_at`: Data e ora di applicazione.
+#[derive(Debug, Clone, QueryableByName)]
+pub struct AppliedMigration {
+    #[diesel(sql_type = Integer)]
+    pub version: i32,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Text)]
+    pub up_sql: String,
+    #[diesel(sql_type = Text)]
+    pub down_sql: String,
+    #[diesel(sql_type = Text)]
+    pub schema_snapshot: String,
+    #[diesel(sql_type = Text)]
+    pub applied_at: String,
+}
+
+/// Piano di migrazione calcolato confrontando lo schema dei modelli con l'ultimo snapshot.
+///
+/// # Campi
+/// - `up`: Istruzioni per portare il database allo schema dei modelli.
+/// - `down`: Istruzioni per tornare allo schema precedente, in ordine inverso.
+/// - `warnings`: Differenze che non possono essere applicate automaticamente (es. cambio di tipo).
+/// - `target`: Schema risultante dopo l'applicazione del piano.
+#[derive(Debug, Clone, Default)]
+pub struct MigrationPlan {
+    pub up: Vec<String>,
+    pub down: Vec<String>,
+    pub warnings: Vec<String>,
+    pub target: SchemaSnapshot,
+}
+
+impl MigrationPlan {
+    /// Indica se il database è già allineato con i modelli.
+    pub fn is_empty(&self) -> bool {
+        self.up.is_empty()
+    }
+}
+
+/// Stato delle migrazioni restituito da `arx db status`.
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub applied: Vec<AppliedMigration>,
+    pub pending: MigrationPlan,
+}
+
+/// Serializza uno snapshot dello schema in formato testuale, una riga `tabella.campo=tipo` per campo.
+pub fn serialize_snapshot(snapshot: &SchemaSnapshot) -> String {
+    let mut lines = Vec::new();
+    for (table, fields) in snapshot {
+        for (field, field_type) in fields {
+            lines.push(format!("{}.{}={}", table, field, field_type));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Ricostruisce uno snapshot dello schema serializzato con `serialize_snapshot`.
+pub fn parse_snapshot(serialized: &str) -> SchemaSnapshot {
+    let mut snapshot = SchemaSnapshot::new();
+    for line in serialized.lines().filter(|l| !l.trim().is_empty()) {
+        if let Some((column, field_type)) = line.split_once('=') {
+            if let Some((table, field)) = column.split_once('.') {
+                snapshot
+                    .entry(table.to_string())
+                    .or_default()
+                    .insert(field.to_string(), field_type.to_string());
+            }
+        }
+    }
+    snapshot
+}
+
+/// Ricava lo schema desiderato dai modelli in `MODEL_DIRS`.
+///
+/// # Parametri
+/// - `db_type`: Tipo di database, usato da `scrape` per mappare i tipi Rust.
+///
+/// # Ritorna
+/// Lo snapshot dello schema dei modelli attivi.
+pub fn desired_schema(db_type: &DatabaseType) -> Result<SchemaSnapshot, MigrationError> {
+    let mut snapshot = SchemaSnapshot::new();
+    let models_path = models_path();
+    for dir in MODEL_DIRS {
+        let path = models_path.join(dir);
+        // La cartella `dev` può non esistere se non sono stati generati modelli personalizzati
+        if !path.exists() {
+            continue;
+        }
+        let structs = scrape(&path.to_string_lossy(), db_type.clone()).map_err(MigrationError::ScrapeError)?;
+        for struct_map in structs {
+            for (struct_name, fields) in struct_map {
+                snapshot
+                    .entry(table_name(&struct_name))
+                    .or_default()
+                    .extend(fields);
+            }
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Calcola il piano di migrazione tra lo schema corrente e lo schema desiderato.
+///
+/// # Parametri
+/// - `current`: Schema registrato nell'ultima migrazione applicata.
+/// - `desired`: Schema ricavato dai modelli.
+/// - `db_type`: Tipo di database, determina il formato delle istruzioni generate.
+///
+/// # Ritorna
+/// Un `MigrationPlan` con le istruzioni `up` e `down`.
+///
+/// # Note
+/// I cambi di tipo di una colonna non vengono applicati automaticamente e sono riportati in `warnings`.
+pub fn plan_migration(current: &SchemaSnapshot, desired: &SchemaSnapshot, db_type: &DatabaseType) -> MigrationPlan {
+    let mongo = matches!(db_type, DatabaseType::MongoDB(_));
+    let mut plan = MigrationPlan { target: desired.clone(), ..Default::default() };
+
+    for table in creation_order(desired) {
+        let fields = &desired[table];
+        match current.get(table) {
+            None => {
+                if mongo {
+                    plan.up.push(format!("createCollection {}", table));
+                    plan.down.push(format!("dropCollection {}", table));
+                } else {
+                    plan.up.push(create_table_sql(table, fields, db_type));
+                    plan.down.push(format!("DROP TABLE IF EXISTS {}", quote_identifier(table)));
+                }
+            }
+            Some(current_fields) => {
+                // MongoDB non ha uno schema rigido: i nuovi campi non richiedono istruzioni
+                if mongo {
+                    continue;
+                }
+                for (field, field_type) in fields {
+                    match current_fields.get(field) {
+                        None => {
+                            plan.up.push(add_column_sql(table, field, field_type));
+                            plan.down.push(drop_column_sql(table, field));
+                        }
+                        Some(current_type) if current_type != field_type => {
+                            plan.warnings.push(format!(
+                                "La colonna {}.{} cambia tipo da {} a {}: modifica da gestire manualmente",
+                                table, field, current_type, field_type
+                            ));
+                        }
+                        _ => {}
+                    }
+                }
+                for (field, field_type) in current_fields {
+                    if !fields.contains_key(field) {
+                        plan.up.push(drop_column_sql(table, field));
+                        plan.down.push(add_column_sql(table, field, field_type));
+                    }
+                }
+            }
+        }
+    }
+
+    for (table, fields) in current {
+        if !desired.contains_key(table) {
+            plan.warnings.push(format!("La tabella {} non corrisponde più a nessun modello e verrà eliminata", table));
+            if mongo {
+                plan.up.push(format!("dropCollection {}", table));
+                plan.down.push(format!("createCollection {}", table));
+            } else {
+                plan.up.push(format!("DROP TABLE IF EXISTS {}", quote_identifier(table)));
+                plan.down.push(create_table_sql(table, fields, db_type));
+            }
+        }
+    }
+
+    // Le istruzioni di rollback vanno eseguite in ordine inverso
+    plan.down.reverse();
+    plan
+}
+
+/// Ordina le tabelle in modo che quelle referenziate dalle chiavi esterne (`REFERENCES tabella(id)`)
+/// vengano create prima, come richiesto da PostgreSQL. Le dipendenze circolari mantengono l'ordine alfabetico.
+fn creation_order(schema: &SchemaSnapshot) -> Vec<&String> {
+    let references = |table: &String| -> Vec<String> {
+        schema[table]
+            .values()
+            .filter_map(|field_type| field_type.split("REFERENCES ").nth(1))
+            .filter_map(|reference| reference.split('(').next())
+            .map(|referenced| referenced.trim().trim_matches('"').to_string())
+            .filter(|referenced| referenced != table && schema.contains_key(referenced))
+            .collect()
+    };
+
+    let mut ordered: Vec<&String> = Vec::new();
+    let mut remaining: Vec<&String> = schema.keys().collect();
+    while !remaining.is_empty() {
+        let ready = remaining
+            .iter()
+            .position(|table| references(table).iter().all(|referenced| ordered.contains(&referenced)))
+            .unwrap_or(0);
+        ordered.push(remaining.remove(ready));
+    }
+    ordered
+}
+
+/// Genera l'istruzione `CREATE TABLE` per una tabella, con `id` come chiave primaria.
+///
+/// Gli ID assegnati dal database (`table_scraper::AUTO_INCREMENT` nello snapshot) utilizzano
+/// `INTEGER PRIMARY KEY AUTOINCREMENT` su SQLite e `GENERATED BY DEFAULT AS IDENTITY` su PostgreSQL,
+/// così che ogni `INSERT` riceva un ID distinto anche con scritture concorrenti.
+fn create_table_sql(table: &str, fields: &BTreeMap<String, String>, db_type: &DatabaseType) -> String {
+    let columns = fields
+        .iter()
+        .map(|(field, field_type)| {
+            if field != "id" {
+                return column_definition(field, field_type);
+            }
+            match (field_type.strip_suffix(AUTO_INCREMENT).map(str::trim_end), db_type) {
+                // Su SQLite `AUTOINCREMENT` è ammesso solo per `INTEGER PRIMARY KEY` (intero a 64 bit)
+                (Some(_), DatabaseType::SQLite(_)) => format!("{} INTEGER PRIMARY KEY {}", quote_identifier(field), AUTO_INCREMENT),
+                (Some(column_type), _) => format!("{} {} GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY", quote_identifier(field), column_type),
+                (None, _) => format!("{} {} PRIMARY KEY", quote_identifier(field), field_type),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("CREATE TABLE IF NOT EXISTS {} ({})", quote_identifier(table), columns)
+}
+
+/// Istruzione di aggiunta di una colonna.
+fn add_column_sql(table: &str, field: &str, field_type: &str) -> String {
+    format!("ALTER TABLE {} ADD COLUMN {}", quote_identifier(table), column_definition(field, field_type))
+}
+
+/// Istruzione di eliminazione di una colonna.
+fn drop_column_sql(table: &str, field: &str) -> String {
+    format!("ALTER TABLE {} DROP COLUMN {}", quote_identifier(table), quote_identifier(field))
+}
+
+/// Racchiude tra doppi apici il nome di una tabella o di una colonna, così che i nomi che coincidono
+/// con parole riservate di SQL (es. `limit`, `user`) siano validi sia su SQLite che su PostgreSQL.
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Definizione di una colonna a partire dal tipo registrato nello snapshot.
+///
+/// La tabella referenziata da una chiave esterna (`REFERENCES tabella(id)`) viene racchiusa tra
+/// doppi apici e il segnaposto `value` dei vincoli `CHECK (value IN (...))` di `table_scraper.rs`
+/// viene sostituito con il nome della colonna. Lo snapshot registra il tipo originale, quindi
+/// gli schemi già applicati restano invariati.
+fn column_definition(field: &str, field_type: &str) -> String {
+    let column = quote_identifier(field);
+    let field_type = field_type.replace("CHECK (value ", &format!("CHECK ({} ", column));
+    let field_type = match field_type.split_once("REFERENCES ") {
+        Some((column_type, reference)) => match reference.split_once('(') {
+            Some((table, rest)) => format!("{}REFERENCES {}({}", column_type, quote_identifier(table.trim()), rest),
+            None => field_type.clone(),
+        },
+        None => field_type.clone(),
+    };
+    format!("{} {}", column, field_type)
+}
+
+/// Istruzione di creazione della tabella di storico delle migrazioni.
+fn history_table_sql() -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} (\
+            version INTEGER PRIMARY KEY, \
+            name TEXT NOT NULL, \
+            up_sql TEXT NOT NULL, \
+            down_sql TEXT NOT NULL, \
+            schema_snapshot TEXT NOT NULL, \
+            applied_at TEXT NOT NULL)",
+        MIGRATIONS_TABLE
+    )
+}
+
+/// Query di lettura dello storico, dalla migrazione più recente.
+fn history_select_sql() -> String {
+    format!(
+        "SELECT version, name, up_sql, down_sql, schema_snapshot, applied_at FROM {} ORDER BY version DESC",
+        MIGRATIONS_TABLE
+    )
+}
+
+/// Separatore tra le istruzioni salvate nello storico.
+const STATEMENT_SEPARATOR: &str = ";\n";
+
+/// Gestore delle migrazioni basato sulla connessione del `ConnectionManager`.
+pub struct MigrationManager {
+    db_type: DatabaseType,
+    connection_manager: ConnectionManager,
+}
+
+impl MigrationManager {
+    /// Crea un nuovo gestore delle migrazioni per il database configurato.
+    ///
+    /// # Parametri
+    /// - `db_type`: Configurazione del database, non può essere `DatabaseType::None`.
+    pub fn new(db_type: DatabaseType) -> Result<Self, MigrationError> {
+        if let DatabaseType::None = db_type {
+            return Err(MigrationError::ConnectionError(
+                "Database non configurato. Digitare 'arx db --help' per ulteriori informazioni".to_string(),
+            ));
+        }
+        Ok(MigrationManager {
+            connection_manager: ConnectionManager::new(db_type.clone()),
+            db_type,
+        })
+    }
+
+    /// Restituisce le migrazioni applicate, dalla più recente.
+    pub async fn history(&self) -> Result<Vec<AppliedMigration>, MigrationError> {
+        match self.connection_manager.connect().await? {
+            DbConnection::Postgres(mut conn) => load_history_pg(&mut conn),
+            DbConnection::SQLite(mut conn) => load_history_sqlite(&mut conn),
+            DbConnection::MongoDB(client) => load_history_mongo(&client).await,
+        }
+    }
+
+    /// Calcola il piano di migrazione senza applicarlo (`arx db plan`).
+    pub async fn plan(&self) -> Result<MigrationPlan, MigrationError> {
+        let history = self.history().await?;
+        let current = history
+            .first()
+            .map(|m| parse_snapshot(&m.schema_snapshot))
+            .unwrap_or_default();
+        let desired = desired_schema(&self.db_type)?;
+        Ok(plan_migration(&current, &desired, &self.db_type))
+    }
+
+    /// Restituisce le migrazioni applicate e il piano in attesa (`arx db status`).
+    pub async fn status(&self) -> Result<MigrationStatus, MigrationError> {
+        Ok(MigrationStatus {
+            applied: self.history().await?,
+            pending: self.plan().await?,
+        })
+    }
+
+    /// Applica il piano di migrazione e lo registra nello storico (`arx db migrate`).
+    ///
+    /// # Parametri
+    /// - `name`: Nome descrittivo opzionale, di default `auto_<timestamp>`.
+    ///
+    /// # Ritorna
+    /// La migrazione applicata, oppure `None` se il database è già allineato.
+    pub async fn migrate(&self, name: Option<String>) -> Result<Option<AppliedMigration>, MigrationError> {
+        let history = self.history().await?;
+        let plan = self.plan().await?;
+        for warning in &plan.warnings {
+            warn!("{}", warning);
+        }
+        if plan.is_empty() {
+            info!("Schema del database già allineato con i modelli");
+            return Ok(None);
+        }
+
+        let now = chrono::Local::now();
+        let migration = AppliedMigration {
+            version: history.first().map(|m| m.version + 1).unwrap_or(1),
+            name: name.unwrap_or_else(|| format!("auto_{}", now.format("%Y%m%d%H%M%S"))),
+            up_sql: plan.up.join(STATEMENT_SEPARATOR),
+            down_sql: plan.down.join(STATEMENT_SEPARATOR),
+            schema_snapshot: serialize_snapshot(&plan.target),
+            applied_at: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+        };
+
+        match self.connection_manager.connect().await? {
+            DbConnection::Postgres(mut conn) => {
+                conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                    for statement in &plan.up {
+                        diesel::sql_query(statement.as_str()).execute(conn)?;
+                    }
+                    diesel::sql_query(format!(
+                        "INSERT INTO {} (version, name, up_sql, down_sql, schema_snapshot, applied_at) \
+                         VALUES ($1, $2, $3, $4, $5, $6)",
+                        MIGRATIONS_TABLE
+                    ))
+                    .bind::<Integer, _>(migration.version)
+                    .bind::<Text, _>(&migration.name)
+                    .bind::<Text, _>(&migration.up_sql)
+                    .bind::<Text, _>(&migration.down_sql)
+                    .bind::<Text, _>(&migration.schema_snapshot)
+                    .bind::<Text, _>(&migration.applied_at)
+                    .execute(conn)?;
+                    Ok(())
+                })?;
+            }
+            DbConnection::SQLite(mut conn) => {
+                conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                    for statement in &plan.up {
+                        diesel::sql_query(statement.as_str()).execute(conn)?;
+                    }
+                    diesel::sql_query(format!(
+                        "INSERT INTO {} (version, name, up_sql, down_sql, schema_snapshot, applied_at) \
+                         VALUES (?, ?, ?, ?, ?, ?)",
+                        MIGRATIONS_TABLE
+                    ))
+                    .bind::<Integer, _>(migration.version)
+                    .bind::<Text, _>(&migration.name)
+                    .bind::<Text, _>(&migration.up_sql)
+                    .bind::<Text, _>(&migration.down_sql)
+                    .bind::<Text, _>(&migration.schema_snapshot)
+                    .bind::<Text, _>(&migration.applied_at)
+                    .execute(conn)?;
+                    Ok(())
+                })?;
+            }
+            DbConnection::MongoDB(client) => {
+                let db = client.database(MONGO_DATABASE);
+                for statement in &plan.up {
+                    run_mongo_statement(&db, statement).await?;
+                }
+                db.collection::<Document>(MIGRATIONS_TABLE)
+                    .insert_one(migration_to_document(&migration))
+                    .await?;
+            }
+        }
+
+        info!("Migrazione {} ({}) applicata", migration.version, migration.name);
+        Ok(Some(migration))
+    }
+
+    /// Annulla le ultime `steps` migrazioni eseguendo le istruzioni `down` (`arx db rollback <n>`).
+    ///
+    /// # Ritorna
+    /// Le migrazioni annullate, dalla più recente.
+    pub async fn rollback(&self, steps: u32) -> Result<Vec<AppliedMigration>, MigrationError> {
+        let history = self.history().await?;
+        if steps == 0 {
+            return Err(MigrationError::InvalidRollback("Il numero di migrazioni deve essere maggiore di 0".to_string()));
+        }
+        if steps as usize > history.len() {
+            return Err(MigrationError::InvalidRollback(format!(
+                "Richiesto rollback di {} migrazioni, ma ne sono state applicate {}",
+                steps, history.len()
+            )));
+        }
+        let reverted: Vec<AppliedMigration> = history.into_iter().take(steps as usize).collect();
+
+        match self.connection_manager.connect().await? {
+            DbConnection::Postgres(mut conn) => {
+                conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                    for migration in &reverted {
+                        for statement in split_statements(&migration.down_sql) {
+                            diesel::sql_query(statement).execute(conn)?;
+                        }
+                        diesel::sql_query(format!("DELETE FROM {} WHERE version = $1", MIGRATIONS_TABLE))
+                            .bind::<Integer, _>(migration.version)
+                            .execute(conn)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            DbConnection::SQLite(mut conn) => {
+                conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                    for migration in &reverted {
+                        for statement in split_statements(&migration.down_sql) {
+                            diesel::sql_query(statement).execute(conn)?;
+                        }
+                        diesel::sql_query(format!("DELETE FROM {} WHERE version = ?", MIGRATIONS_TABLE))
+                            .bind::<Integer, _>(migration.version)
+                            .execute(conn)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            DbConnection::MongoDB(client) => {
+                let db = client.database(MONGO_DATABASE);
+                for migration in &reverted {
+                    for statement in split_statements(&migration.down_sql) {
+                        run_mongo_statement(&db, statement).await?;
+                    }
+                    db.collection::<Document>(MIGRATIONS_TABLE)
+                        .delete_one(doc! { "version": migration.version })
+                        .await?;
+                }
+            }
+        }
+
+        for migration in &reverted {
+            info!("Migrazione {} ({}) annullata", migration.version, migration.name);
+        }
+        Ok(reverted)
+    }
+}
+
+/// Divide le istruzioni salvate nello storico.
+fn split_statements(sql: &str) -> Vec<&str> {
+    sql.split(STATEMENT_SEPARATOR).filter(|s| !s.trim().is_empty()).collect()
+}
+
+/// Legge lo storico delle migrazioni da PostgreSQL, creando la tabella se non esiste.
+fn load_history_pg(conn: &mut PgConnection) -> Result<Vec<AppliedMigration>, MigrationError> {
+    diesel::sql_query(history_table_sql()).execute(conn)?;
+    Ok(diesel::sql_query(history_select_sql()).load::<AppliedMigration>(conn)?)
+}
+
+/// Legge lo storico delle migrazioni da SQLite, creando la tabella se non esiste.
+fn load_history_sqlite(conn: &mut SqliteConnection) -> Result<Vec<AppliedMigration>, MigrationError> {
+    diesel::sql_query(history_table_sql()).execute(conn)?;
+    Ok(diesel::sql_query(history_select_sql()).load::<AppliedMigration>(conn)?)
+}
+
+/// Legge lo storico delle migrazioni dalla collezione MongoDB `arx_migrations`.
+async fn load_history_mongo(client: &mongodb::Client) -> Result<Vec<AppliedMigration>, MigrationError> {
+    let collection = client.database(MONGO_DATABASE).collection::<Document>(MIGRATIONS_TABLE);
+    let mut cursor = collection
+        .find(doc! {})
+        .sort(doc! { "version": -1 })
+        .await?;
+
+    let mut documents = Vec::new();
+    while cursor.advance().await? {
+        documents.push(cursor.deserialize_current()?);
+    }
+
+    Ok(documents
+        .iter()
+        .map(|d| AppliedMigration {
+            version: d.get_i32("version").unwrap_or_default(),
+            name: d.get_str("name").unwrap_or_default().to_string(),
+            up_sql: d.get_str("up_sql").unwrap_or_default().to_string(),
+            down_sql: d.get_str("down_sql").unwrap_or_default().to_string(),
+            schema_snapshot: d.get_str("schema_snapshot").unwrap_or_default().to_string(),
+            applied_at: d.get_str("applied_at").unwrap_or_default().to_string(),
+        })
+        .collect())
+}
+
+/// Converte una migrazione nel documento salvato su MongoDB.
+fn migration_to_document(migration: &AppliedMigration) -> Document {
+    doc! {
+        "version": migration.version,
+        "name": &migration.name,
+        "up_sql": &migration.up_sql,
+        "down_sql": &migration.down_sql,
+        "schema_snapshot": &migration.schema_snapshot,
+        "applied_at": &migration.applied_at,
+    }
+}
+
+/// Esegue un'istruzione di migrazione MongoDB (`createCollection` o `dropCollection`).
+async fn run_mongo_statement(db: &mongodb::Database, statement: &str) -> Result<(), MigrationError> {
+    match statement.split_once(' ') {
+        Some(("createCollection", collection)) => db.create_collection(collection).await?,
+        Some(("dropCollection", collection)) => db.collection::<Document>(collection).drop().await?,
+        _ => warn!("Istruzione MongoDB non riconosciuta: {}", statement),
+    }
+    Ok(())
+}
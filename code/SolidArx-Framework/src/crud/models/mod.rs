@@ -2,6 +2,9 @@ pub mod dev{}
 pub mod default{
     pub mod api_key;
     pub mod article;
+    pub mod article_category;
+    pub mod article_tag;
+    pub mod audit_entry;
     pub mod category;
     pub mod command;
     pub mod comment;
@@ -31,4 +34,6 @@ pub mod default{
     pub mod user;
 }
 pub mod table_scraper;
-pub mod table_generator;
\ No newline at end of file
+pub mod model_generator;
+pub mod migrations;
+pub mod table_introspector;
\ No newline at end of file
@@ -0,0 +1,456 @@
+//! Modulo per la generazione dei modelli CRUD personalizzati.
+//!
+//! Questo modulo è utilizzato dal comando `arx model new` per creare un nuovo file modello
+//! all'interno di `crud/models/dev`, seguendo le stesse convenzioni dei modelli in `crud/models/default`:
+//! blocco `cfg_if!`, campi `store` e `ops`, costruttore `new` e `#[derive(Serialize, Deserialize, ArxModel)]`.
+//!
+//! ### Passaggi eseguiti dal generatore:
+//! 1. Validazione del nome del modello e dei campi passati dal CLI.
+//! 2. Scrittura del file `crud/models/dev/<nome_modello>.rs`.
+//! 3. Registrazione del modulo nel blocco `pub mod dev{}` di `models/mod.rs`.
+//!
+//! Le implementazioni CRUD vengono generate da `#[derive(ArxModel)]`, quindi non è necessario modificare `crud_ops.rs`.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{info, warn};
+
+use crate::crud::crud_ops::{AllocType, CrudOperations};
+
+/// Percorso di default della cartella `models`, relativo alla root del progetto.
+/// Il percorso assoluto viene risolto da `models_path`.
+pub const MODELS_PATH: &str = "src/crud/models";
+
+/// Tipi Rust supportati per i campi dei modelli generati.
+/// Sono gli stessi tipi gestiti da `map_to_sql` e `map_to_mongo` in `table_scraper.rs`.
+const SUPPORTED_TYPES: [&str; 19] = [
+    "u16", "u32", "i32", "u64", "i64",
+    "f32", "f64",
+    "bool", "String",
+    "Option<u32>", "Option<i32>", "Option<u64>", "Option<i64>",
+    "Option<f32>", "Option<f64>", "Option<bool>", "Option<String>",
+    "chrono::NaiveDateTime", "Option<chrono::NaiveDateTime>",
+];
+
+/// Campi riservati gestiti direttamente dal generatore.
+const RESERVED_FIELDS: [&str; 5] = ["id", "store", "ops", "memory", "revocation"];
+
+/// Parole chiave di Rust (edizione 2021, comprese quelle riservate), non utilizzabili come nomi dei campi.
+const RUST_KEYWORDS: [&str; 51] = [
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "async", "await", "dyn", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "typeof", "unsized", "virtual", "yield", "try",
+];
+
+/// Definizione di un campo del modello da generare.
+///
+/// # Campi
+/// - `name`: Nome del campo in snake_case.
+/// - `ty`: Tipo Rust del campo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSpec {
+    pub name: String,
+    pub ty: String,
+}
+
+impl FieldSpec {
+    /// Esegue il parsing di un campo nel formato `nome:tipo` passato dal CLI.
+    ///
+    /// # Parametri
+    /// - `input`: Stringa nel formato `nome:tipo` (es. `amount:f64`).
+    ///
+    /// # Ritorna
+    /// Un `FieldSpec` valido o un messaggio di errore se il formato o il tipo non sono supportati.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let (name, ty) = input
+            .split_once(':')
+            .ok_or_else(|| format!("Campo non valido '{}': formato atteso nome:tipo", input))?;
+        let name = name.trim().to_string();
+        let ty = ty.trim().to_string();
+
+        if !is_snake_case(&name) {
+            return Err(format!("Nome del campo non valido '{}': usare snake_case", name));
+        }
+        if RUST_KEYWORDS.contains(&name.as_str()) {
+            return Err(format!("Nome del campo non valido '{}': è una parola chiave di Rust", name));
+        }
+        if RESERVED_FIELDS.contains(&name.as_str()) {
+            return Err(format!("Il campo '{}' è riservato e viene generato automaticamente", name));
+        }
+        if !SUPPORTED_TYPES.contains(&ty.as_str()) {
+            return Err(format!(
+                "Tipo '{}' non supportato per il campo '{}'. Tipi supportati: {}",
+                ty, name, SUPPORTED_TYPES.join(", ")
+            ));
+        }
+
+        Ok(FieldSpec { name, ty })
+    }
+}
+
+/// Definizione completa di un modello da generare.
+///
+/// # Campi
+/// - `name`: Nome della struct in PascalCase (es. `Invoice`).
+/// - `fields`: Campi del modello, escluso `id` che viene sempre generato.
+/// - `store`: Tipo di allocazione del modello (`InMemory`, `Database` o `Cached`).
+/// - `ops`: Operazioni CRUD abilitate per il modello.
+#[derive(Debug, Clone)]
+pub struct ModelSpec {
+    pub name: String,
+    pub fields: Vec<FieldSpec>,
+    pub store: AllocType,
+    pub ops: CrudOperations,
+}
+
+impl ModelSpec {
+    /// Crea una nuova specifica di modello a partire dagli argomenti del CLI.
+    ///
+    /// # Parametri
+    /// - `name`: Nome del modello in PascalCase.
+    /// - `fields`: Campi nel formato `nome:tipo`.
+    /// - `store`: Tipo di allocazione (`database`, `memory` o `cached`).
+    /// - `ops`: Lista di operazioni separate da virgola (es. `create,read,list`).
+    ///
+    /// # Ritorna
+    /// Un `ModelSpec` valido o un messaggio di errore.
+    pub fn new(name: &str, fields: &[String], store: &str, ops: &str) -> Result<Self, String> {
+        if !is_pascal_case(name) || RUST_KEYWORDS.contains(&name) {
+            return Err(format!("Nome del modello non valido '{}': usare PascalCase (es. Invoice)", name));
+        }
+
+        let mut parsed_fields: Vec<FieldSpec> = Vec::new();
+        for field in fields {
+            let field = FieldSpec::parse(field)?;
+            if parsed_fields.iter().any(|f| f.name == field.name) {
+                return Err(format!("Campo '{}' definito più volte", field.name));
+            }
+            parsed_fields.push(field);
+        }
+
+        Ok(ModelSpec {
+            name: name.to_string(),
+            fields: parsed_fields,
+            store: parse_store(store)?,
+            ops: parse_ops(ops)?,
+        })
+    }
+
+    /// Nome del modulo e del file del modello in snake_case (es. `SensorReading` -> `sensor_reading`).
+    pub fn module_name(&self) -> String {
+        to_snake_case(&self.name)
+    }
+}
+
+/// Converte il valore `--store` del CLI in un `AllocType`.
+pub fn parse_store(input: &str) -> Result<AllocType, String> {
+    match input.to_lowercase().as_str() {
+        "database" | "db" => Ok(AllocType::Database),
+        "memory" | "inmemory" | "in-memory" | "mem" => Ok(AllocType::InMemory),
+        "cached" | "cache" => Ok(AllocType::Cached),
+        _ => Err(format!("Tipo di allocazione non riconosciuto: {} (usare database, memory o cached)", input)),
+    }
+}
+
+/// Converte il valore `--ops` del CLI in un `CrudOperations`.
+///
+/// Le operazioni non elencate vengono disabilitate.
+pub fn parse_ops(input: &str) -> Result<CrudOperations, String> {
+    let mut ops = CrudOperations {
+        create: false,
+        read: false,
+        update: false,
+        delete: false,
+        list: false,
+        search: false,
+        revoke: false,
+    };
+
+    for op in input.split(',').map(|op| op.trim().to_lowercase()).filter(|op| !op.is_empty()) {
+        match op.as_str() {
+            "create" => ops.create = true,
+            "read" => ops.read = true,
+            "update" => ops.update = true,
+            "delete" => ops.delete = true,
+            "list" => ops.list = true,
+            "search" => ops.search = true,
+            "revoke" => ops.revoke = true,
+            _ => return Err(format!("Operazione CRUD non riconosciuta: {}", op)),
+        }
+    }
+
+    Ok(ops)
+}
+
+/// Genera il codice sorgente del modello secondo le convenzioni di `crud/models/default`.
+///
+/// # Parametri
+/// - `spec`: Specifica del modello da generare.
+///
+/// # Ritorna
+/// Il contenuto del file `.rs` del modello.
+pub fn render_model(spec: &ModelSpec) -> String {
+    let in_memory = matches!(spec.store, AllocType::InMemory);
+
+    let mut struct_fields = String::from("                pub id: u32,\n");
+    for field in &spec.fields {
+        struct_fields.push_str(&format!("                pub {}: {},\n", field.name, field.ty));
+    }
+    // I modelli revocabili registrano la revoca nel campo `revocation`, richiesto da `#[derive(ArxModel)]`
+    if spec.ops.revoke {
+        struct_fields.push_str("                pub revocation: Option<Revocation>,\n");
+    }
+    // I campi del framework non vengono serializzati, `store` e `ops` vengono ricostruiti dal modello
+    struct_fields.push_str(&format!("                #[serde(skip, default = \"{}::default_store\")]\n", spec.name));
+    struct_fields.push_str("                pub store: AllocType,\n");
+    if in_memory {
+        struct_fields.push_str("                #[serde(skip)]\n");
+        struct_fields.push_str("                pub memory: Box<[u8]>,\n");
+    }
+    struct_fields.push_str(&format!("                #[serde(skip, default = \"{}::declared_operations\")]\n", spec.name));
+    struct_fields.push_str("                pub ops: CrudOperations,\n");
+
+    let mut params = vec!["id: u32".to_string()];
+    params.extend(spec.fields.iter().map(|f| format!("{}: {}", f.name, f.ty)));
+    if in_memory {
+        params.push("memory: Box<[u8]>".to_string());
+    }
+
+    let mut init_fields = String::from("                        id,\n");
+    for field in &spec.fields {
+        init_fields.push_str(&format!("                        {},\n", field.name));
+    }
+    if spec.ops.revoke {
+        init_fields.push_str("                        revocation: None,\n");
+    }
+    init_fields.push_str(&format!("                        store: AllocType::{:?},\n", spec.store));
+    if in_memory {
+        init_fields.push_str("                        memory,\n");
+    }
+
+    let ops: Vec<&str> = [
+        ("create", spec.ops.create),
+        ("read", spec.ops.read),
+        ("update", spec.ops.update),
+        ("delete", spec.ops.delete),
+        ("list", spec.ops.list),
+        ("search", spec.ops.search),
+        ("revoke", spec.ops.revoke),
+    ]
+    .iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(op, _)| *op)
+    .collect();
+
+    format!(
+"pub mod model {{
+
+    // Usato per incapsulare i blocchi in relazione al `cfg` attivo per la generazione di codice in compile time
+    use cfg_if::cfg_if;
+
+
+    cfg_if! {{
+        /*
+        Seppur ridondante in relazione al `crud_ops` è necessario per rendere
+        la generazione delle tables selettiva per `table_scraper.rs`
+        */
+        if #[cfg(any(
+                    feature = \"webapp\",
+                    feature = \"api\",
+                    feature = \"desktop\",
+                    feature = \"automation\",
+                    feature = \"embedded\"
+                ))] {{
+
+            use crate::crud::crud_ops::AllocType;
+            use crate::crud::crud_ops::CrudOperations;
+            use crate::crud::crud_ops::ArxModel;
+            use serde::{{Deserialize, Serialize}};
+{revocation_import}
+            // Modello generato tramite `arx model new`
+            #[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]
+            #[arx(store = \"{arx_store}\", ops = \"{arx_ops}\")]
+            pub struct {name} {{
+{struct_fields}            }}
+
+            impl {name} {{
+                pub fn new({params}) -> Self {{
+                    {name} {{
+{init_fields}                        ops: CrudOperations{{
+                            create: {create},
+                            read: {read},
+                            update: {update},
+                            delete: {delete},
+                            list: {list},
+                            search: {search},
+                            revoke: {revoke},
+                        }},
+                    }}
+                }}
+            }}
+        }}
+    }}
+}}
+",
+        name = spec.name,
+        revocation_import = if spec.ops.revoke { "            use crate::crud::revocation::Revocation;\n" } else { "" },
+        arx_store = match spec.store {
+            AllocType::InMemory => "memory",
+            AllocType::Database => "database",
+            AllocType::Cached => "cached",
+        },
+        arx_ops = ops.join(","),
+        struct_fields = struct_fields,
+        params = params.join(", "),
+        init_fields = init_fields,
+        create = spec.ops.create,
+        read = spec.ops.read,
+        update = spec.ops.update,
+        delete = spec.ops.delete,
+        list = spec.ops.list,
+        search = spec.ops.search,
+        revoke = spec.ops.revoke,
+    )
+}
+
+/// Registra il modulo del modello nel blocco `pub mod dev{}` di `models/mod.rs`.
+///
+/// # Parametri
+/// - `mod_rs`: Contenuto attuale di `models/mod.rs`.
+/// - `module_name`: Nome del modulo da registrare.
+///
+/// # Ritorna
+/// Il nuovo contenuto di `models/mod.rs` o un errore se il blocco `dev` non è presente
+/// oppure il modulo è già registrato.
+pub fn register_dev_module(mod_rs: &str, module_name: &str) -> Result<String, String> {
+    let declaration = format!("pub mod {};", module_name);
+    let start = mod_rs
+        .find("pub mod dev")
+        .ok_or_else(|| "Blocco `pub mod dev` non trovato in models/mod.rs".to_string())?;
+    let open = start + mod_rs[start..]
+        .find('{')
+        .ok_or_else(|| "Blocco `pub mod dev` non valido in models/mod.rs".to_string())?;
+    let close = open + mod_rs[open..]
+        .find('}')
+        .ok_or_else(|| "Blocco `pub mod dev` non chiuso in models/mod.rs".to_string())?;
+
+    let body = &mod_rs[open + 1..close];
+    if body.lines().any(|line| line.trim() == declaration) {
+        return Err(format!("Il modulo '{}' è già registrato in models/mod.rs", module_name));
+    }
+
+    // Mantiene l'ordine alfabetico dei moduli, come nel blocco `default`
+    let mut modules: Vec<String> = body
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    modules.push(declaration);
+    modules.sort();
+
+    let mut new_body = String::from("\n");
+    for module in modules {
+        new_body.push_str(&format!("    {}\n", module));
+    }
+
+    Ok(format!("{}{}{}", &mod_rs[..=open], new_body, &mod_rs[close..]))
+}
+
+/// Restituisce il percorso della cartella `models` del progetto.
+///
+/// Risale dalla cartella corrente fino alla prima che contiene `MODELS_PATH`, in modo che il CLI
+/// funzioni anche se eseguito da una sottocartella del progetto. In mancanza, utilizza la cartella
+/// del manifest con cui è stato compilato il CLI.
+pub fn models_path() -> PathBuf {
+    env::current_dir()
+        .ok()
+        .and_then(|dir| {
+            dir.ancestors()
+                .map(|ancestor| ancestor.join(MODELS_PATH))
+                .find(|path| path.join("mod.rs").is_file())
+        })
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(MODELS_PATH))
+}
+
+/// Genera il modello e lo registra in `models/mod.rs`.
+///
+/// # Parametri
+/// - `spec`: Specifica del modello da generare.
+/// - `models_path`: Percorso della cartella `models` (di default `models_path()`).
+///
+/// # Ritorna
+/// Il percorso del file generato o un messaggio di errore.
+///
+/// # Note
+/// Tutti i contenuti vengono preparati prima di scrivere su disco, in modo da non lasciare
+/// il progetto in uno stato parziale se una delle fasi fallisce.
+pub fn generate_model(spec: &ModelSpec, models_path: &Path) -> Result<PathBuf, String> {
+    let dev_dir = models_path.join("dev");
+    let model_file = dev_dir.join(format!("{}.rs", spec.module_name()));
+    let mod_rs_path = models_path.join("mod.rs");
+
+    if model_file.exists() {
+        return Err(format!("Il modello {} esiste già", model_file.display()));
+    }
+    if models_path.join("default").join(format!("{}.rs", spec.module_name())).exists() {
+        warn!("Esiste già un modello di default con nome '{}'", spec.module_name());
+    }
+
+    let mod_rs = fs::read_to_string(&mod_rs_path)
+        .map_err(|e| format!("Errore nella lettura di {}: {}", mod_rs_path.display(), e))?;
+
+    let model_rs = render_model(spec);
+    let new_mod_rs = register_dev_module(&mod_rs, &spec.module_name())?;
+
+    fs::create_dir_all(&dev_dir)
+        .map_err(|e| format!("Errore nella creazione della cartella {}: {}", dev_dir.display(), e))?;
+    fs::write(&model_file, model_rs)
+        .map_err(|e| format!("Errore nella scrittura di {}: {}", model_file.display(), e))?;
+    fs::write(&mod_rs_path, new_mod_rs)
+        .map_err(|e| format!("Errore nella scrittura di {}: {}", mod_rs_path.display(), e))?;
+
+    info!("Modello {} generato in {}", spec.name, model_file.display());
+    Ok(model_file)
+}
+
+/// Converte un nome di tabella in PascalCase (es. `api_key` -> `ApiKey`).
+///
+/// Utilizzata da `table_introspector.rs` per derivare il nome dei modelli dalle tabelle esistenti.
+pub fn to_pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Verifica che il nome sia in PascalCase e sia un identificatore Rust valido.
+fn is_pascal_case(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_uppercase() => chars.all(|c| c.is_ascii_alphanumeric()),
+        _ => false,
+    }
+}
+
+/// Verifica che il nome sia in snake_case e sia un identificatore Rust valido.
+fn is_snake_case(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_lowercase() || first == '_' => {
+            chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+/// Converte un nome PascalCase in snake_case, con la stessa implementazione di `#[derive(ArxModel)]`.
+pub use arx_common::to_snake_case;
@@ -0,0 +1,347 @@
+//! Modulo per la generazione dei modelli CRUD a partire da un database esistente.
+//!
+//! Utilizzato dal comando `arx db introspect`, esegue l'operazione inversa di `table_scraper.rs`:
+//! legge lo schema di un database SQLite o PostgreSQL (o campiona i documenti di una collezione MongoDB)
+//! e produce le specifiche dei modelli nel formato di `crud/models/dev`, tramite `model_generator.rs`.
+//!
+//! I modelli generati utilizzano `AllocType::Database` e le operazioni CRUD di default,
+//! in modo che le tabelle esistenti siano subito gestibili dal framework.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Text};
+use diesel::{PgConnection, sqlite::SqliteConnection};
+use mongodb::bson::{doc, Bson, Document};
+use log::{info, warn};
+
+use crate::config::network_config::DatabaseType;
+use crate::network::connection_management::{ConnectionManager, DatabaseConnection, DbConnection};
+use crate::crud::crud_ops::AllocType;
+use crate::crud::models::migrations::{MigrationError, MIGRATIONS_TABLE, MONGO_DATABASE};
+use crate::crud::mongo_store::COUNTERS_COLLECTION;
+use crate::crud::models::model_generator::{
+    generate_model,
+    parse_ops,
+    to_pascal_case,
+    FieldSpec,
+    ModelSpec,
+};
+
+/// Operazioni CRUD abilitate di default sui modelli generati.
+const DEFAULT_OPS: &str = "create,read,update,delete,list";
+/// Colonne generate dal framework che non fanno parte dei campi del modello.
+const FRAMEWORK_COLUMNS: [&str; 4] = ["id", "store", "ops", "memory"];
+
+/// Colonna letta dallo schema del database.
+///
+/// # Campi
+/// - `name`: Nome della colonna.
+/// - `db_type`: Tipo della colonna nel database (SQL o BSON).
+/// - `nullable`: Indica se la colonna ammette valori nulli.
+#[derive(Debug, Clone)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub db_type: String,
+    pub nullable: bool,
+}
+
+/// Riga restituita dalle query sullo schema SQL.
+#[derive(QueryableByName)]
+struct ColumnRow {
+    #[diesel(sql_type = Text)]
+    table_name: String,
+    #[diesel(sql_type = Text)]
+    column_name: String,
+    #[diesel(sql_type = Text)]
+    data_type: String,
+    #[diesel(sql_type = Integer)]
+    nullable: i32,
+}
+
+/// Funzione che mappa un tipo SQL al tipo Rust corrispondente, inversa di `map_to_sql`.
+///
+/// # Argomenti
+/// * `column` - Il nome della colonna, usato per riconoscere gli identificativi (`id`, `*_id`).
+/// * `sql_type` - Il tipo SQL della colonna.
+/// * `nullable` - Indica se la colonna ammette valori nulli.
+///
+/// # Ritorna
+/// Il tipo Rust da utilizzare nel modello.
+///
+/// # Note
+/// Le colonne nullabili sono mappate su `Option` del tipo base, in modo che i valori `NULL` siano leggibili.
+pub fn map_from_sql(column: &str, sql_type: &str, nullable: bool) -> &'static str {
+    let sql_type = sql_type.to_uppercase();
+    let is_id = column == "id" || column.ends_with("_id");
+    let base = match sql_type.split(['(', ' ']).next().unwrap_or("") {
+        "INTEGER" | "INT" | "INT4" | "SMALLINT" | "INT2" | "SERIAL" | "MEDIUMINT" => {
+            if is_id { "u32" } else { "i32" }
+        }
+        // `version: u64` abilita il controllo ottimistico della concorrenza (vedi `ArxModel::VERSIONED`)
+        "BIGINT" | "INT8" | "BIGSERIAL" if column == "version" => "u64",
+        "BIGINT" | "INT8" | "BIGSERIAL" => "i64",
+        "BOOLEAN" | "BOOL" => "bool",
+        "REAL" | "FLOAT4" => "f32",
+        "DOUBLE" | "FLOAT8" | "FLOAT" | "NUMERIC" | "DECIMAL" => "f64",
+        "TIMESTAMP" | "DATETIME" => "chrono::NaiveDateTime",
+        // TEXT, VARCHAR, CHAR, JSON e tipi sconosciuti vengono gestiti come stringhe
+        _ => "String",
+    };
+
+    if !nullable {
+        return base;
+    }
+    match base {
+        "u32" => "Option<u32>",
+        "i32" => "Option<i32>",
+        "u64" => "Option<u64>",
+        "i64" => "Option<i64>",
+        "bool" => "Option<bool>",
+        "f32" => "Option<f32>",
+        "f64" => "Option<f64>",
+        "chrono::NaiveDateTime" => "Option<chrono::NaiveDateTime>",
+        _ => "Option<String>",
+    }
+}
+
+/// Funzione che restituisce il tipo MongoDB di un valore BSON, con la nomenclatura di `map_to_mongo`.
+///
+/// # Argomenti
+/// * `value` - Il valore BSON campionato.
+///
+/// # Ritorna
+/// Il nome del tipo BSON rilevato (es. `int`, `string`) usato per confrontare i campioni.
+pub fn bson_type_name(value: &Bson) -> &'static str {
+    match value {
+        Bson::Int32(_) => "int",
+        Bson::Int64(_) => "long",
+        Bson::Double(_) => "double",
+        Bson::Boolean(_) => "bool",
+        Bson::DateTime(_) => "date",
+        Bson::Null => "null",
+        _ => "string",
+    }
+}
+
+/// Funzione che mappa un tipo MongoDB al tipo Rust corrispondente, inversa di `map_to_mongo`.
+///
+/// # Argomenti
+/// * `field` - Il nome del campo.
+/// * `mongo_type` - Il tipo rilevato dai documenti campionati.
+/// * `nullable` - Indica se il campo è assente o nullo in almeno un documento.
+pub fn map_from_mongo(field: &str, mongo_type: &str, nullable: bool) -> &'static str {
+    let sql_type = match mongo_type {
+        "int" => "INTEGER",
+        "long" => "BIGINT",
+        "double" => "DOUBLE PRECISION",
+        "bool" => "BOOLEAN",
+        "date" => "TIMESTAMP",
+        _ => "TEXT",
+    };
+    map_from_sql(field, sql_type, nullable)
+}
+
+/// Converte le colonne di una tabella in una specifica di modello.
+///
+/// # Parametri
+/// - `table`: Nome della tabella o collezione.
+/// - `columns`: Colonne lette dal database.
+/// - `mapper`: Funzione di mappatura dal tipo del database al tipo Rust.
+///
+/// # Ritorna
+/// Una `ModelSpec` con `AllocType::Database` e le operazioni CRUD di default,
+/// oppure un errore se la tabella non ha una colonna `id` (la chiave primaria dei modelli).
+pub fn model_spec_from_columns(
+    table: &str,
+    columns: &[ColumnInfo],
+    mapper: fn(&str, &str, bool) -> &'static str,
+) -> Result<ModelSpec, String> {
+    if !columns.iter().any(|c| c.name == "id") {
+        return Err("nessuna colonna `id`, necessaria come chiave primaria del modello".to_string());
+    }
+
+    let mut fields = Vec::new();
+    for column in columns {
+        if FRAMEWORK_COLUMNS.contains(&column.name.as_str()) {
+            continue;
+        }
+        let field = format!("{}:{}", column.name, mapper(&column.name, &column.db_type, column.nullable));
+        match FieldSpec::parse(&field) {
+            Ok(field) => fields.push(field),
+            Err(e) => warn!("Colonna {}.{} ignorata: {}", table, column.name, e),
+        }
+    }
+
+    Ok(ModelSpec {
+        name: to_pascal_case(table),
+        fields,
+        store: AllocType::Database,
+        ops: parse_ops(DEFAULT_OPS)?,
+    })
+}
+
+/// Legge le colonne di tutte le tabelle da SQLite.
+fn introspect_sqlite(conn: &mut SqliteConnection) -> Result<BTreeMap<String, Vec<ColumnInfo>>, MigrationError> {
+    let rows = diesel::sql_query(
+        "SELECT m.name AS table_name, p.name AS column_name, p.type AS data_type, \
+         CASE WHEN p.\"notnull\" = 0 AND p.pk = 0 THEN 1 ELSE 0 END AS nullable \
+         FROM sqlite_master m JOIN pragma_table_info(m.name) p \
+         WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%' \
+         ORDER BY m.name, p.cid",
+    )
+    .load::<ColumnRow>(conn)?;
+    Ok(group_columns(rows))
+}
+
+/// Legge le colonne di tutte le tabelle dello schema `public` da PostgreSQL.
+fn introspect_pg(conn: &mut PgConnection) -> Result<BTreeMap<String, Vec<ColumnInfo>>, MigrationError> {
+    let rows = diesel::sql_query(
+        "SELECT table_name::text AS table_name, column_name::text AS column_name, \
+         data_type::text AS data_type, \
+         CASE WHEN is_nullable = 'YES' THEN 1 ELSE 0 END AS nullable \
+         FROM information_schema.columns \
+         WHERE table_schema = 'public' \
+         ORDER BY table_name, ordinal_position",
+    )
+    .load::<ColumnRow>(conn)?;
+    Ok(group_columns(rows))
+}
+
+/// Raggruppa le righe lette dallo schema SQL per tabella.
+fn group_columns(rows: Vec<ColumnRow>) -> BTreeMap<String, Vec<ColumnInfo>> {
+    let mut tables: BTreeMap<String, Vec<ColumnInfo>> = BTreeMap::new();
+    for row in rows {
+        tables.entry(row.table_name).or_default().push(ColumnInfo {
+            name: row.column_name,
+            db_type: row.data_type,
+            nullable: row.nullable != 0,
+        });
+    }
+    tables
+}
+
+/// Campiona i documenti delle collezioni MongoDB e ne ricava i campi.
+///
+/// # Parametri
+/// - `client`: Client MongoDB.
+/// - `sample_size`: Numero di documenti campionati per collezione.
+///
+/// # Note
+/// Un campo assente o nullo in almeno un documento viene considerato nullabile.
+/// Se un campo ha tipi diversi nei campioni viene mappato come stringa.
+async fn introspect_mongo(
+    client: &mongodb::Client,
+    sample_size: u32,
+) -> Result<BTreeMap<String, Vec<ColumnInfo>>, MigrationError> {
+    let db = client.database(MONGO_DATABASE);
+    let mut tables = BTreeMap::new();
+
+    for collection_name in db.list_collection_names().await? {
+        let collection = db.collection::<Document>(&collection_name);
+        let mut cursor = collection
+            .aggregate(vec![doc! { "$sample": { "size": sample_size as i64 } }])
+            .await?;
+
+        let mut samples: u32 = 0;
+        // campo -> (tipo rilevato, occorrenze non nulle)
+        let mut fields: BTreeMap<String, (String, u32)> = BTreeMap::new();
+        while cursor.advance().await? {
+            let document: Document = cursor.deserialize_current()?;
+            samples += 1;
+            for (key, value) in document.iter() {
+                // L'`_id` numerico o testuale è l'ID del modello (vedi `mongo_store.rs`),
+                // mentre l'ObjectId generato da MongoDB non fa parte dei campi
+                let key = match (key.as_str(), value) {
+                    ("_id", Bson::ObjectId(_)) => continue,
+                    ("_id", _) => "id".to_string(),
+                    _ => key.clone(),
+                };
+                let detected = bson_type_name(value);
+                let entry = fields.entry(key).or_insert_with(|| (detected.to_string(), 0));
+                if detected == "null" {
+                    continue;
+                }
+                if entry.0 == "null" {
+                    entry.0 = detected.to_string();
+                } else if entry.0 != detected {
+                    entry.0 = "string".to_string();
+                }
+                entry.1 += 1;
+            }
+        }
+
+        let columns = fields
+            .into_iter()
+            .map(|(name, (db_type, present))| ColumnInfo { name, db_type, nullable: present < samples })
+            .collect();
+        tables.insert(collection_name, columns);
+    }
+
+    Ok(tables)
+}
+
+/// Legge lo schema del database configurato e restituisce le specifiche dei modelli.
+///
+/// # Parametri
+/// - `db_type`: Configurazione del database da analizzare.
+/// - `tables`: Tabelle da includere, tutte se vuoto.
+/// - `sample_size`: Numero di documenti campionati per le collezioni MongoDB.
+///
+/// # Ritorna
+/// Le specifiche dei modelli, esclusa la tabella di storico delle migrazioni.
+pub async fn introspect(
+    db_type: DatabaseType,
+    tables: &[String],
+    sample_size: u32,
+) -> Result<Vec<ModelSpec>, MigrationError> {
+    if let DatabaseType::None = db_type {
+        return Err(MigrationError::ConnectionError("Database non configurato".to_string()));
+    }
+    let connection_manager = ConnectionManager::new(db_type.clone());
+
+    let (schema, mapper): (_, fn(&str, &str, bool) -> &'static str) = match connection_manager.connect().await? {
+        DbConnection::Postgres(mut conn) => (introspect_pg(&mut conn)?, map_from_sql),
+        DbConnection::SQLite(mut conn) => (introspect_sqlite(&mut conn)?, map_from_sql),
+        DbConnection::MongoDB(client) => (introspect_mongo(&client, sample_size).await?, map_from_mongo),
+    };
+
+    let mut specs = Vec::new();
+    for (table, columns) in schema {
+        if table == MIGRATIONS_TABLE || table == COUNTERS_COLLECTION || (!tables.is_empty() && !tables.contains(&table)) {
+            continue;
+        }
+        match model_spec_from_columns(&table, &columns, mapper) {
+            Ok(spec) => specs.push(spec),
+            Err(e) => warn!("Tabella {} ignorata: {}", table, e),
+        }
+    }
+
+    info!("Lette {} tabelle dal database", specs.len());
+    Ok(specs)
+}
+
+/// Genera i file dei modelli in `crud/models/dev` a partire dalle specifiche lette dal database.
+///
+/// # Parametri
+/// - `specs`: Specifiche dei modelli restituite da `introspect`.
+/// - `models_path`: Percorso della cartella `models`.
+///
+/// # Ritorna
+/// I percorsi dei file generati. I modelli già presenti in `default` o `dev` vengono saltati.
+pub fn write_models(specs: &[ModelSpec], models_path: &Path) -> Result<Vec<String>, String> {
+    let mut generated = Vec::new();
+    for spec in specs {
+        let file_name = format!("{}.rs", spec.module_name());
+        let exists = ["default", "dev"]
+            .iter()
+            .any(|dir| models_path.join(dir).join(&file_name).exists());
+        if exists {
+            warn!("Il modello {} esiste già, tabella ignorata", spec.name);
+            continue;
+        }
+        let path = generate_model(spec, models_path)?;
+        generated.push(path.display().to_string());
+    }
+    Ok(generated)
+}
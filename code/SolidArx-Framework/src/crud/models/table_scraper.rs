@@ -1,10 +1,135 @@
 use std::fs;
-use syn::{self, Item, ItemStruct, ItemMod, parse_file};
+use syn::{self, Attribute, Field, Item, ItemStruct, ItemMod, Macro, Meta, Token, Type, parse_file};
+use syn::parse::{ParseStream, Parser};
+use syn::punctuated::Punctuated;
 use std::collections::HashMap;
 use crate::config::network_config::DatabaseType ;
+use crate::crud::models::model_generator::to_snake_case;
+use log::debug;
 // Importa il modulo per  per ottenere la rappresentazione del tipo come una stringa
 use quote::ToTokens; 
 
+/// Struttura estratta da un modello: nome della sezione (`name`, `fields`) -> chiave -> valore.
+pub type ScrapedStruct = HashMap<String, HashMap<String, String>>;
+
+/// Feature del crate e relativo stato nella compilazione corrente, usate per valutare i `#[cfg]` dei modelli.
+const FEATURES: [(&str, bool); 13] = [
+    ("auth", cfg!(feature = "auth")),
+    ("crud", cfg!(feature = "crud")),
+    ("api", cfg!(feature = "api")),
+    ("file_management", cfg!(feature = "file_management")),
+    ("task_automation", cfg!(feature = "task_automation")),
+    ("blockchain", cfg!(feature = "blockchain")),
+    ("ml", cfg!(feature = "ml")),
+    ("frontend", cfg!(feature = "frontend")),
+    ("webapp", cfg!(feature = "webapp")),
+    ("api_backend", cfg!(feature = "api_backend")),
+    ("desktop", cfg!(feature = "desktop")),
+    ("automation", cfg!(feature = "automation")),
+    ("embedded", cfg!(feature = "embedded")),
+];
+
+/// Valuta un predicato `cfg` sulle feature compilate (`feature = "..."`, `any`, `all`, `not`).
+///
+/// I predicati diversi dalle feature (es. `test`, `unix`) vengono considerati non attivi.
+fn cfg_predicate(meta: &Meta) -> bool {
+    match meta {
+        Meta::NameValue(name_value) if name_value.path.is_ident("feature") => {
+            let feature = name_value.value.to_token_stream().to_string();
+            let feature = feature.trim_matches('"');
+            FEATURES.iter().any(|(name, enabled)| *name == feature && *enabled)
+        }
+        Meta::List(list) => {
+            let nested = list
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .unwrap_or_default();
+            if list.path.is_ident("any") {
+                nested.iter().any(cfg_predicate)
+            } else if list.path.is_ident("all") {
+                nested.iter().all(cfg_predicate)
+            } else if list.path.is_ident("not") {
+                !nested.iter().any(cfg_predicate)
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Verifica che tutti gli attributi `#[cfg(...)]` siano attivi con le feature compilate.
+fn is_enabled(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .all(|attr| attr.parse_args::<Meta>().map(|meta| cfg_predicate(&meta)).unwrap_or(false))
+}
+
+/// Funzione che restituisce gli item del primo ramo attivo di un blocco `cfg_if!`.
+///
+/// # Argomenti
+/// * `mac` - La macro da analizzare.
+///
+/// # Ritorna
+/// Gli item del ramo attivo, nessun item se la macro non è `cfg_if!` o nessun ramo è attivo.
+///
+/// # Note
+/// I modelli sono definiti all'interno di `cfg_if! { if #[cfg(...)] { ... } else { ... } }`:
+/// ogni ramo viene analizzato come un file Rust, come farebbe `cfg_if` in compilazione.
+fn parse_cfg_if(mac: &Macro) -> Result<Vec<Item>, String> {
+    if !mac.path.is_ident("cfg_if") {
+        return Ok(Vec::new());
+    }
+
+    let parser = |input: ParseStream| -> syn::Result<Vec<Item>> {
+        let mut active = None;
+        while !input.is_empty() {
+            // `if #[cfg(...)]` introduce un ramo condizionale, altrimenti è il ramo `else` finale
+            let enabled = if input.peek(Token![if]) {
+                input.parse::<Token![if]>()?;
+                is_enabled(&input.call(Attribute::parse_outer)?)
+            } else {
+                true
+            };
+            let content;
+            syn::braced!(content in input);
+            let branch: syn::File = content.parse()?;
+            if active.is_none() && enabled {
+                active = Some(branch.items);
+            }
+            if input.peek(Token![else]) {
+                input.parse::<Token![else]>()?;
+            }
+        }
+        Ok(active.unwrap_or_default())
+    };
+
+    parser
+        .parse2(mac.tokens.clone())
+        .map_err(|e| format!("Errore nel parsing del blocco cfg_if!: {}", e))
+}
+
+/// Funzione che raccoglie le struct attive da una lista di item, visitando moduli e blocchi `cfg_if!`.
+fn collect_structs(items: Vec<Item>) -> Result<Vec<ItemStruct>, String> {
+    let mut structs = Vec::new();
+    for item in items {
+        match item {
+            Item::Struct(item_struct) if is_enabled(&item_struct.attrs) => {
+                structs.push(item_struct); // Aggiungi la struct trovata
+            }
+            Item::Mod(item_mod) if is_enabled(&item_mod.attrs) => {
+                // Se c'è un sottogruppo di moduli, esplora anche quello
+                structs.extend(parse_mod_items(&item_mod)?);
+            }
+            Item::Macro(item_macro) => {
+                structs.extend(collect_structs(parse_cfg_if(&item_macro.mac)?)?);
+            }
+            _ => {} // Ignora altri tipi di item
+        }
+    }
+    Ok(structs)
+}
+
 /// Funzione che esegue il parsing di un modulo Rust e restituisce le struct trovate all'interno.
 /// 
 /// # Argomenti
@@ -18,25 +143,32 @@ use quote::ToTokens;
 /// Lo scraping dei file `.rs` viene fatto in maniera selettiva grazie alle feature implementate nel codice dei modelli
 /// Se la struct non è stata attivata dalla feature corretta, non verrà inclusa nella mappa risultante.
 fn parse_mod_items(item_mod: &ItemMod) -> Result<Vec<ItemStruct>, String> {
-    let mut structs = Vec::new();
-
     // Verifica se il modulo ha contenuti (ad esempio, file separati o item inclusi)
-    if let Some(module_path) = &item_mod.content {
-        for mod_item in module_path.1.clone() {
-            match mod_item {
-                Item::Struct(item_struct) => {
-                    structs.push(item_struct); // Aggiungi la struct trovata
-                }
-                Item::Mod(item_submod) => {
-                    // Se c'è un sottogruppo di moduli, esplora anche quello
-                    structs.extend(parse_mod_items(&item_submod)?);
-                }
-                _ => {} // Ignora altri tipi di item
-            }
-        }
+    match &item_mod.content {
+        Some(module_path) => collect_structs(module_path.1.clone()),
+        None => Ok(Vec::new()),
     }
+}
 
-    Ok(structs)
+/// Funzione che restituisce il tipo di colonna (o di campo MongoDB) di un campo del modello.
+///
+/// # Argomenti
+/// * `ty` - Il tipo Rust del campo.
+/// * `db_type` - Il tipo di database, determina la mappatura utilizzata.
+///
+/// # Ritorna
+/// Il tipo SQL (o MongoDB) corrispondente.
+///
+/// # Note
+/// La rappresentazione del tipo di `syn` separa i token con spazi (es. `Option < String >`),
+/// quindi gli spazi vengono rimossi prima della mappatura.
+pub fn column_type(ty: &Type, db_type: &DatabaseType) -> String {
+    let type_name = ty.to_token_stream().to_string().replace(' ', "");
+    match db_type {
+        DatabaseType::PostgreSQL(_) | DatabaseType::SQLite(_) => map_to_sql(&type_name).to_string(),
+        DatabaseType::MongoDB(_) => map_to_mongo(&type_name).to_string(),
+        DatabaseType::None => panic!("Operazione impossibile, Database non configurato"),
+    }
 }
 
 /// Funzione che mappa i tipi Rust a tipi SQL corrispondenti.
@@ -58,16 +190,25 @@ fn map_to_sql(type_name: &str) -> &str {
         
         // Tipi opzionali (nullabili)
         "Option<u32>" | "Option<i32>" => "INTEGER NULL",
+        "Option<u64>" | "Option<i64>" => "BIGINT NULL",
         "Option<String>" => "TEXT NULL",
+        "Option<bool>" => "BOOLEAN NULL",
+        "Option<f32>" => "REAL NULL",
+        "Option<f64>" => "DOUBLE PRECISION NULL",
         
         // Tipi personalizzati o complessi
-        "AllocType" => "TEXT", // Rappresentabile come stringa
-        "CrudOperations" => "JSON", // JSON per flessibilità
+        "AllocType" => "TEXT", // Nome della variante, come nella serializzazione serde
+        "CrudOperations" => "JSON", // Oggetto JSON della serializzazione serde
         "Box<[u8]>" => "BYTEA", // Tipico per dati binari
-        "ExeLogStatus" | "MacroStatus" | "ProjectStatus" => "TEXT CHECK (value IN ('Active', 'Disabled', 'Completed'))", // Enum con vincoli
+        "ExeLogStatus" | "MacroStatus" | "ProjectStatus" => "TEXT CHECK (value IN ('Active', 'Disabled', 'Completed'))", // Enum con vincoli, `value` è sostituito con la colonna da `migrations.rs`
         "ExecutionFrequency" => "TEXT", // Frequenze come stringhe
         "Option<ProjectMetadata>" => "JSON NULL", // Serializzato come JSON
+        "Option<Revocation>" => "JSON NULL", // Revoca serializzata come JSON
+        "AuditAction" => "TEXT", // Operazione del log di audit come stringa
+        "Vec<String>" => "JSON", // Es. `changed_fields` del log di audit
         "chrono::NaiveDateTime" => "TIMESTAMP", // Data e ora
+        "Option<chrono::NaiveDateTime>" => "TIMESTAMP NULL", // Es. `deleted_at` dei modelli con cestino
+        "Uuid" => "TEXT", // UUID come testo, vedi `id_generator.rs`
         _ => "TEXT", // Default per tipi sconosciuti
     }
 }
@@ -93,7 +234,10 @@ fn map_to_mongo(type_name: &str) -> &str {
         
         // Tipi opzionali (nullabili)
         "Option<u32>" | "Option<i32>" => "int",
+        "Option<u64>" | "Option<i64>" => "long",
         "Option<String>" => "string",
+        "Option<bool>" => "bool",
+        "Option<f32>" | "Option<f64>" => "double",
         
         // Tipi personalizzati o complessi
         "AllocType" => "string",
@@ -102,12 +246,99 @@ fn map_to_mongo(type_name: &str) -> &str {
         "ExeLogStatus" | "MacroStatus" | "ProjectStatus" => "string",
         "ExecutionFrequency" => "string",
         "Option<ProjectMetadata>" => "object",
+        "Option<Revocation>" => "object",
+        "AuditAction" => "string",
+        "Vec<String>" => "array",
         "chrono::NaiveDateTime" => "date",
+        "Option<chrono::NaiveDateTime>" => "date",
+        "Uuid" => "string",
         _ => "string",
     }
 }
 
 
+/// Campi gestiti dal framework che non vengono persistiti, come in `#[derive(ArxModel)]`.
+const FRAMEWORK_FIELDS: [&str; 3] = ["store", "ops", "memory"];
+
+/// Verifica se la struct è un modello persistito, ovvero se deriva `ArxModel`.
+///
+/// Le struct di supporto (es. `ProjectMetadata`) non generano tabelle.
+fn is_arx_model(item: &ItemStruct) -> bool {
+    item.attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .any(|attr| attr.meta.to_token_stream().to_string().contains("ArxModel"))
+}
+
+/// Segnaposto dello snapshot dello schema per gli ID assegnati dal database (`IdStrategy::AutoIncrement`).
+///
+/// `migrations::create_table_sql` lo traduce in `INTEGER PRIMARY KEY AUTOINCREMENT` su SQLite
+/// e in `GENERATED BY DEFAULT AS IDENTITY` su PostgreSQL.
+pub const AUTO_INCREMENT: &str = "AUTOINCREMENT";
+
+/// Verifica se l'ID del modello viene assegnato dal database, come per `#[derive(ArxModel)]`:
+/// strategia dichiarata con `#[arx(id = "auto")]`, di default per gli ID `u32` e `u64`.
+fn is_auto_increment(item: &ItemStruct, id: &Field) -> bool {
+    let mut declared = None;
+    for attr in item.attrs.iter().filter(|attr| attr.path().is_ident("arx")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("id") {
+                declared = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<syn::Lit>()?;
+            }
+            Ok(())
+        });
+    }
+    match declared.as_deref() {
+        Some(strategy) => ["auto", "auto_increment"].contains(&strategy),
+        None => ["u32", "u64"].contains(&id.ty.to_token_stream().to_string().as_str()),
+    }
+}
+
+/// Verifica se il campo viene persistito, escludendo i campi del framework e quelli con `#[arx(skip)]`.
+fn is_persisted(field: &Field) -> bool {
+    let name = field.ident.as_ref().map(|i| i.to_string()).unwrap_or_default();
+    let skipped = field.attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("arx"))
+        .any(|attr| attr.meta.to_token_stream().to_string().contains("skip"));
+    !FRAMEWORK_FIELDS.contains(&name.as_str()) && !skipped
+}
+
+/// Vincolo di chiave esterna dichiarato con `#[arx(belongs_to = "...", on_delete = "...")]` (vedi `relations.rs`).
+///
+/// # Ritorna
+/// La clausola SQL `REFERENCES tabella(id) ON DELETE ...`, `None` se il campo non è una chiave esterna.
+fn foreign_key(field: &Field) -> Option<String> {
+    let mut target = None;
+    let mut on_delete = "RESTRICT";
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("arx")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("belongs_to") {
+                let path: syn::Path = meta.value()?.parse::<syn::LitStr>()?.parse()?;
+                target = path.segments.last().map(|segment| table_name(&segment.ident.to_string()));
+            } else if meta.path.is_ident("on_delete")
+                && meta.value()?.parse::<syn::LitStr>()?.value() == "cascade" {
+                    on_delete = "CASCADE";
+                }
+            Ok(())
+        });
+    }
+    target.map(|table| format!("REFERENCES {}(id) ON DELETE {}", table, on_delete))
+}
+
+/// Funzione che restituisce il nome della tabella (o collezione) associata a una struct.
+///
+/// # Argomenti
+/// * `struct_name` - Il nome della struct in PascalCase.
+///
+/// # Ritorna
+/// Il nome della tabella in snake_case (es. `ApiKey` -> `api_key`).
+pub fn table_name(struct_name: &str) -> String {
+    to_snake_case(struct_name)
+}
+
 /// Funzione che esegue il parsing di un file `.rs` e restituisce tutte le struct presenti al suo interno.
 /// 
 /// # Argomenti
@@ -125,21 +356,8 @@ fn parse_rs_file(file_path: &str) -> Result<Vec<ItemStruct>, String> {
     let syntax = parse_file(&file_content)
         .map_err(|e| format!("Errore nel parsing del file: {}", e))?;
     
-    // Filtra gli item del file per ottenere solo quelli di tipo struct
-    let mut structs = Vec::new();
-    for item in syntax.items {
-        match item {
-            Item::Mod(item_mod) => {
-                structs.extend(parse_mod_items(&item_mod)?); // Estende la lista con le struct nei moduli
-            }
-            Item::Struct(item_struct) => {
-                structs.push(item_struct); // Aggiungi direttamente la struct se non è incapsulata in un modulo
-            }
-            _ => {} // Ignora altri tipi di item
-        }
-    }
-
-    Ok(structs)
+    // Filtra gli item del file per ottenere solo le struct, anche nei moduli e nei blocchi `cfg_if!`
+    collect_structs(syntax.items)
 }
 
 /// Funzione per leggere tutti i file `.rs` all'interno di una cartella.
@@ -177,31 +395,35 @@ fn read_rs_dir(directory: &str) -> Result<Vec<String>, String> {
 /// * `directory` - La cartella contenente i file `.rs` da scansionare e analizzare.
 ///
 /// # Ritorna
-/// Un `Result<Vec<HashMap<String, HashMap<String, String>>>, String>` che contiene
+/// Un `Result<Vec<ScrapedStruct>, String>` che contiene
 /// una lista di strutture mappate (nome struct -> campi -> tipi SQL), oppure un messaggio di errore.
 /// 
 /// # Note
 /// Lo scraping dei file `.rs` viene fatto in maniera selettiva grazie alle feature implementate nel codice dei modelli
 /// Se la struct non è stata attivata dalla feature corretta, non verrà inclusa nella mappa risultante.
-pub fn scrape(directory: &str, db_type: DatabaseType) -> Result<Vec<HashMap<String, HashMap<String, String>>>, String> {
+pub fn scrape(directory: &str, db_type: DatabaseType) -> Result<Vec<ScrapedStruct>, String> {
     let files = read_rs_dir(directory)?;
     let mut all_structs = Vec::new();
 
     for file_path in files {
-        println!("Elaborando il file: {}", file_path);
+        // Scritto nel log e non su stdout, riservato all'output di `arx --output json`
+        debug!("Elaborando il file: {}", file_path);
         let structs = parse_rs_file(&file_path)?;
 
         if structs.is_empty() {
-            println!("Nessuna struct trovata nel file: {}", file_path);
+            debug!("Nessuna struct trovata nel file: {}", file_path);
         }
 
         let mut struct_map = HashMap::new();
         
-        for item in structs.iter() {
+        for item in structs.iter().filter(|item| is_arx_model(item)) {
             let struct_name = item.ident.to_string();
             let struct_name_ref: String = struct_name.clone(); // Uso di String
 
-            let fields = item.fields.iter().collect::<Vec<_>>();
+            let fields = item.fields
+                .iter()
+                .filter(|field| is_persisted(field) && is_enabled(&field.attrs))
+                .collect::<Vec<_>>();
             let mut fields_map = HashMap::new();
             for field in fields {
                 let field_name = field
@@ -210,15 +432,18 @@ pub fn scrape(directory: &str, db_type: DatabaseType) -> Result<Vec<HashMap<Stri
                     .map(|f| f.to_string()) // Convertito in String
                     .unwrap_or("Unnamed".to_string());
 
-                let field_type_str = field.ty.to_token_stream().to_string();
-                let field_type = match db_type {
-                    DatabaseType::PostgreSQL(_) => map_to_sql(&field_type_str),
-                    DatabaseType::SQLite(_) => map_to_sql(&field_type_str),
-                    DatabaseType::MongoDB(_) => map_to_mongo(&field_type_str),
-                    DatabaseType::None => panic!("Operazione impossibile, Database non configurato"),
-                };
+                let mut field_type = column_type(&field.ty, &db_type);
+                if field_name == "id" && !matches!(db_type, DatabaseType::MongoDB(_)) && is_auto_increment(item, field) {
+                    field_type = format!("{} {}", field_type, AUTO_INCREMENT);
+                }
 
-                let field_type_clone = field_type.to_string(); // Uso di String per evitare riferimenti
+                // Le chiavi esterne diventano vincoli solo sugli storage SQL
+                let field_type_clone = match (foreign_key(field), &db_type) {
+                    (Some(reference), DatabaseType::PostgreSQL(_) | DatabaseType::SQLite(_)) => {
+                        format!("{} {}", field_type, reference)
+                    }
+                    _ => field_type, // Uso di String per evitare riferimenti
+                };
                 let field_name_ref: String = field_name.clone(); // Converte in String
 
                 fields_map.insert(field_name_ref, field_type_clone);
@@ -0,0 +1,432 @@
+//! Modulo per lo storage su MongoDB dei modelli CRUD con `AllocType::Database`.
+//!
+//! `database_store.rs` delega a questo modulo le operazioni quando la connessione registrata
+//! è `DbConnection::MongoDB`. Ogni modello è salvato nella collection con il nome della tabella,
+//! nel database `models`, come creata da `arx db migrate` (vedi `migrations.rs`).
+//!
+//! ### Mappatura dei documenti:
+//! - L'ID del modello viene salvato in `_id` (intero a 64 bit per `u32` e `u64`, stringa per `Uuid`),
+//!   al posto dell'`ObjectId` generato da MongoDB.
+//! - Gli altri campi vengono convertiti con `ModelValue::to_bson` e riletti con `ModelValue::from_bson`.
+//! - I documenti con un `_id` di tipo `ObjectId` (es. il documento di esempio inserito da `create_mongodb_table`)
+//!   vengono ignorati da letture e query.
+//! - Con `IdStrategy::AutoIncrement` gli ID vengono assegnati da un contatore per modello,
+//!   salvato nella collection `arx_counters`.
+//!
+//! ### Transazioni:
+//! `transaction` esegue le scritture in una sessione con transazione, se il server la supporta
+//! (replica set o cluster shardato). Su un server standalone le operazioni vengono eseguite senza sessione.
+//! Le query con cursore non partecipano alla sessione.
+//!
+//! ### Change stream:
+//! `watch_changes` riceve le modifiche di tutte le collection dei modelli, anche quelle degli altri processi,
+//! e le inoltra a `watch.rs`. Richiede un replica set o un cluster shardato.
+//!
+//! Il driver di MongoDB è asincrono: le operazioni vengono eseguite sul runtime tokio corrente
+//! se è multi-thread, altrimenti su un runtime dedicato (da un thread separato con `current_thread`).
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::IntoFuture;
+use std::time::Duration;
+
+use log::{error, info, warn};
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::change_stream::event::{ChangeStreamEvent, OperationType, ResumeToken};
+use mongodb::change_stream::ChangeStream;
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::{FullDocumentType, ReturnDocument};
+use mongodb::{Client, ClientSession, Collection};
+use once_cell::sync::Lazy;
+use tokio::runtime::RuntimeFlavor;
+
+use crate::crud::crud_ops::{ArxModel, CrudError};
+use crate::crud::id_generator::{IdStrategy, ModelKey};
+use crate::crud::model_value::{FromModelValue, ModelValue, ToModelValue};
+use crate::crud::models::migrations::MONGO_DATABASE;
+use crate::crud::query::{Page, Query};
+use crate::crud::watch::ChangeKind;
+
+
+/// Collection dei contatori degli ID incrementali, esclusa dall'introspezione.
+pub const COUNTERS_COLLECTION: &str = "arx_counters";
+
+/// Attesa prima di riaprire un change stream interrotto.
+const RESUME_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Codice di errore di MongoDB per una chiave duplicata.
+const DUPLICATE_KEY: i32 = 11000;
+
+/// Runtime utilizzato quando le operazioni vengono chiamate al di fuori di un runtime tokio.
+static RUNTIME: Lazy<Option<tokio::runtime::Runtime>> = Lazy::new(|| match tokio::runtime::Runtime::new() {
+    Ok(runtime) => Some(runtime),
+    Err(e) => {
+        error!("Errore nella creazione del runtime per MongoDB: {}", e);
+        None
+    }
+});
+
+thread_local! {
+    /// Sessione della transazione attiva sul thread.
+    static SESSION: RefCell<Option<ClientSession>> = const { RefCell::new(None) };
+}
+
+/// Esegue un'operazione asincrona del driver in modo sincrono.
+///
+/// Utilizzata anche da `database_store::connect`, così che il client venga creato sullo stesso runtime.
+/// `block_in_place` non è consentito su un runtime `current_thread`: in questo caso l'operazione viene
+/// eseguita sul runtime dedicato da un thread separato, attendendone il risultato.
+pub(crate) fn block_on<F>(future: F) -> Result<F::Output, CrudError>
+where
+    F: IntoFuture + Send,
+    F::Output: Send,
+{
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            Ok(tokio::task::block_in_place(|| handle.block_on(future.into_future())))
+        }
+        Ok(_) => std::thread::scope(|scope| scope.spawn(|| dedicated_block_on(future)).join())
+            .unwrap_or_else(|_| Err(CrudError::StorageError("Operazione MongoDB interrotta da un panic".to_string()))),
+        Err(_) => dedicated_block_on(future),
+    }
+}
+
+/// Esegue l'operazione sul runtime dedicato, al di fuori di un runtime tokio.
+fn dedicated_block_on<F: IntoFuture>(future: F) -> Result<F::Output, CrudError> {
+    match RUNTIME.as_ref() {
+        Some(runtime) => Ok(runtime.block_on(future.into_future())),
+        None => Err(CrudError::StorageError("Runtime per MongoDB non disponibile".to_string())),
+    }
+}
+
+/// Esegue un'azione del driver nella sessione della transazione attiva, se presente.
+macro_rules! in_session {
+    ($action:expr) => {
+        SESSION.with(|session| match session.borrow_mut().as_mut() {
+            Some(session) => block_on($action.session(session)),
+            None => block_on($action),
+        })
+    };
+}
+
+fn storage_error(e: mongodb::error::Error) -> CrudError {
+    CrudError::StorageError(e.to_string())
+}
+
+/// Errore di una scrittura, `CrudError::Conflict` se l'ID del record esiste già.
+fn write_error<T: ArxModel>(e: mongodb::error::Error, id: &T::Id) -> CrudError {
+    match *e.kind {
+        ErrorKind::Write(WriteFailure::WriteError(ref error)) if error.code == DUPLICATE_KEY => CrudError::conflict::<T>(id),
+        _ => storage_error(e),
+    }
+}
+
+fn collection<T: ArxModel>(client: &Client) -> Collection<Document> {
+    client.database(MONGO_DATABASE).collection::<Document>(T::TABLE_NAME)
+}
+
+/// Filtro sull'ID del modello.
+fn id_filter<T: ArxModel>(id: &T::Id) -> Document {
+    doc! { "_id": id.to_model_value().to_bson() }
+}
+
+/// Limita il filtro ai documenti dei modelli, escludendo quelli con `_id` di tipo `ObjectId`.
+fn models_only(filter: Document) -> Document {
+    let models = doc! { "_id": { "$not": { "$type": "objectId" } } };
+    if filter.is_empty() {
+        models
+    } else {
+        doc! { "$and": [models, filter] }
+    }
+}
+
+/// Converte il modello in documento, con l'ID in `_id`.
+pub fn to_document<T: ArxModel>(item: &T) -> Document {
+    item.to_values()
+        .into_iter()
+        .map(|(field, value)| match field {
+            "id" => ("_id".to_string(), value.to_bson()),
+            field => (field.to_string(), value.to_bson()),
+        })
+        .collect()
+}
+
+/// Valori dei campi di un documento, con `_id` in `id`.
+fn document_values(document: Document) -> HashMap<String, ModelValue> {
+    document
+        .into_iter()
+        .map(|(field, value)| {
+            let field = if field == "_id" { "id".to_string() } else { field };
+            (field, ModelValue::from_bson(value))
+        })
+        .collect()
+}
+
+/// Ricostruisce il modello da un documento.
+pub fn from_document<T: ArxModel>(document: Document) -> Result<T, CrudError> {
+    let values = document_values(document);
+    T::from_values(&values).map_err(|e| CrudError::StorageError(format!("{}: {}", T::TABLE_NAME, e)))
+}
+
+/// Riserva `count` ID incrementali consecutivi del modello, restituendo il primo.
+///
+/// Il contatore viene prima allineato all'ID più alto presente nella collection,
+/// così da non assegnare ID già utilizzati da record inseriti con ID esplicito.
+async fn next_id<T: ArxModel>(client: &Client, count: u64) -> Result<u64, mongodb::error::Error> {
+    let last = collection::<T>(client).find_one(models_only(doc! {})).sort(doc! { "_id": -1 }).await?;
+    let last = last
+        .and_then(|document| document.get("_id").cloned())
+        .and_then(|id| i64::from_model_value(ModelValue::from_bson(id)).ok())
+        .unwrap_or(0);
+
+    let counters = client.database(MONGO_DATABASE).collection::<Document>(COUNTERS_COLLECTION);
+    counters
+        .update_one(doc! { "_id": T::TABLE_NAME }, doc! { "$max": { "seq": last } })
+        .upsert(true)
+        .await?;
+    let counter = counters
+        .find_one_and_update(doc! { "_id": T::TABLE_NAME }, doc! { "$inc": { "seq": count as i64 } })
+        .return_document(ReturnDocument::After)
+        .await?;
+    let seq = counter.and_then(|counter| counter.get_i64("seq").ok()).unwrap_or(last + count as i64);
+    Ok(seq as u64 + 1 - count)
+}
+
+/// Inserisce un nuovo documento nella collection del modello.
+///
+/// Con `IdStrategy::AutoIncrement` e ID non impostato, l'ID viene assegnato dal contatore del modello.
+pub(crate) fn insert<T: ArxModel>(client: &Client, item: &mut T) -> Result<(), CrudError> {
+    if item.id().is_unset() && T::ID_STRATEGY == IdStrategy::AutoIncrement {
+        let next = block_on(next_id::<T>(client, 1))?.map_err(storage_error)?;
+        let id = T::Id::from_sequence(next)
+            .ok_or_else(|| CrudError::StorageError(format!("ID {} fuori dai limiti per {}", next, T::TABLE_NAME)))?;
+        item.set_id(id);
+    }
+    let document = to_document(item);
+    in_session!(collection::<T>(client).insert_one(document))?.map_err(|e| write_error::<T>(e, &item.id()))?;
+    Ok(())
+}
+
+/// Inserisce più documenti nella collection del modello con un unico `insert_many` non ordinato:
+/// i documenti non validi (es. `_id` duplicato) non impediscono l'inserimento degli altri.
+///
+/// # Ritorna
+/// L'esito di ogni record nell'ordine di `items`.
+pub(crate) fn insert_many<T: ArxModel>(client: &Client, items: &mut [T]) -> Result<Vec<Result<(), CrudError>>, CrudError> {
+    let unset = items.iter().filter(|item| item.id().is_unset()).count() as u64;
+    if unset > 0 && T::ID_STRATEGY == IdStrategy::AutoIncrement {
+        let first = block_on(next_id::<T>(client, unset))?.map_err(storage_error)?;
+        for (next, item) in (first..).zip(items.iter_mut().filter(|item| item.id().is_unset())) {
+            let id = T::Id::from_sequence(next)
+                .ok_or_else(|| CrudError::StorageError(format!("ID {} fuori dai limiti per {}", next, T::TABLE_NAME)))?;
+            item.set_id(id);
+        }
+    }
+    let mut outcomes: Vec<Result<(), CrudError>> = items.iter().map(|_| Ok(())).collect();
+    if items.is_empty() {
+        return Ok(outcomes);
+    }
+
+    let documents: Vec<Document> = items.iter().map(to_document::<T>).collect();
+    if let Err(e) = in_session!(collection::<T>(client).insert_many(documents).ordered(false))? {
+        match *e.kind {
+            ErrorKind::InsertMany(ref failure) if failure.write_errors.is_some() => {
+                for error in failure.write_errors.iter().flatten() {
+                    outcomes[error.index] = Err(match error.code {
+                        DUPLICATE_KEY => CrudError::conflict::<T>(&items[error.index].id()),
+                        _ => CrudError::StorageError(error.message.clone()),
+                    });
+                }
+            }
+            _ => return Err(storage_error(e)),
+        }
+    }
+    Ok(outcomes)
+}
+
+/// Legge un documento in base all'ID del modello, `None` se non esiste.
+pub(crate) fn select<T: ArxModel>(client: &Client, id: &T::Id) -> Result<Option<T>, CrudError> {
+    let document = in_session!(collection::<T>(client).find_one(id_filter::<T>(id)))?.map_err(storage_error)?;
+    document.map(from_document::<T>).transpose()
+}
+
+/// Sostituisce un documento esistente.
+///
+/// Con `expected` il documento viene sostituito solo se il campo `version` ha il valore indicato.
+///
+/// # Ritorna
+/// `false` se nessun documento ha l'ID del modello (e la versione attesa).
+pub(crate) fn update<T: ArxModel>(client: &Client, item: &T, expected: Option<u64>) -> Result<bool, CrudError> {
+    let document = to_document(item);
+    let mut filter = id_filter::<T>(&item.id());
+    if let Some(version) = expected {
+        filter.insert("version", version.to_model_value().to_bson());
+    }
+    let result = in_session!(collection::<T>(client).replace_one(filter, document))?.map_err(storage_error)?;
+    Ok(result.matched_count > 0)
+}
+
+/// Elimina un documento in base all'ID del modello.
+///
+/// # Ritorna
+/// `false` se il documento non esiste.
+pub(crate) fn delete<T: ArxModel>(client: &Client, id: &T::Id) -> Result<bool, CrudError> {
+    let result = in_session!(collection::<T>(client).delete_one(id_filter::<T>(id)))?.map_err(storage_error)?;
+    Ok(result.deleted_count > 0)
+}
+
+/// Esegue una query sulla collection del modello.
+///
+/// La query deve essere già validata con `Query::validate` sui campi del modello.
+pub(crate) fn query<T: ArxModel>(client: &Client, query: &Query) -> Result<Page<T>, CrudError> {
+    let mongo = query.to_mongo(&T::text_fields());
+    let collection = collection::<T>(client);
+
+    let (documents, total) = block_on(async {
+        let mut find = collection.find(models_only(mongo.filter)).sort(mongo.sort).skip(mongo.skip);
+        if let Some(limit) = mongo.limit {
+            find = find.limit(limit);
+        }
+        let mut cursor = find.await?;
+        let mut documents = Vec::new();
+        while cursor.advance().await? {
+            documents.push(cursor.deserialize_current()?);
+        }
+        let total = collection.count_documents(models_only(mongo.count_filter)).await?;
+        Ok::<_, mongodb::error::Error>((documents, total))
+    })?
+    .map_err(storage_error)?;
+
+    let items = documents.into_iter().map(from_document::<T>).collect::<Result<Vec<T>, CrudError>>()?;
+    Ok(query.into_page(items, total as usize))
+}
+
+/// Indica se il server supporta le transazioni (replica set o `mongos`).
+async fn supports_transactions(client: &Client) -> Result<bool, mongodb::error::Error> {
+    let hello = client.database("admin").run_command(doc! { "hello": 1 }).await?;
+    Ok(hello.contains_key("setName") || hello.get_str("msg") == Ok("isdbgrid"))
+}
+
+/// Rimuove la sessione rimasta attiva sul thread, ad esempio in caso di panic durante `transaction`.
+///
+/// Il driver annulla la transazione alla chiusura della sessione.
+struct SessionScope;
+
+impl Drop for SessionScope {
+    fn drop(&mut self) {
+        let _ = SESSION.try_with(|session| session.borrow_mut().take());
+    }
+}
+
+/// Esegue `work` in una transazione MongoDB, con commit se restituisce `Ok` e abort in caso di errore.
+///
+/// Se il server non supporta le transazioni `work` viene eseguita senza sessione.
+/// Una sessione già attiva sul thread viene riutilizzata.
+pub(crate) fn transaction<R>(client: &Client, work: impl FnOnce() -> Result<R, CrudError>) -> Result<R, CrudError> {
+    if SESSION.with(|session| session.borrow().is_some()) {
+        return work();
+    }
+    if !block_on(supports_transactions(client))?.map_err(storage_error)? {
+        warn!("Il server MongoDB non supporta le transazioni, operazioni eseguite senza sessione");
+        return work();
+    }
+    let mut session = block_on(client.start_session())?.map_err(storage_error)?;
+    block_on(session.start_transaction())?.map_err(storage_error)?;
+
+    SESSION.with(|current| *current.borrow_mut() = Some(session));
+    let _scope = SessionScope;
+    let result = work();
+
+    let mut session = match SESSION.with(|current| current.borrow_mut().take()) {
+        Some(session) => session,
+        None => return result,
+    };
+    match result {
+        Ok(value) => {
+            block_on(session.commit_transaction())?.map_err(storage_error)?;
+            info!("Transazione MongoDB confermata");
+            Ok(value)
+        }
+        Err(e) => {
+            if let Ok(Err(abort)) = block_on(session.abort_transaction()) {
+                error!("Abort della transazione MongoDB fallito: {}", abort);
+            }
+            warn!("Transazione MongoDB annullata: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Apre il change stream delle collection dei modelli, riprendendo dall'evento `resume_after` se indicato.
+async fn open_change_stream(
+    client: &Client,
+    resume_after: Option<ResumeToken>,
+) -> Result<ChangeStream<ChangeStreamEvent<Document>>, mongodb::error::Error> {
+    let database = client.database(MONGO_DATABASE);
+    let mut watch = database.watch().full_document(FullDocumentType::UpdateLookup);
+    if let Some(token) = resume_after {
+        watch = watch.resume_after(token);
+    }
+    watch.await
+}
+
+/// Converte un evento del change stream e lo inoltra a `on_change`.
+///
+/// Vengono ignorati i contatori degli ID, i documenti con `_id` di tipo `ObjectId` e le operazioni
+/// che non modificano i documenti (es. `drop`).
+fn forward_change(event: ChangeStreamEvent<Document>, on_change: ChangeHandler) {
+    let kind = match event.operation_type {
+        OperationType::Insert => ChangeKind::Created,
+        OperationType::Update | OperationType::Replace => ChangeKind::Updated,
+        OperationType::Delete => ChangeKind::Deleted,
+        _ => return,
+    };
+    let table = match event.ns.and_then(|ns| ns.coll) {
+        Some(table) if table != COUNTERS_COLLECTION => table,
+        _ => return,
+    };
+    let id = match event.document_key.and_then(|mut key| key.remove("_id")) {
+        Some(Bson::ObjectId(_)) | None => return,
+        Some(id) => ModelValue::from_bson(id),
+    };
+    on_change(&table, kind, id, event.full_document.map(document_values));
+}
+
+/// Funzione che riceve le modifiche: tabella, tipo di modifica, ID e valori del documento se disponibili.
+pub(crate) type ChangeHandler = fn(&str, ChangeKind, ModelValue, Option<HashMap<String, ModelValue>>);
+
+/// Avvia un thread che riceve le modifiche delle collection dei modelli con un change stream,
+/// passandole a `on_change`.
+///
+/// In caso di errore il change stream viene riaperto dall'ultimo evento ricevuto (resume token).
+pub(crate) fn watch_changes(client: &Client, on_change: ChangeHandler) -> Result<(), CrudError> {
+    let mut stream = block_on(open_change_stream(client, None))?.map_err(storage_error)?;
+    let client = client.clone();
+    std::thread::Builder::new()
+        .name("arx-change-stream".to_string())
+        .spawn(move || loop {
+            let error = match block_on(stream.next_if_any()) {
+                Ok(Ok(Some(event))) => {
+                    forward_change(event, on_change);
+                    continue;
+                }
+                Ok(Ok(None)) => continue,
+                Ok(Err(e)) => e.to_string(),
+                Err(e) => e.to_string(),
+            };
+            error!("Change stream interrotto: {}", error);
+            let resume_after = stream.resume_token();
+            stream = loop {
+                std::thread::sleep(RESUME_INTERVAL);
+                match block_on(open_change_stream(&client, resume_after.clone())) {
+                    Ok(Ok(reopened)) => break reopened,
+                    Ok(Err(e)) => warn!("Riapertura del change stream fallita: {}", e),
+                    Err(e) => warn!("Riapertura del change stream fallita: {}", e),
+                }
+            };
+            info!("Change stream ripreso");
+        })
+        .map_err(|e| CrudError::StorageError(format!("Avvio del change stream fallito: {}", e)))?;
+    info!("In ascolto delle modifiche delle collection nel database {}", MONGO_DATABASE);
+    Ok(())
+}
@@ -0,0 +1,626 @@
+//! Modulo per la costruzione di query tipizzate su `List` e `Search`.
+//!
+//! Una `Query` descrive filtri, ordinamento e paginazione in modo indipendente dallo storage:
+//! - In memoria viene eseguita da `Query::apply` sui valori restituiti da `ArxModel::to_values`.
+//! - Sugli storage SQL viene tradotta da `Query::to_sql` in una SELECT parametrizzata.
+//! - Su MongoDB viene tradotta da `Query::to_mongo` in filtro, ordinamento, skip e limit,
+//!   con il campo `id` del modello mappato su `_id`.
+//!
+//! ### Filtri supportati:
+//! - Uguaglianza e disuguaglianza (`eq`, `ne`).
+//! - Intervalli (`gt`, `gte`, `lt`, `lte`, `between`).
+//! - Sottostringa e prefisso sui campi testuali (`contains`, `starts_with`), distinguendo maiuscole e minuscole
+//!   su tutti gli storage (`GLOB` su SQLite, `LIKE` su PostgreSQL, espressione regolare su MongoDB).
+//! - Ricerca testuale sui campi `String` del modello (`text`, vedi `ArxModel::text_fields`),
+//!   senza distinzione tra maiuscole e minuscole.
+//!
+//! ### Paginazione:
+//! - `limit` e `offset` per la paginazione classica.
+//! - `after` con il `Cursor` restituito in `Page::next_cursor` per la paginazione a cursore,
+//!   stabile anche se vengono inseriti nuovi record tra una pagina e l'altra.
+//!
+//! L'ordinamento termina sempre con `id` crescente, così il cursore identifica un record in modo univoco.
+//! I valori nulli precedono gli altri in ordine crescente e li seguono in ordine decrescente, su tutti gli storage.
+//!
+//! ### Esempio:
+//! ```rust,ignore
+//! let query = Query::new()
+//!     .eq("device_id", 7u32)
+//!     .between("value", 10.0, 20.0)
+//!     .order_by("timestamp", SortDirection::Desc)
+//!     .limit(50);
+//! let page = SensorData::search(&query)?;
+//! let next = SensorData::search(&query.after(page.next_cursor.unwrap()))?;
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use mongodb::bson::{doc, Bson, Document};
+
+use crate::crud::crud_ops::ArxModel;
+use crate::crud::model_value::{ModelValue, ToModelValue};
+
+/// Operatore di confronto di un filtro.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+    StartsWith,
+}
+
+/// Filtro su un singolo campo del modello.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    pub field: String,
+    pub op: FilterOp,
+    pub value: ModelValue,
+}
+
+/// Direzione dell'ordinamento.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// Posizione dell'ultimo record di una pagina: valori dei campi di ordinamento seguiti da `id`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor(pub Vec<ModelValue>);
+
+/// Pagina di risultati di una query.
+///
+/// # Campi
+/// - `items`: Record della pagina.
+/// - `total`: Numero totale di record che soddisfano i filtri, indipendentemente dalla paginazione.
+/// - `next_cursor`: Cursore per la pagina successiva, `None` se non ci sono altri record.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Dialetto SQL per la generazione dei parametri.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SqlDialect {
+    /// Parametri numerati (`$1`, `$2`, ...).
+    PostgreSQL,
+    /// Parametri posizionali (`?`).
+    SQLite,
+}
+
+/// Query SQL generata da `Query::to_sql`.
+///
+/// # Campi
+/// - `select`: SELECT con filtri, ordinamento e paginazione.
+/// - `count`: SELECT COUNT(*) con i soli filtri, per `Page::total`.
+/// - `params`: Parametri della SELECT, nell'ordine dei segnaposto.
+/// - `count_params`: Parametri della COUNT, nell'ordine dei segnaposto.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlQuery {
+    pub select: String,
+    pub count: String,
+    pub params: Vec<ModelValue>,
+    pub count_params: Vec<ModelValue>,
+}
+
+/// Query MongoDB generata da `Query::to_mongo`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MongoQuery {
+    /// Filtro della `find`, con il cursore se presente.
+    pub filter: Document,
+    /// Filtro per il conteggio, con i soli filtri della query.
+    pub count_filter: Document,
+    pub sort: Document,
+    pub skip: u64,
+    /// Limite della `find`, con un record in più per determinare `next_cursor`.
+    pub limit: Option<i64>,
+}
+
+/// Query tipizzata con filtri, ordinamento e paginazione.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub filters: Vec<Filter>,
+    pub text: Option<String>,
+    pub order_by: Vec<(String, SortDirection)>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+    pub after: Option<Cursor>,
+    pub include_revoked: bool,
+    pub include_trashed: bool,
+}
+
+impl Query {
+    /// Crea una query vuota, che restituisce tutti i record ordinati per `id`.
+    pub fn new() -> Self {
+        Query::default()
+    }
+
+    /// Aggiunge un filtro sul campo indicato.
+    pub fn filter(mut self, field: &str, op: FilterOp, value: impl ToModelValue) -> Self {
+        self.filters.push(Filter { field: field.to_string(), op, value: value.to_model_value() });
+        self
+    }
+
+    /// Il campo deve essere uguale al valore.
+    pub fn eq(self, field: &str, value: impl ToModelValue) -> Self {
+        self.filter(field, FilterOp::Eq, value)
+    }
+
+    /// Il campo deve essere diverso dal valore.
+    pub fn ne(self, field: &str, value: impl ToModelValue) -> Self {
+        self.filter(field, FilterOp::Ne, value)
+    }
+
+    /// Il campo deve essere maggiore del valore.
+    pub fn gt(self, field: &str, value: impl ToModelValue) -> Self {
+        self.filter(field, FilterOp::Gt, value)
+    }
+
+    /// Il campo deve essere maggiore o uguale al valore.
+    pub fn gte(self, field: &str, value: impl ToModelValue) -> Self {
+        self.filter(field, FilterOp::Gte, value)
+    }
+
+    /// Il campo deve essere minore del valore.
+    pub fn lt(self, field: &str, value: impl ToModelValue) -> Self {
+        self.filter(field, FilterOp::Lt, value)
+    }
+
+    /// Il campo deve essere minore o uguale al valore.
+    pub fn lte(self, field: &str, value: impl ToModelValue) -> Self {
+        self.filter(field, FilterOp::Lte, value)
+    }
+
+    /// Il campo deve essere compreso tra `min` e `max`, estremi inclusi.
+    pub fn between(self, field: &str, min: impl ToModelValue, max: impl ToModelValue) -> Self {
+        self.gte(field, min).lte(field, max)
+    }
+
+    /// Il campo testuale deve contenere la sottostringa.
+    pub fn contains(self, field: &str, value: &str) -> Self {
+        self.filter(field, FilterOp::Contains, value.to_string())
+    }
+
+    /// Il campo testuale deve iniziare con il prefisso.
+    pub fn starts_with(self, field: &str, value: &str) -> Self {
+        self.filter(field, FilterOp::StartsWith, value.to_string())
+    }
+
+    /// Almeno un campo testuale deve contenere il testo, senza distinzione tra maiuscole e minuscole.
+    ///
+    /// Sostituisce la stringa libera accettata in precedenza da `Search::search`. Un testo vuoto non filtra.
+    pub fn text(mut self, value: &str) -> Self {
+        self.text = (!value.is_empty()).then(|| value.to_string());
+        self
+    }
+
+    /// Aggiunge un campo di ordinamento, i campi vengono applicati nell'ordine di inserimento.
+    pub fn order_by(mut self, field: &str, direction: SortDirection) -> Self {
+        self.order_by.push((field.to_string(), direction));
+        self
+    }
+
+    /// Numero massimo di record per pagina.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Numero di record da saltare.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Restituisce i record successivi al cursore di una pagina precedente.
+    pub fn after(mut self, cursor: Cursor) -> Self {
+        self.after = Some(cursor);
+        self
+    }
+
+    /// Include i record revocati, esclusi di default da `List` e `Search`.
+    pub fn include_revoked(mut self) -> Self {
+        self.include_revoked = true;
+        self
+    }
+
+    /// Include i record nel cestino, esclusi di default da `List` e `Search` (vedi `ArxModel::SOFT_DELETE`).
+    pub fn include_trashed(mut self) -> Self {
+        self.include_trashed = true;
+        self
+    }
+
+    /// Campi di ordinamento effettivi, con `id` crescente come ultimo criterio.
+    pub fn sort_keys(&self) -> Vec<(String, SortDirection)> {
+        let mut keys = self.order_by.clone();
+        if !keys.iter().any(|(field, _)| field == "id") {
+            keys.push(("id".to_string(), SortDirection::Asc));
+        }
+        keys
+    }
+
+    /// Verifica che i campi della query esistano nel modello.
+    ///
+    /// Necessaria prima di `to_sql`, dato che i nomi dei campi vengono inseriti direttamente nella query.
+    pub fn validate(&self, fields: &[&str]) -> Result<(), String> {
+        let names = self.filters.iter().map(|f| &f.field).chain(self.order_by.iter().map(|(field, _)| field));
+        for name in names {
+            if !fields.contains(&name.as_str()) {
+                return Err(format!("Campo '{}' non presente nel modello", name));
+            }
+        }
+        if let Some(cursor) = &self.after {
+            if cursor.0.len() != self.sort_keys().len() {
+                return Err("Cursore non compatibile con l'ordinamento della query".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifica se i valori di un record soddisfano filtri e ricerca testuale.
+    ///
+    /// # Parametri
+    /// - `values`: Valori dei campi del record.
+    /// - `text_fields`: Campi su cui applicare la ricerca testuale.
+    pub fn matches(&self, values: &HashMap<&str, ModelValue>, text_fields: &[&str]) -> bool {
+        let filters_match = self.filters.iter().all(|filter| {
+            let value = values.get(filter.field.as_str()).unwrap_or(&ModelValue::Null);
+            filter_matches(filter, value)
+        });
+        let text_match = match &self.text {
+            Some(text) => {
+                let text = text.to_lowercase();
+                text_fields.iter().any(|field| {
+                    values.get(field).and_then(ModelValue::as_text).is_some_and(|v| v.to_lowercase().contains(&text))
+                })
+            }
+            None => true,
+        };
+        filters_match && text_match
+    }
+
+    /// Esegue la query su un insieme di record in memoria.
+    ///
+    /// # Parametri
+    /// - `items`: Tutti i record del modello.
+    ///
+    /// # Ritorna
+    /// La pagina di risultati con il totale dei record che soddisfano i filtri.
+    pub fn apply<T: ArxModel>(&self, items: Vec<T>) -> Page<T> {
+        let keys = self.sort_keys();
+        let text_fields = T::text_fields();
+        let mut rows: Vec<(Vec<ModelValue>, T)> = items
+            .into_iter()
+            .filter_map(|item| {
+                let values: HashMap<&str, ModelValue> = item.to_values().into_iter().collect();
+                self.matches(&values, &text_fields).then(|| (sort_values(&keys, &values), item))
+            })
+            .collect();
+        let total = rows.len();
+
+        rows.sort_by(|(a, _), (b, _)| compare_keys(&keys, a, b));
+        if let Some(cursor) = &self.after {
+            rows.retain(|(values, _)| compare_keys(&keys, values, &cursor.0) == Ordering::Greater);
+        }
+
+        let fetch = self.limit.map(|limit| limit + 1).unwrap_or(usize::MAX);
+        let items = rows.into_iter().skip(self.offset).take(fetch).map(|(_, item)| item).collect();
+        self.into_page(items, total)
+    }
+
+    /// Costruisce la pagina a partire dai record già filtrati, ordinati e paginati da uno storage.
+    ///
+    /// Lo storage deve restituire fino a `limit + 1` record: il record in più indica
+    /// che esiste una pagina successiva e non viene incluso nei risultati.
+    pub fn into_page<T: ArxModel>(&self, mut items: Vec<T>, total: usize) -> Page<T> {
+        let next_cursor = match self.limit {
+            Some(limit) if items.len() > limit => {
+                items.truncate(limit);
+                items.last().map(|item| {
+                    let values: HashMap<&str, ModelValue> = item.to_values().into_iter().collect();
+                    Cursor(sort_values(&self.sort_keys(), &values))
+                })
+            }
+            _ => None,
+        };
+        Page { items, total, next_cursor }
+    }
+
+    /// Traduce la query in SQL parametrizzato per la tabella indicata.
+    ///
+    /// # Parametri
+    /// - `table`: Nome della tabella.
+    /// - `text_fields`: Colonne su cui applicare la ricerca testuale.
+    /// - `dialect`: Dialetto per i segnaposto dei parametri.
+    ///
+    /// # Note
+    /// I nomi dei campi non vengono parametrizzati: chiamare prima `validate` con i campi del modello.
+    pub fn to_sql(&self, table: &str, text_fields: &[&str], dialect: SqlDialect) -> SqlQuery {
+        let mut params = Vec::new();
+        let mut conditions = Vec::new();
+
+        for filter in &self.filters {
+            // Il confronto con NULL in SQL richiede IS NULL / IS NOT NULL
+            match (filter.op, &filter.value) {
+                (FilterOp::Eq, ModelValue::Null) => {
+                    conditions.push(format!("{} IS NULL", filter.field));
+                    continue;
+                }
+                (FilterOp::Ne, ModelValue::Null) => {
+                    conditions.push(format!("{} IS NOT NULL", filter.field));
+                    continue;
+                }
+                _ => {}
+            }
+            let (operator, value) = match filter.op {
+                FilterOp::Eq => ("=", filter.value.clone()),
+                FilterOp::Ne => ("<>", filter.value.clone()),
+                FilterOp::Gt => (">", filter.value.clone()),
+                FilterOp::Gte => (">=", filter.value.clone()),
+                FilterOp::Lt => ("<", filter.value.clone()),
+                FilterOp::Lte => ("<=", filter.value.clone()),
+                // `LIKE` su SQLite non distingue maiuscole e minuscole, a differenza di `GLOB`
+                FilterOp::Contains if dialect == SqlDialect::SQLite => ("GLOB", glob_pattern(&filter.value, "*", "*")),
+                FilterOp::StartsWith if dialect == SqlDialect::SQLite => ("GLOB", glob_pattern(&filter.value, "", "*")),
+                FilterOp::Contains => ("LIKE", like_pattern(&filter.value, "%", "%")),
+                FilterOp::StartsWith => ("LIKE", like_pattern(&filter.value, "", "%")),
+            };
+            params.push(value);
+            let placeholder = placeholder(dialect, params.len());
+            let escape = if operator == "LIKE" { " ESCAPE '\\'" } else { "" };
+            conditions.push(format!("{} {} {}{}", filter.field, operator, placeholder, escape));
+        }
+
+        if let Some(text) = &self.text {
+            let mut alternatives = Vec::new();
+            for field in text_fields {
+                params.push(like_pattern(&ModelValue::Text(text.to_lowercase()), "%", "%"));
+                alternatives.push(format!("LOWER({}) LIKE {} ESCAPE '\\'", field, placeholder(dialect, params.len())));
+            }
+            conditions.push(match alternatives.is_empty() {
+                true => "1 = 0".to_string(),
+                false => format!("({})", alternatives.join(" OR ")),
+            });
+        }
+
+        let count_params = params.clone();
+        let count = format!("SELECT COUNT(*) AS total FROM {}{}", table, where_clause(&conditions));
+
+        let keys = self.sort_keys();
+        if let Some(cursor) = &self.after {
+            let mut alternatives = Vec::new();
+            for (i, (field, direction)) in keys.iter().enumerate() {
+                // In ordine decrescente nessun valore segue un valore nullo
+                if cursor.0[i] == ModelValue::Null && *direction == SortDirection::Desc {
+                    continue;
+                }
+                let mut parts = Vec::new();
+                for ((previous, _), value) in keys[..i].iter().zip(&cursor.0) {
+                    if *value == ModelValue::Null {
+                        parts.push(format!("{} IS NULL", previous));
+                    } else {
+                        params.push(value.clone());
+                        parts.push(format!("{} = {}", previous, placeholder(dialect, params.len())));
+                    }
+                }
+                match (&cursor.0[i], direction) {
+                    (ModelValue::Null, _) => parts.push(format!("{} IS NOT NULL", field)),
+                    (value, SortDirection::Asc) => {
+                        params.push(value.clone());
+                        parts.push(format!("{} > {}", field, placeholder(dialect, params.len())));
+                    }
+                    (value, SortDirection::Desc) => {
+                        params.push(value.clone());
+                        parts.push(format!("({} < {} OR {} IS NULL)", field, placeholder(dialect, params.len()), field));
+                    }
+                }
+                alternatives.push(format!("({})", parts.join(" AND ")));
+            }
+            conditions.push(format!("({})", alternatives.join(" OR ")));
+        }
+
+        // I valori nulli vengono ordinati come in memoria, dove precedono tutti gli altri valori
+        let order: Vec<String> = keys
+            .iter()
+            .map(|(field, direction)| match direction {
+                SortDirection::Asc => format!("{} ASC NULLS FIRST", field),
+                SortDirection::Desc => format!("{} DESC NULLS LAST", field),
+            })
+            .collect();
+        let mut select = format!("SELECT * FROM {}{} ORDER BY {}", table, where_clause(&conditions), order.join(", "));
+        match self.limit {
+            Some(limit) => select.push_str(&format!(" LIMIT {} OFFSET {}", limit + 1, self.offset)),
+            // SQLite richiede LIMIT per utilizzare OFFSET, -1 indica nessun limite
+            None if self.offset > 0 && dialect == SqlDialect::SQLite => select.push_str(&format!(" LIMIT -1 OFFSET {}", self.offset)),
+            None if self.offset > 0 => select.push_str(&format!(" OFFSET {}", self.offset)),
+            None => {}
+        }
+
+        SqlQuery { select, count, params, count_params }
+    }
+
+    /// Traduce la query in filtro, ordinamento e paginazione per MongoDB.
+    ///
+    /// # Parametri
+    /// - `text_fields`: Campi su cui applicare la ricerca testuale.
+    ///
+    /// # Note
+    /// L'ID del modello è salvato in `_id` (vedi `mongo_store.rs`), quindi `id` viene tradotto in `_id`.
+    pub fn to_mongo(&self, text_fields: &[&str]) -> MongoQuery {
+        let mut conditions: Vec<Document> = self.filters.iter().map(filter_to_bson).collect();
+
+        if let Some(text) = &self.text {
+            let alternatives: Vec<Document> = text_fields
+                .iter()
+                .map(|field| doc! { mongo_field(field): { "$regex": regex_escape(text), "$options": "i" } })
+                .collect();
+            conditions.push(doc! { "$or": alternatives });
+        }
+        let count_filter = and_documents(conditions.clone());
+
+        let keys = self.sort_keys();
+        if let Some(cursor) = &self.after {
+            let alternatives: Vec<Document> = keys
+                .iter()
+                .enumerate()
+                .filter(|(i, (_, direction))| cursor.0[*i] != ModelValue::Null || *direction == SortDirection::Asc)
+                .map(|(i, (field, direction))| {
+                    // `{ campo: null }` corrisponde anche ai documenti senza il campo
+                    let mut parts: Vec<Document> = keys[..i]
+                        .iter()
+                        .zip(&cursor.0)
+                        .map(|((previous, _), value)| doc! { mongo_field(previous): value.to_bson() })
+                        .collect();
+                    let field = mongo_field(field);
+                    parts.push(match (&cursor.0[i], direction) {
+                        (ModelValue::Null, _) => doc! { field: { "$ne": Bson::Null } },
+                        (value, SortDirection::Asc) => doc! { field: { "$gt": value.to_bson() } },
+                        (value, SortDirection::Desc) => {
+                            doc! { "$or": [{ field: { "$lt": value.to_bson() } }, { field: Bson::Null }] }
+                        }
+                    });
+                    doc! { "$and": parts }
+                })
+                .collect();
+            conditions.push(doc! { "$or": alternatives });
+        }
+
+        let mut sort = Document::new();
+        for (field, direction) in &keys {
+            sort.insert(mongo_field(field), if *direction == SortDirection::Asc { 1 } else { -1 });
+        }
+
+        MongoQuery {
+            filter: and_documents(conditions),
+            count_filter,
+            sort,
+            skip: self.offset as u64,
+            limit: self.limit.map(|limit| limit as i64 + 1),
+        }
+    }
+}
+
+/// Verifica un filtro sul valore di un campo.
+fn filter_matches(filter: &Filter, value: &ModelValue) -> bool {
+    let ordering = value.compare(&filter.value);
+    match filter.op {
+        FilterOp::Eq => ordering == Some(Ordering::Equal),
+        FilterOp::Ne => ordering != Some(Ordering::Equal),
+        FilterOp::Gt => ordering == Some(Ordering::Greater),
+        FilterOp::Gte => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        FilterOp::Lt => ordering == Some(Ordering::Less),
+        FilterOp::Lte => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        FilterOp::Contains => match (value.as_text(), filter.value.as_text()) {
+            (Some(text), Some(needle)) => text.contains(needle),
+            _ => false,
+        },
+        FilterOp::StartsWith => match (value.as_text(), filter.value.as_text()) {
+            (Some(text), Some(prefix)) => text.starts_with(prefix),
+            _ => false,
+        },
+    }
+}
+
+/// Valori dei campi di ordinamento di un record.
+fn sort_values(keys: &[(String, SortDirection)], values: &HashMap<&str, ModelValue>) -> Vec<ModelValue> {
+    keys.iter()
+        .map(|(field, _)| values.get(field.as_str()).cloned().unwrap_or(ModelValue::Null))
+        .collect()
+}
+
+/// Confronta due record in base ai campi di ordinamento.
+fn compare_keys(keys: &[(String, SortDirection)], a: &[ModelValue], b: &[ModelValue]) -> Ordering {
+    for ((_, direction), (a, b)) in keys.iter().zip(a.iter().zip(b)) {
+        let ordering = a.compare(b).unwrap_or(Ordering::Equal);
+        let ordering = if *direction == SortDirection::Asc { ordering } else { ordering.reverse() };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Segnaposto del parametro in posizione `index` (a partire da 1).
+fn placeholder(dialect: SqlDialect, index: usize) -> String {
+    match dialect {
+        SqlDialect::PostgreSQL => format!("${}", index),
+        SqlDialect::SQLite => "?".to_string(),
+    }
+}
+
+fn where_clause(conditions: &[String]) -> String {
+    match conditions.is_empty() {
+        true => String::new(),
+        false => format!(" WHERE {}", conditions.join(" AND ")),
+    }
+}
+
+/// Pattern LIKE con i caratteri speciali del valore protetti da `\`.
+fn like_pattern(value: &ModelValue, prefix: &str, suffix: &str) -> ModelValue {
+    let text = value.as_text().unwrap_or_default();
+    let escaped = text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    ModelValue::Text(format!("{}{}{}", prefix, escaped, suffix))
+}
+
+/// Pattern GLOB di SQLite con i caratteri speciali del valore racchiusi tra parentesi quadre.
+fn glob_pattern(value: &ModelValue, prefix: &str, suffix: &str) -> ModelValue {
+    let mut escaped = String::new();
+    for c in value.as_text().unwrap_or_default().chars() {
+        match c {
+            '*' | '?' | '[' => escaped.push_str(&format!("[{}]", c)),
+            c => escaped.push(c),
+        }
+    }
+    ModelValue::Text(format!("{}{}{}", prefix, escaped, suffix))
+}
+
+/// Protegge i caratteri speciali delle espressioni regolari di MongoDB.
+fn regex_escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Nome del campo nei documenti MongoDB, con `id` salvato come `_id`.
+fn mongo_field(field: &str) -> &str {
+    match field {
+        "id" => "_id",
+        field => field,
+    }
+}
+
+fn filter_to_bson(filter: &Filter) -> Document {
+    let field = mongo_field(&filter.field);
+    let value = filter.value.to_bson();
+    match filter.op {
+        FilterOp::Eq => doc! { field: value },
+        FilterOp::Ne => doc! { field: { "$ne": value } },
+        FilterOp::Gt => doc! { field: { "$gt": value } },
+        FilterOp::Gte => doc! { field: { "$gte": value } },
+        FilterOp::Lt => doc! { field: { "$lt": value } },
+        FilterOp::Lte => doc! { field: { "$lte": value } },
+        FilterOp::Contains => doc! { field: { "$regex": regex_escape(filter.value.as_text().unwrap_or_default()) } },
+        FilterOp::StartsWith => {
+            doc! { field: { "$regex": format!("^{}", regex_escape(filter.value.as_text().unwrap_or_default())) } }
+        }
+    }
+}
+
+fn and_documents(conditions: Vec<Document>) -> Document {
+    match conditions.len() {
+        0 => Document::new(),
+        _ => doc! { "$and": conditions.into_iter().map(Bson::Document).collect::<Vec<_>>() },
+    }
+}
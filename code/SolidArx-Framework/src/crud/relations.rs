@@ -0,0 +1,170 @@
+//! Modulo per le relazioni tra i modelli CRUD.
+//!
+//! Le relazioni vengono dichiarate sui modelli con `#[derive(ArxModel)]`:
+//! - `#[arx(belongs_to = "Article", on_delete = "cascade")]` sul campo chiave esterna (es. `Comment.article_id`):
+//!   il record collegato deve esistere alla creazione e all'aggiornamento, e `table_scraper.rs`
+//!   genera il vincolo `REFERENCES article(id)` con la politica di eliminazione.
+//! - `#[arx(has_many = "Comment")]` sul modello collegato: all'eliminazione definitiva del record
+//!   viene applicata la politica del `belongs_to` del modello dipendente.
+//! - Molti a molti: tramite un modello di collegamento con due `belongs_to` (es. `ArticleTag`).
+//!
+//! ### Politiche di eliminazione (`OnDelete`):
+//! - `Cascade`: I record dipendenti vengono eliminati definitivamente insieme al record.
+//! - `Restrict` (default): L'eliminazione restituisce `CrudError::ReferenceViolation` se esistono record dipendenti.
+//!
+//! Le politiche vengono applicate dalle funzioni CRUD (per tutti gli storage, memoria inclusa)
+//! nella stessa transazione dell'eliminazione (vedi `unit_of_work.rs`). Lo spostamento nel cestino
+//! (`ArxModel::SOFT_DELETE`) non coinvolge i record dipendenti, solo la rimozione definitiva.
+//!
+//! ### Caricamento:
+//! - `parent`: Record collegato tramite `belongs_to` (es. l'`Article` di un `Comment`).
+//! - `children`: Record dipendenti (es. i `Comment` di un `Article`).
+//! - `load_with`: Record con i suoi dipendenti in una sola chiamata.
+//! - `related`, `link`, `unlink`: Record collegati tramite un modello di collegamento.
+
+use std::collections::HashMap;
+
+use crate::crud::crud_ops::{self, ArxModel, CrudError};
+use crate::crud::model_value::{field_value, ModelValue, ToModelValue};
+use crate::crud::query::{Page, Query};
+
+/// Politica applicata ai record dipendenti all'eliminazione definitiva del record collegato.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnDelete {
+    Cascade,
+    Restrict,
+}
+
+impl OnDelete {
+    /// Clausola SQL del vincolo di chiave esterna.
+    pub fn to_sql(&self) -> &'static str {
+        match self {
+            OnDelete::Cascade => "ON DELETE CASCADE",
+            OnDelete::Restrict => "ON DELETE RESTRICT",
+        }
+    }
+}
+
+/// Relazione `belongs_to` dichiarata su un campo chiave esterna.
+///
+/// # Campi
+/// - `field`: Campo chiave esterna del modello dipendente.
+/// - `target`: Tabella del modello collegato.
+/// - `on_delete`: Politica all'eliminazione del record collegato.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Relation {
+    pub field: &'static str,
+    pub target: &'static str,
+    pub on_delete: OnDelete,
+}
+
+/// Relazione di `C` verso `P`, `CrudError::InvalidQuery` se non è dichiarata.
+///
+/// Con più relazioni verso lo stesso modello (es. `author_id` e `reviewer_id`) viene utilizzata la prima.
+pub fn relation<C: ArxModel, P: ArxModel>() -> Result<Relation, CrudError> {
+    C::relations().into_iter().find(|relation| relation.target == P::TABLE_NAME).ok_or_else(|| {
+        CrudError::InvalidQuery(format!("Il modello {} non dichiara una relazione con {}", C::TABLE_NAME, P::TABLE_NAME))
+    })
+}
+
+/// Verifica che esista il record collegato dalla chiave esterna, utilizzata dal codice di `#[derive(ArxModel)]`.
+///
+/// # Ritorna
+/// `CrudError::ReferenceViolation` se il record di `P` non esiste.
+pub fn ensure_exists<C: ArxModel, P: ArxModel>(item: &C, key: &P::Id) -> Result<(), CrudError> {
+    match crud_ops::find_model::<P>(key) {
+        Ok(_) => Ok(()),
+        Err(CrudError::NotFound { .. }) => Err(CrudError::reference_violation::<C>(&item.id(), P::TABLE_NAME)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Applica la politica di eliminazione ai record di `C` che dipendono dal record di `P`,
+/// utilizzata dal codice di `#[derive(ArxModel)]` per `has_many`.
+pub fn on_parent_delete<P: ArxModel, C: ArxModel>(id: &P::Id) -> Result<(), CrudError> {
+    let relation = relation::<C, P>()?;
+    let query = Query::new().include_revoked().include_trashed().eq(relation.field, id.clone());
+    let dependents = crud_ops::query_models::<C>(&query)?.items;
+    match relation.on_delete {
+        OnDelete::Restrict if !dependents.is_empty() => Err(CrudError::reference_violation::<P>(id, C::TABLE_NAME)),
+        OnDelete::Restrict => Ok(()),
+        OnDelete::Cascade => {
+            for dependent in dependents {
+                crud_ops::remove_dependent::<C>(&dependent.id())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Chiave esterna di `child` verso il modello `P`.
+fn foreign_key<C: ArxModel, P: ArxModel>(child: &C) -> Result<P::Id, CrudError> {
+    let relation = relation::<C, P>()?;
+    let values: HashMap<String, ModelValue> =
+        child.to_values().into_iter().map(|(field, value)| (field.to_string(), value)).collect();
+    field_value(&values, relation.field).map_err(|e| CrudError::StorageError(format!("{}: {}", C::TABLE_NAME, e)))
+}
+
+/// Legge il record di `P` collegato a `child` tramite `belongs_to`.
+pub fn parent<C: ArxModel, P: ArxModel>(child: &C) -> Result<P, CrudError> {
+    crud_ops::read_model::<P>(foreign_key::<C, P>(child)?)
+}
+
+/// Elenca i record di `C` che dipendono da `parent`, secondo filtri, ordinamento e paginazione della query.
+pub fn children<P: ArxModel, C: ArxModel>(parent: &P, query: &Query) -> Result<Page<C>, CrudError> {
+    let relation = relation::<C, P>()?;
+    crud_ops::list_models::<C>(&query.clone().eq(relation.field, parent.id()))
+}
+
+/// Legge un record di `P` insieme a tutti i suoi record dipendenti di `C`.
+///
+/// # Esempio
+/// ```rust,ignore
+/// let (article, comments) = relations::load_with::<Article, Comment>(article_id)?;
+/// ```
+pub fn load_with<P: ArxModel, C: ArxModel>(id: P::Id) -> Result<(P, Vec<C>), CrudError> {
+    let parent = crud_ops::read_model::<P>(id)?;
+    let dependents = children::<P, C>(&parent, &Query::new())?.items;
+    Ok((parent, dependents))
+}
+
+/// Elenca i record di `T` collegati a `parent` tramite il modello di collegamento `J` (molti a molti).
+///
+/// # Esempio
+/// ```rust,ignore
+/// let tags = relations::related::<Article, ArticleTag, Tag>(&article)?;
+/// ```
+pub fn related<P: ArxModel, J: ArxModel, T: ArxModel>(parent: &P) -> Result<Vec<T>, CrudError> {
+    children::<P, J>(parent, &Query::new())?
+        .items
+        .iter()
+        .map(|link| foreign_key::<J, T>(link).and_then(crud_ops::read_model::<T>))
+        .collect()
+}
+
+/// Collega `parent` e `target` creando un record del modello di collegamento `J`.
+///
+/// Il modello di collegamento deve avere solo `id` e le due chiavi esterne.
+pub fn link<P: ArxModel, J: ArxModel, T: ArxModel>(parent: &P::Id, target: &T::Id) -> Result<J, CrudError> {
+    let mut values: HashMap<String, ModelValue> = HashMap::new();
+    values.insert("id".to_string(), ModelValue::Int(0));
+    values.insert(relation::<J, P>()?.field.to_string(), parent.to_model_value());
+    values.insert(relation::<J, T>()?.field.to_string(), target.to_model_value());
+    let link = J::from_values(&values).map_err(|e| CrudError::StorageError(format!("{}: {}", J::TABLE_NAME, e)))?;
+    crud_ops::create_model(link)
+}
+
+/// Rimuove il collegamento tra `parent` e `target`.
+///
+/// # Ritorna
+/// Il numero di collegamenti rimossi.
+pub fn unlink<P: ArxModel, J: ArxModel, T: ArxModel>(parent: &P::Id, target: &T::Id) -> Result<usize, CrudError> {
+    let query = Query::new()
+        .eq(relation::<J, P>()?.field, parent.clone())
+        .eq(relation::<J, T>()?.field, target.clone());
+    let links = crud_ops::list_models::<J>(&query)?.items;
+    for link in &links {
+        crud_ops::delete_model::<J>(link.id())?;
+    }
+    Ok(links.len())
+}
@@ -0,0 +1,122 @@
+//! Modulo per la revoca dei record (token, API key, firmware, comandi, ecc.).
+//!
+//! I modelli che dichiarano l'operazione `revoke` hanno un campo `revocation: Option<Revocation>`,
+//! persistito come gli altri campi nello storage del modello. Un record revocato:
+//! - Non viene restituito da `Read`, `List` e `Search` (salvo `Query::include_revoked`).
+//! - Mantiene data, motivo e autore della revoca.
+//!
+//! Lo stato di revoca viene mantenuto in una cache in memoria per `is_revoked`, così da evitare
+//! una lettura dallo storage ad ogni controllo (es. validazione di un token per ogni richiesta).
+//! La cache viene invalidata da `Create`, `Update` e `Delete` sul record.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use chrono::{NaiveDateTime, Utc};
+use log::{error, info};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::crud::crud_ops::{revoke_model, ArxModel, CrudError};
+use crate::crud::model_value::{field_value, FromModelValue, ModelValue, ToModelValue};
+use crate::crud::query::Query;
+
+/// Stato di revoca noto per ogni record, indicizzato per tabella e ID.
+static REVOKED: Lazy<Mutex<HashMap<(&'static str, String), bool>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Dati della revoca di un record.
+///
+/// # Campi
+/// - `revoked_at`: Data e ora della revoca (UTC).
+/// - `reason`: Motivo della revoca.
+/// - `actor`: Utente o servizio che ha eseguito la revoca.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revocation {
+    pub revoked_at: NaiveDateTime,
+    pub reason: String,
+    pub actor: String,
+}
+
+impl Revocation {
+    /// Crea una revoca con la data e l'ora correnti.
+    pub fn new(reason: &str, actor: &str) -> Self {
+        Revocation {
+            revoked_at: Utc::now().naive_utc(),
+            reason: reason.to_string(),
+            actor: actor.to_string(),
+        }
+    }
+}
+
+impl ToModelValue for Revocation {
+    fn to_model_value(&self) -> ModelValue {
+        let mut values = BTreeMap::new();
+        values.insert("revoked_at".to_string(), self.revoked_at.to_model_value());
+        values.insert("reason".to_string(), self.reason.to_model_value());
+        values.insert("actor".to_string(), self.actor.to_model_value());
+        ModelValue::Map(values)
+    }
+}
+
+impl FromModelValue for Revocation {
+    fn from_model_value(value: ModelValue) -> Result<Self, String> {
+        match value.into_structured() {
+            ModelValue::Map(values) => {
+                let values: HashMap<String, ModelValue> = values.into_iter().collect();
+                Ok(Revocation {
+                    revoked_at: field_value(&values, "revoked_at")?,
+                    reason: field_value(&values, "reason")?,
+                    actor: field_value(&values, "actor")?,
+                })
+            }
+            other => Err(format!("atteso map, trovato {}", other.type_name())),
+        }
+    }
+}
+
+/// Restituisce lo stato di revoca in cache, se noto.
+pub(crate) fn cached<T: ArxModel>(id: &T::Id) -> Option<bool> {
+    match REVOKED.lock() {
+        Ok(revoked) => revoked.get(&(T::TABLE_NAME, id.to_string())).copied(),
+        Err(e) => {
+            error!("Errore di lock sulla cache delle revoche: {}", e);
+            None
+        }
+    }
+}
+
+/// Aggiorna lo stato di revoca in cache.
+pub(crate) fn remember<T: ArxModel>(id: &T::Id, revoked: bool) {
+    if let Ok(mut cache) = REVOKED.lock() {
+        cache.insert((T::TABLE_NAME, id.to_string()), revoked);
+    }
+}
+
+/// Rimuove lo stato di revoca dalla cache, da chiamare quando il record viene modificato o eliminato.
+pub(crate) fn invalidate<T: ArxModel>(id: &T::Id) {
+    if let Ok(mut cache) = REVOKED.lock() {
+        cache.remove(&(T::TABLE_NAME, id.to_string()));
+    }
+}
+
+/// Revoca tutti i record di un utente, ad esempio i token e le API key alla disattivazione dell'account.
+///
+/// # Parametri
+/// - `user_id`: ID dell'utente proprietario dei record.
+/// - `reason`: Motivo della revoca.
+/// - `actor`: Utente o servizio che esegue la revoca.
+///
+/// # Ritorna
+/// Gli ID dei record revocati. I record già revocati vengono ignorati.
+/// Restituisce `CrudError::InvalidQuery` se il modello non ha un campo `user_id`.
+pub fn revoke_for_user<T: ArxModel>(user_id: u32, reason: &str, actor: &str) -> Result<Vec<T::Id>, CrudError> {
+    let records = crate::crud::crud_ops::query_models::<T>(&Query::new().eq("user_id", user_id))?;
+
+    let mut revoked = Vec::new();
+    for item in records.items {
+        revoke_model::<T>(item.id(), reason, actor)?;
+        revoked.push(item.id());
+    }
+    info!("Revocati {} record di {} per l'utente {}", revoked.len(), T::TABLE_NAME, user_id);
+    Ok(revoked)
+}
@@ -0,0 +1,179 @@
+//! Modulo per le transazioni su più modelli CRUD (unit of work).
+//!
+//! Le operazioni CRUD sono indipendenti: "creare un `Article` con i suoi `Tag`" oppure
+//! "inserire un `Command` e aggiornare il suo `Device`" richiedono più chiamate senza atomicità.
+//! Questo modulo le raggruppa in un'unica unità, applicata interamente o per niente:
+//! - `transaction`: Esegue una closure con le normali chiamate CRUD (`Article::create`, `Device::update`, ...),
+//!   utile quando le operazioni successive dipendono dagli ID assegnati dalle precedenti.
+//! - `UnitOfWork`: Registra creazioni, aggiornamenti ed eliminazioni su modelli diversi e le esegue con `commit`.
+//!
+//! ### Atomicità per storage:
+//! - `AllocType::Database`: Un'unica transazione sulla connessione registrata (SQLite o PostgreSQL),
+//!   oppure una sessione MongoDB se il server supporta le transazioni (vedi `database_store::transaction`).
+//! - `AllocType::InMemory`: Ogni scrittura registra nel journal della transazione come ripristinare
+//!   il record precedente, il journal viene applicato in ordine inverso in caso di errore.
+//! - `AllocType::Cached`: Durante la transazione le scritture raggiungono subito il database anche con
+//!   `WriteBehind`, i record modificati vengono rimossi dalla cache in caso di errore.
+//!
+//! Le scritture in memoria sono visibili agli altri thread prima del commit, mentre le loro operazioni
+//! sul database attendono la fine della transazione.
+
+use std::cell::RefCell;
+
+use log::{info, warn};
+
+use crate::crud::crud_ops::{self, ArxModel, CrudError};
+use crate::crud::database_store;
+
+/// Azione che annulla una scrittura non transazionale (memoria o cache).
+type Undo = Box<dyn FnOnce()>;
+
+thread_local! {
+    /// Journal della transazione attiva sul thread, `None` al di fuori di una transazione.
+    static JOURNAL: RefCell<Option<Vec<Undo>>> = RefCell::new(None);
+    /// Azioni da eseguire dopo il commit della transazione attiva sul thread (es. le notifiche di `watch.rs`).
+    static ON_COMMIT: RefCell<Vec<Box<dyn FnOnce()>>> = RefCell::new(Vec::new());
+}
+
+/// Indica se sul thread corrente è attiva una transazione.
+pub fn is_active() -> bool {
+    JOURNAL.with(|journal| journal.borrow().is_some())
+}
+
+/// Registra l'azione che annulla una scrittura, se è attiva una transazione.
+pub(crate) fn record_undo(undo: impl FnOnce() + 'static) {
+    JOURNAL.with(|journal| {
+        if let Some(journal) = journal.borrow_mut().as_mut() {
+            journal.push(Box::new(undo));
+        }
+    });
+}
+
+/// Esegue `action` dopo il commit della transazione attiva, oppure subito al di fuori di una transazione.
+///
+/// Se la transazione viene annullata l'azione non viene eseguita.
+pub(crate) fn after_commit(action: impl FnOnce() + 'static) {
+    if is_active() {
+        ON_COMMIT.with(|actions| actions.borrow_mut().push(Box::new(action)));
+    } else {
+        action();
+    }
+}
+
+/// Applica il journal in ordine inverso, anche in caso di panic durante la transazione.
+struct JournalScope;
+
+impl Drop for JournalScope {
+    fn drop(&mut self) {
+        if let Ok(Some(journal)) = JOURNAL.try_with(|journal| journal.borrow_mut().take()) {
+            for undo in journal.into_iter().rev() {
+                undo();
+            }
+        }
+        // Le azioni rimaste appartengono a una transazione annullata
+        let _ = ON_COMMIT.try_with(|actions| actions.borrow_mut().clear());
+    }
+}
+
+/// Esegue `work` come un'unica transazione su tutti i modelli coinvolti.
+///
+/// # Parametri
+/// - `work`: Le operazioni da eseguire, con le normali chiamate CRUD.
+///
+/// # Ritorna
+/// Il risultato di `work` dopo il commit. Se `work` restituisce un errore, tutte le scritture
+/// vengono annullate e viene restituito l'errore.
+///
+/// # Note
+/// Una transazione avviata all'interno di un'altra ne fa parte: l'errore annulla anche la transazione esterna.
+pub fn transaction<R>(work: impl FnOnce() -> Result<R, CrudError>) -> Result<R, CrudError> {
+    if is_active() {
+        return work();
+    }
+    JOURNAL.with(|journal| *journal.borrow_mut() = Some(Vec::new()));
+    let _scope = JournalScope;
+
+    let result = database_store::transaction(work);
+    if result.is_ok() {
+        // Commit riuscito, il journal non va applicato
+        JOURNAL.with(|journal| journal.borrow_mut().take());
+        for action in ON_COMMIT.with(|actions| actions.take()) {
+            action();
+        }
+    }
+    result
+}
+
+/// Operazione registrata in una `UnitOfWork`.
+type Operation = Box<dyn FnOnce() -> Result<(), CrudError>>;
+
+/// Insieme di creazioni, aggiornamenti ed eliminazioni su modelli diversi, eseguite con `commit`.
+///
+/// Le operazioni vengono eseguite nell'ordine di registrazione, con gli stessi controlli dei permessi
+/// delle chiamate CRUD. Il primo errore annulla tutte le operazioni già eseguite.
+///
+/// # Esempio
+/// ```ignore
+/// let mut unit = UnitOfWork::new();
+/// unit.create(command).update(device);
+/// unit.commit()?;
+/// ```
+#[derive(Default)]
+pub struct UnitOfWork {
+    operations: Vec<Operation>,
+}
+
+impl UnitOfWork {
+    /// Crea un'unità vuota.
+    pub fn new() -> Self {
+        UnitOfWork::default()
+    }
+
+    /// Registra la creazione di un record.
+    pub fn create<T: ArxModel>(&mut self, item: T) -> &mut Self {
+        self.operations.push(Box::new(move || crud_ops::create_model(item).map(|_| ())));
+        self
+    }
+
+    /// Registra l'aggiornamento di un record esistente.
+    pub fn update<T: ArxModel>(&mut self, item: T) -> &mut Self {
+        self.operations.push(Box::new(move || crud_ops::update_model(item).map(|_| ())));
+        self
+    }
+
+    /// Registra l'eliminazione di un record in base al suo ID.
+    pub fn delete<T: ArxModel>(&mut self, id: T::Id) -> &mut Self {
+        self.operations.push(Box::new(move || crud_ops::delete_model::<T>(id)));
+        self
+    }
+
+    /// Numero di operazioni registrate.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Indica se non ci sono operazioni registrate.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Esegue tutte le operazioni in un'unica transazione.
+    ///
+    /// # Ritorna
+    /// Il numero di operazioni eseguite, oppure l'errore della prima operazione fallita
+    /// dopo aver annullato le precedenti.
+    pub fn commit(self) -> Result<usize, CrudError> {
+        let total = self.operations.len();
+        let result = transaction(|| {
+            for operation in self.operations {
+                operation()?;
+            }
+            Ok(total)
+        });
+        match &result {
+            Ok(total) => info!("Unit of work confermata con {} operazioni", total),
+            Err(e) => warn!("Unit of work annullata: {}", e),
+        }
+        result
+    }
+}
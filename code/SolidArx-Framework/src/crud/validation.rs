@@ -0,0 +1,226 @@
+//! Modulo per la validazione dei campi dei modelli CRUD.
+//!
+//! Le regole vengono dichiarate sui campi con `#[derive(ArxModel)]` e verificate da `Create` e `Update`
+//! prima di scrivere nello storage:
+//! ```rust,ignore
+//! #[arx(validate(email, length(max = 254)))]
+//! pub email: String,
+//! #[arx(validate(cron))]
+//! pub cron_expression: String,
+//! ```
+//!
+//! ### Regole supportate:
+//! - `email`: Indirizzo email (`utente@dominio.tld`).
+//! - `regex = "..."`: Il testo deve corrispondere all'espressione regolare.
+//! - `length(min = .., max = ..)`: Numero di caratteri del testo o di elementi della lista.
+//! - `range(min = .., max = ..)`: Valore numerico compreso tra gli estremi (inclusi).
+//! - `cron`: Espressione cron a 5 o 6 campi (con i secondi) oppure `@hourly`, `@daily`, ...
+//! - `url`: URL assoluto con schema `http`, `https`, `ws`, `wss` o `ftp`.
+//! - `path`: Percorso di un endpoint (es. `/api/users/:id` o `/api/users/{id}`).
+//! - `custom = "funzione"`: Funzione `fn(&TipoDelCampo) -> Result<(), String>`.
+//!
+//! I campi opzionali con valore `None` non vengono verificati. Tutti i campi non validi vengono
+//! restituiti insieme in `CrudError::ValidationFailed`, serializzabili con `serde` per il layer API.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::crud::model_value::ModelValue;
+
+/// Espressione regolare della regola `email`.
+const EMAIL_PATTERN: &str = r"^[^\s@]+@[^\s@]+\.[^\s@]+$";
+/// Espressione regolare della regola `url`.
+const URL_PATTERN: &str = r"^(https?|wss?|ftp)://[^\s/?#]+([/?#]\S*)?$";
+/// Espressione regolare della regola `path`: segmenti statici, `:parametro` o `{parametro}`.
+const PATH_PATTERN: &str =
+    r"^/(([A-Za-z0-9._~-]+|:[A-Za-z_]\w*|\{[A-Za-z_]\w*\})(/([A-Za-z0-9._~-]+|:[A-Za-z_]\w*|\{[A-Za-z_]\w*\}))*/?)?$";
+/// Macro cron equivalenti a un'espressione completa.
+const CRON_MACROS: [&str; 7] = ["@yearly", "@annually", "@monthly", "@weekly", "@daily", "@midnight", "@hourly"];
+
+/// Espressioni regolari già compilate, per pattern.
+static PATTERNS: Lazy<Mutex<HashMap<&'static str, Regex>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Campo non valido restituito in `CrudError::ValidationFailed`.
+///
+/// # Campi
+/// - `field`: Nome del campo.
+/// - `rule`: Regola non soddisfatta (es. `email`, `length`, `custom`).
+/// - `message`: Descrizione dell'errore.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub rule: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, rule: &str, message: impl Into<String>) -> Self {
+        FieldError { field: field.to_string(), rule: rule.to_string(), message: message.into() }
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.field, self.rule, self.message)
+    }
+}
+
+/// Regola di validazione di un campo, generata da `#[arx(validate(...))]`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    Email,
+    Regex(&'static str),
+    Length { min: Option<usize>, max: Option<usize> },
+    Range { min: Option<f64>, max: Option<f64> },
+    Cron,
+    Url,
+    Path,
+}
+
+impl Rule {
+    /// Nome della regola, come in `#[arx(validate(...))]`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::Email => "email",
+            Rule::Regex(_) => "regex",
+            Rule::Length { .. } => "length",
+            Rule::Range { .. } => "range",
+            Rule::Cron => "cron",
+            Rule::Url => "url",
+            Rule::Path => "path",
+        }
+    }
+
+    /// Verifica il valore del campo.
+    ///
+    /// # Ritorna
+    /// La descrizione dell'errore se il valore non soddisfa la regola.
+    fn check(&self, value: &ModelValue) -> Result<(), String> {
+        match (self, value) {
+            (Rule::Email, ModelValue::Text(text)) => match text.len() <= 254 && is_match(EMAIL_PATTERN, text)? {
+                true => Ok(()),
+                false => Err(format!("'{}' non è un indirizzo email valido", text)),
+            },
+            (Rule::Regex(pattern), ModelValue::Text(text)) => match is_match(pattern, text)? {
+                true => Ok(()),
+                false => Err(format!("'{}' non corrisponde a {}", text, pattern)),
+            },
+            (Rule::Length { min, max }, ModelValue::Text(text)) => within("lunghezza", text.chars().count(), *min, *max),
+            (Rule::Length { min, max }, ModelValue::List(values)) => within("lunghezza", values.len(), *min, *max),
+            (Rule::Range { min, max }, ModelValue::Int(number)) => within("valore", *number as f64, *min, *max),
+            (Rule::Range { min, max }, ModelValue::Float(number)) => within("valore", *number, *min, *max),
+            (Rule::Cron, ModelValue::Text(text)) => validate_cron(text),
+            (Rule::Url, ModelValue::Text(text)) => match is_match(URL_PATTERN, text)? {
+                true => Ok(()),
+                false => Err(format!("'{}' non è un URL valido", text)),
+            },
+            (Rule::Path, ModelValue::Text(text)) => match is_match(PATH_PATTERN, text)? {
+                true => Ok(()),
+                false => Err(format!("'{}' non è un percorso valido", text)),
+            },
+            (rule, other) => Err(format!("regola {} non applicabile a un valore {}", rule.name(), other.type_name())),
+        }
+    }
+}
+
+/// Verifica che il valore sia compreso tra gli estremi indicati (inclusi).
+fn within<N: PartialOrd + std::fmt::Display>(what: &str, value: N, min: Option<N>, max: Option<N>) -> Result<(), String> {
+    match (min, max) {
+        (Some(min), _) if value < min => Err(format!("{} {} inferiore al minimo di {}", what, value, min)),
+        (_, Some(max)) if value > max => Err(format!("{} {} superiore al massimo di {}", what, value, max)),
+        _ => Ok(()),
+    }
+}
+
+/// Verifica il testo con un'espressione regolare, compilata al primo utilizzo.
+fn is_match(pattern: &'static str, text: &str) -> Result<bool, String> {
+    let mut patterns = PATTERNS.lock().map_err(|e| format!("Errore di lock sul mutex: {}", e))?;
+    if !patterns.contains_key(pattern) {
+        let regex = Regex::new(pattern).map_err(|e| format!("espressione regolare {} non valida: {}", pattern, e))?;
+        patterns.insert(pattern, regex);
+    }
+    Ok(patterns[pattern].is_match(text))
+}
+
+/// Verifica il valore di un campo con le regole dichiarate, utilizzata dal codice di `#[derive(ArxModel)]`.
+///
+/// # Ritorna
+/// Un `FieldError` per ogni regola non soddisfatta, nessuno se il valore è `Null`.
+pub fn check(field: &str, value: &ModelValue, rules: &[Rule]) -> Vec<FieldError> {
+    if *value == ModelValue::Null {
+        return Vec::new();
+    }
+    rules
+        .iter()
+        .filter_map(|rule| rule.check(value).err().map(|message| FieldError::new(field, rule.name(), message)))
+        .collect()
+}
+
+/// Verifica un'espressione cron a 5 campi (minuti, ore, giorno del mese, mese, giorno della settimana)
+/// o a 6 campi con i secondi in testa.
+pub fn validate_cron(expression: &str) -> Result<(), String> {
+    let expression = expression.trim();
+    if CRON_MACROS.contains(&expression) {
+        return Ok(());
+    }
+
+    const NUMBERS: &[&str] = &[];
+    const MONTHS: &[&str] = &["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+    const DAYS: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+    // Campo, valore minimo, valore massimo e nomi ammessi a partire dal minimo
+    let mut bounds: Vec<(&str, u32, u32, &[&str])> = vec![
+        ("minuti", 0, 59, NUMBERS),
+        ("ore", 0, 23, NUMBERS),
+        ("giorno", 1, 31, NUMBERS),
+        ("mese", 1, 12, MONTHS),
+        ("giorno della settimana", 0, 7, DAYS),
+    ];
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    match fields.len() {
+        5 => {}
+        6 => bounds.insert(0, ("secondi", 0, 59, NUMBERS)),
+        count => return Err(format!("'{}' ha {} campi, attesi 5 o 6", expression, count)),
+    }
+
+    for (field, (name, min, max, names)) in fields.iter().zip(&bounds) {
+        let value = |token: &str| -> Result<u32, String> {
+            let parsed = match names.iter().position(|n| n.eq_ignore_ascii_case(token)) {
+                Some(index) => index as u32 + min,
+                None => token.parse::<u32>().map_err(|_| format!("'{}' non valido per il campo {}", token, name))?,
+            };
+            match (*min..=*max).contains(&parsed) {
+                true => Ok(parsed),
+                false => Err(format!("{} fuori dall'intervallo {}-{} del campo {}", parsed, min, max, name)),
+            }
+        };
+        for item in field.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+            if let Some(step) = step {
+                if !matches!(step.parse::<u32>(), Ok(step) if step > 0) {
+                    return Err(format!("passo '{}' non valido per il campo {}", step, name));
+                }
+            }
+            match range {
+                "*" => {}
+                "?" if *name == "giorno" || *name == "giorno della settimana" => {}
+                _ => match range.split_once('-') {
+                    Some((start, end)) if value(start)? > value(end)? => {
+                        return Err(format!("intervallo '{}' non valido per il campo {}", range, name));
+                    }
+                    Some(_) => {}
+                    None => {
+                        value(range)?;
+                    }
+                },
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,383 @@
+//! Modulo per l'osservazione delle modifiche dei modelli CRUD (watch).
+//!
+//! Invece di interrogare periodicamente `List`, UI e automazioni possono ricevere gli eventi di creazione,
+//! aggiornamento ed eliminazione dei record di un modello, eventualmente filtrati da una `Query`:
+//! ```rust,ignore
+//! let mut changes = watch::subscribe::<Task>(&Query::new().eq("status", TaskStatus::Completed))?;
+//! while let Ok(event) = changes.next().await {
+//!     info!("Task {} completato", event.id);
+//! }
+//! ```
+//!
+//! ### Origine degli eventi:
+//! - Le operazioni CRUD del processo notificano le modifiche dopo il commit della transazione
+//!   (vedi `unit_of_work::after_commit`): le modifiche annullate non vengono mai notificate.
+//! - `listen` riceve anche le modifiche degli altri processi: con PostgreSQL tramite `LISTEN/NOTIFY`
+//!   (vedi `database_store::listen_notifications`), con MongoDB tramite i change stream
+//!   (vedi `mongo_store::watch_changes`). Con un listener attivo le modifiche ai modelli su database
+//!   arrivano solo dal listener, comprese quelle del processo stesso.
+//!
+//! ### Cursori:
+//! Ogni evento ha un numero di sequenza crescente per modello. Gli ultimi `HISTORY_CAPACITY` eventi
+//! vengono conservati, così che un osservatore interrotto possa riprendere con `resume` dall'ultimo
+//! cursore ricevuto (`Watch::cursor`) senza perdere eventi. I cursori sono validi nel processo corrente
+//! e gli eventi vengono conservati solo dopo la prima sottoscrizione al modello.
+//!
+//! Gli eventi di eliminazione contengono l'ultimo stato del record solo se disponibile (modelli con audit,
+//! cestino, purge): senza stato vengono inoltrati a tutti gli osservatori del modello, anche con filtri.
+
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{NaiveDateTime, Utc};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use tokio::sync::broadcast::{self, error::RecvError, error::TryRecvError};
+
+use crate::config::network_config::DatabaseType;
+use crate::crud::crud_ops::{self, AllocType, ArxModel, CrudError, CrudOperation};
+use crate::crud::database_store;
+use crate::crud::model_value::{FromModelValue, ModelValue, ToModelValue};
+use crate::crud::models::default::audit_entry::model::AuditAction;
+use crate::crud::mongo_store;
+use crate::crud::query::Query;
+use crate::crud::unit_of_work;
+
+/// Numero di eventi conservati per modello per la ripresa con `resume`.
+pub const HISTORY_CAPACITY: usize = 1024;
+
+/// Dimensione massima del contenuto di una notifica PostgreSQL, oltre la quale il record viene riletto.
+const MAX_NOTIFY_PAYLOAD: usize = 7900;
+
+/// Canali indicizzati per nome di tabella, con il tipo del modello cancellato.
+type ChannelMap = HashMap<&'static str, Box<dyn Any + Send>>;
+
+/// Canali degli eventi, per tabella (`Channel<T>`).
+static CHANNELS: Lazy<Mutex<ChannelMap>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// Funzioni che convertono le modifiche ricevute dal database negli eventi del modello, per tabella.
+static REMOTE: Lazy<Mutex<HashMap<&'static str, mongo_store::ChangeHandler>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// Indica se è attivo un listener delle modifiche del database (vedi `listen`).
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+/// Tipo di modifica di un record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl ChangeKind {
+    /// Nome della modifica, utilizzato nelle notifiche PostgreSQL.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Updated => "updated",
+            ChangeKind::Deleted => "deleted",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "created" => Some(ChangeKind::Created),
+            "updated" => Some(ChangeKind::Updated),
+            "deleted" => Some(ChangeKind::Deleted),
+            _ => None,
+        }
+    }
+}
+
+impl From<AuditAction> for ChangeKind {
+    /// Revoca e ripristino dal cestino modificano il record, il purge lo elimina.
+    fn from(action: AuditAction) -> Self {
+        match action {
+            AuditAction::Create => ChangeKind::Created,
+            AuditAction::Update | AuditAction::Revoke | AuditAction::Restore => ChangeKind::Updated,
+            AuditAction::Delete | AuditAction::Purge => ChangeKind::Deleted,
+        }
+    }
+}
+
+/// Modifica di un record del modello.
+///
+/// # Campi
+/// - `sequence`: Cursore dell'evento, crescente per modello.
+/// - `kind`: Tipo di modifica.
+/// - `id`: ID del record.
+/// - `item`: Nuovo stato del record, per le eliminazioni l'ultimo stato se disponibile.
+///   I campi dichiarati con `#[arx(secret)]` hanno il valore di default del tipo.
+/// - `timestamp`: Data della notifica (UTC).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent<T: ArxModel> {
+    pub sequence: u64,
+    pub kind: ChangeKind,
+    pub id: T::Id,
+    pub item: Option<T>,
+    pub timestamp: NaiveDateTime,
+}
+
+/// Canale degli eventi di un modello con gli ultimi eventi notificati.
+struct Channel<T: ArxModel> {
+    sender: broadcast::Sender<ChangeEvent<T>>,
+    history: VecDeque<ChangeEvent<T>>,
+    sequence: u64,
+}
+
+fn lock_channels() -> Result<MutexGuard<'static, ChannelMap>, CrudError> {
+    CHANNELS.lock().map_err(|e| CrudError::StorageError(format!("Errore di lock sui canali di watch: {}", e)))
+}
+
+/// Notifica agli osservatori la modifica di un record, chiamata dalle funzioni di `crud_ops.rs`.
+///
+/// Per i modelli su database la modifica viene inviata anche con `NOTIFY` su PostgreSQL.
+pub(crate) fn publish<T: ArxModel>(kind: ChangeKind, id: &T::Id, item: Option<&T>) {
+    let store = item.map(ArxModel::store).unwrap_or_else(T::default_store);
+    if store != AllocType::InMemory {
+        database_store::notify(|| notification::<T>(kind, id, item));
+        if LISTENING.load(Ordering::Relaxed) {
+            return;
+        }
+    }
+    let (id, item) = (id.clone(), item.cloned());
+    unit_of_work::after_commit(move || dispatch::<T>(kind, id, item));
+}
+
+/// Contenuto JSON della notifica PostgreSQL senza i campi segreti, e senza i valori del record se superano `MAX_NOTIFY_PAYLOAD`.
+fn notification<T: ArxModel>(kind: ChangeKind, id: &T::Id, item: Option<&T>) -> String {
+    let values = item.map(|item| {
+        ModelValue::Map(item.public_values().into_iter().map(|(field, value)| (field.to_string(), value)).collect()).to_json()
+    });
+    let mut payload = serde_json::json!({
+        "model": T::TABLE_NAME,
+        "kind": kind.name(),
+        "id": id.to_model_value().to_json(),
+        "item": values,
+    });
+    if payload.to_string().len() > MAX_NOTIFY_PAYLOAD {
+        payload["item"] = serde_json::Value::Null;
+    }
+    payload.to_string()
+}
+
+/// Registra l'evento nel canale del modello e lo invia agli osservatori.
+///
+/// Senza sottoscrizioni al modello l'evento non viene conservato.
+fn dispatch<T: ArxModel>(kind: ChangeKind, id: T::Id, item: Option<T>) {
+    let mut channels = match lock_channels() {
+        Ok(channels) => channels,
+        Err(e) => return warn!("{}", e),
+    };
+    let Some(channel) = channels.get_mut(T::TABLE_NAME).and_then(|channel| channel.downcast_mut::<Channel<T>>()) else {
+        return;
+    };
+    channel.sequence += 1;
+    let item = item.map(|mut item| {
+        item.redact();
+        item
+    });
+    let event = ChangeEvent { sequence: channel.sequence, kind, id, item, timestamp: Utc::now().naive_utc() };
+    if channel.history.len() == HISTORY_CAPACITY {
+        channel.history.pop_front();
+    }
+    channel.history.push_back(event.clone());
+    // Nessun osservatore attivo: l'evento resta disponibile per `resume`
+    let _ = channel.sender.send(event);
+}
+
+/// Converte una modifica ricevuta dal database nell'evento del modello.
+///
+/// Se il contenuto della notifica non include i valori, il record viene riletto dallo storage.
+fn remote_change<T: ArxModel>(table: &str, kind: ChangeKind, id: ModelValue, values: Option<HashMap<String, ModelValue>>) {
+    let id = match T::Id::from_model_value(id) {
+        Ok(id) => id,
+        Err(e) => return warn!("ID non valido nella modifica di {}: {}", table, e),
+    };
+    let item = match (kind, values) {
+        (_, Some(values)) => T::from_values(&values).ok(),
+        (ChangeKind::Deleted, None) => None,
+        (_, None) => crud_ops::find_model::<T>(&id).ok(),
+    };
+    dispatch::<T>(kind, id, item);
+}
+
+/// Inoltra una modifica ricevuta dal database al modello della tabella, se osservato.
+fn forward(table: &str, kind: ChangeKind, id: ModelValue, values: Option<HashMap<String, ModelValue>>) {
+    let handler = REMOTE.lock().ok().and_then(|handlers| handlers.get(table).copied());
+    if let Some(handler) = handler {
+        handler(table, kind, id, values);
+    }
+}
+
+/// Inoltra una notifica PostgreSQL ricevuta da `database_store::listen_notifications`.
+fn forward_notification(payload: &str) {
+    let json: serde_json::Value = match serde_json::from_str(payload) {
+        Ok(json) => json,
+        Err(e) => return warn!("Notifica non valida: {}", e),
+    };
+    let (Some(table), Some(kind)) = (json["model"].as_str(), json["kind"].as_str().and_then(ChangeKind::from_name)) else {
+        return warn!("Notifica non valida: {}", payload);
+    };
+    let values = match ModelValue::from_json(json["item"].clone()) {
+        ModelValue::Map(values) => Some(values.into_iter().collect()),
+        _ => None,
+    };
+    forward(table, kind, ModelValue::from_json(json["id"].clone()), values);
+}
+
+/// Riceve anche le modifiche eseguite da altri processi sul database.
+///
+/// - PostgreSQL: Connessione dedicata in ascolto delle notifiche `NOTIFY` inviate dalle scritture.
+/// - MongoDB: Change stream sul database dei modelli (con la connessione registrata in `database_store`).
+/// - SQLite: Non supportato, restituisce `CrudError::InvalidQuery`.
+pub fn listen(database: &DatabaseType) -> Result<(), CrudError> {
+    match database {
+        DatabaseType::PostgreSQL(config) => {
+            let url = config.database_url.as_deref().ok_or_else(|| {
+                CrudError::InvalidQuery("URL del database PostgreSQL non configurato".to_string())
+            })?;
+            database_store::listen_notifications(url, forward_notification)?;
+        }
+        DatabaseType::MongoDB(_) => match database_store::mongo_client()? {
+            Some(client) => mongo_store::watch_changes(&client, forward)?,
+            None => return Err(CrudError::StorageError("Nessuna connessione MongoDB registrata".to_string())),
+        },
+        _ => {
+            return Err(CrudError::InvalidQuery(
+                "Le modifiche degli altri processi sono disponibili solo con PostgreSQL e MongoDB".to_string(),
+            ))
+        }
+    }
+    LISTENING.store(true, Ordering::Relaxed);
+    info!("Watch in ascolto delle modifiche del database");
+    Ok(())
+}
+
+/// Sottoscrizione alle modifiche di un modello, creata con `subscribe` o `resume`.
+pub struct Watch<T: ArxModel> {
+    receiver: broadcast::Receiver<ChangeEvent<T>>,
+    backlog: VecDeque<ChangeEvent<T>>,
+    query: Query,
+    cursor: u64,
+}
+
+impl<T: ArxModel> Watch<T> {
+    /// Cursore dell'ultimo evento ricevuto, da passare a `resume` per riprendere l'osservazione.
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    /// Attende il prossimo evento che soddisfa la query.
+    ///
+    /// # Ritorna
+    /// L'evento, oppure `CrudError::StorageError` se l'osservatore è rimasto indietro di oltre
+    /// `HISTORY_CAPACITY` eventi (riprendere con `resume` dal `cursor`).
+    pub async fn next(&mut self) -> Result<ChangeEvent<T>, CrudError> {
+        loop {
+            let event = match self.backlog.pop_front() {
+                Some(event) => event,
+                None => self.receiver.recv().await.map_err(|e| self.receive_error(e))?,
+            };
+            if let Some(event) = self.accept(event) {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Restituisce il prossimo evento già ricevuto che soddisfa la query, senza attendere.
+    pub fn try_next(&mut self) -> Result<Option<ChangeEvent<T>>, CrudError> {
+        loop {
+            let event = match self.backlog.pop_front() {
+                Some(event) => event,
+                None => match self.receiver.try_recv() {
+                    Ok(event) => event,
+                    Err(TryRecvError::Empty) => return Ok(None),
+                    Err(TryRecvError::Lagged(missed)) => return Err(self.receive_error(RecvError::Lagged(missed))),
+                    Err(TryRecvError::Closed) => return Err(self.receive_error(RecvError::Closed)),
+                },
+            };
+            if let Some(event) = self.accept(event) {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    /// Aggiorna il cursore e filtra l'evento con la query.
+    fn accept(&mut self, event: ChangeEvent<T>) -> Option<ChangeEvent<T>> {
+        if event.sequence <= self.cursor {
+            return None;
+        }
+        self.cursor = event.sequence;
+        let matches = match &event.item {
+            Some(item) => self.query.matches(&item.to_values().into_iter().collect(), &T::text_fields()),
+            None => true,
+        };
+        matches.then_some(event)
+    }
+
+    fn receive_error(&self, error: RecvError) -> CrudError {
+        match error {
+            RecvError::Lagged(missed) => CrudError::StorageError(format!(
+                "Watch di {} in ritardo di {} eventi, riprendere dal cursore {}",
+                T::TABLE_NAME,
+                missed,
+                self.cursor
+            )),
+            RecvError::Closed => CrudError::StorageError(format!("Canale di watch di {} chiuso", T::TABLE_NAME)),
+        }
+    }
+}
+
+/// Osserva le modifiche dei record del modello che soddisfano i filtri della query,
+/// a partire dalla prossima modifica (`Query::new()` per tutti i record).
+///
+/// Richiede l'operazione `read` del modello.
+pub fn subscribe<T: ArxModel>(query: &Query) -> Result<Watch<T>, CrudError> {
+    open::<T>(query, None)
+}
+
+/// Riprende l'osservazione dal cursore di un `Watch` precedente, ricevendo prima gli eventi successivi
+/// ancora conservati.
+///
+/// # Ritorna
+/// `CrudError::InvalidQuery` se gli eventi successivi al cursore non sono più disponibili.
+pub fn resume<T: ArxModel>(query: &Query, cursor: u64) -> Result<Watch<T>, CrudError> {
+    open::<T>(query, Some(cursor))
+}
+
+fn open<T: ArxModel>(query: &Query, cursor: Option<u64>) -> Result<Watch<T>, CrudError> {
+    if !crud_ops::is_permitted::<T>(CrudOperation::Read) {
+        return Err(CrudError::OperationNotPermitted { model: T::TABLE_NAME, operation: CrudOperation::Read });
+    }
+    query.validate(&T::field_names()).map_err(CrudError::InvalidQuery)?;
+    if let Ok(mut handlers) = REMOTE.lock() {
+        handlers.insert(T::TABLE_NAME, remote_change::<T>);
+    }
+
+    let mut channels = lock_channels()?;
+    let channel = channels
+        .entry(T::TABLE_NAME)
+        .or_insert_with(|| {
+            Box::new(Channel::<T> { sender: broadcast::channel(HISTORY_CAPACITY).0, history: VecDeque::new(), sequence: 0 })
+        })
+        .downcast_mut::<Channel<T>>()
+        .ok_or_else(|| CrudError::StorageError(format!("Canale di watch non valido per {}", T::TABLE_NAME)))?;
+
+    let cursor = cursor.unwrap_or(channel.sequence);
+    let oldest = channel.history.front().map_or(channel.sequence + 1, |event| event.sequence);
+    if cursor + 1 < oldest {
+        return Err(CrudError::InvalidQuery(format!(
+            "Cursore {} di {} non più disponibile, il primo evento conservato è {}",
+            cursor,
+            T::TABLE_NAME,
+            oldest
+        )));
+    }
+    Ok(Watch {
+        receiver: channel.sender.subscribe(),
+        backlog: channel.history.iter().filter(|event| event.sequence > cursor).cloned().collect(),
+        query: query.clone(),
+        cursor,
+    })
+}
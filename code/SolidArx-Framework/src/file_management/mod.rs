@@ -3,6 +3,6 @@ pub mod resource_manager;
 
 pub fn initialize() -> Result<(), String> {
     // Logica di inizializzazione per File Management
-    println!("Initializing File Management module...");
+    log::info!("Initializing File Management module...");
     Ok(())
 }
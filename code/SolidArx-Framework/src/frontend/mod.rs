@@ -1,5 +1,5 @@
 pub fn initialize() -> Result<(), String> {
     // Logica di inizializzazione per Frontend
-    println!("Initializing Frontend module...");
+    log::info!("Initializing Frontend module...");
     Ok(())
 }
\ No newline at end of file
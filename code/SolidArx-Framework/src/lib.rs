@@ -21,7 +21,6 @@ pub mod network;
 ///
 /// Queste direttive permettono di includere o escludere moduli in base alle feature abilitate.
 /// Solo i moduli necessari per il tipo di applicazione scelto verranno compilati e inclusi.
-
 #[cfg(feature = "auth")]
 pub mod auth;
 
@@ -1,115 +1,587 @@
-use solid_arx::cli::{parse_arguments,Commands};
-use solid_arx::core::system_core::CoreSystem;
-use solid_arx::config::{
-    global_config::CoreConfig,
-    memory_config::MemoryConfig,
-    database_config::DatabaseType,
+use std::path::Path;
+use std::process::ExitCode;
+use serde_json::json;
+use solid_arx_lib::cli::{parse_arguments,requested_output,Commands,ModelCommands,DbCommands,DbConnectionArgs,DataCommands,RunOverrides};
+use solid_arx_lib::core::system_core::CoreSystem;
+use solid_arx_lib::config::{
+    global_config::ApplicationType,
+    init_wizard::run_wizard,
+    network_config::DatabaseType,
+    project_config::{ProjectConfig, CoreProfile, MemoryProfile, DatabaseProfile},
 };
-use solid_arx::monitoring::logger::setup_logging;
+use solid_arx_lib::monitoring::logger::{setup_logging, setup_logging_to_stderr};
+use solid_arx_lib::monitoring::output::{emit, CliError, CommandOutput, OutputFormat};
 use log::info;
 
-/// Funzione helper per gestire il comando Init e generare le configurazioni del Core e della Memoria
+/// Funzione helper per gestire il comando Init e salvare le configurazioni del Core e della Memoria nel profilo
+///
+/// Con `--interactive` la configurazione guidata imposta anche il database del profilo.
+#[allow(clippy::too_many_arguments)]
 fn handle_init(
-    cli_args: &solid_arx::cli::Cli,
-) -> Result<(CoreConfig, MemoryConfig), Box<dyn std::error::Error>> {
-    if let Commands::Init {
-        app_type,
-        memory_scale,
-        max_threads,
-        buffer_size,
-        pool_size,
-    } = &cli_args.command
-    {
-        info!("Inizializzazione del progetto:");
+    config_path: &Path,
+    profile: &str,
+    app_type: &Option<ApplicationType>,
+    interactive: bool,
+    memory_scale: u8,
+    max_threads: u8,
+    buffer_size: usize,
+    pool_size: usize,
+) -> Result<CommandOutput, CliError> {
+    let mut project_config = ProjectConfig::load(config_path).map_err(CliError::config)?;
+
+    if interactive {
+        // Le domande sono scritte su stderr, stdout resta riservato all'output del comando
+        let wizard_profile = run_wizard(&mut std::io::stdin().lock(), &mut std::io::stderr())
+            .map_err(CliError::usage)?;
+        wizard_profile.database_type().map_err(CliError::config)?;
+        *project_config.profile_mut(profile) = wizard_profile;
+    } else {
+        let app_type = app_type.as_ref()
+            .ok_or_else(|| CliError::usage("Specificare --app-type oppure --interactive"))?;
+
+        info!("Inizializzazione del progetto (profilo {}):", profile);
         info!("App Type: {:?}", app_type);
         info!("Memory Scale: {}", memory_scale);
         info!("Max Threads: {}", max_threads);
         info!("Buffer Size: {}", buffer_size);
         info!("Pool Size: {}", pool_size);
 
-        // Configurazioni Core e Memoria
-        let core_config = CoreConfig::new(app_type.clone(), *max_threads);
-        let memory_config = MemoryConfig::new(
-            define_pool_size(app_type.clone(), *pool_size),
-            define_buffer_size(app_type.clone(), *buffer_size),
-            define_multiplier(app_type.clone(), *memory_scale),
-        );
-
-        return Ok((core_config, memory_config));
+        // I valori pari a 0 vengono calcolati in base al tipo di applicazione all'avvio con `arx run`
+        let profile_config = project_config.profile_mut(profile);
+        profile_config.core = CoreProfile {
+            app_type: Some(format!("{:?}", app_type).to_lowercase()),
+            max_threads: Some(max_threads),
+        };
+        profile_config.memory = MemoryProfile {
+            memory_scale: Some(memory_scale),
+            buffer_size: (buffer_size != 0).then_some(buffer_size),
+            pool_size: (pool_size != 0).then_some(pool_size),
+        };
     }
+    let saved = project_config.profile(profile).map_err(CliError::config)?.clone();
+    project_config.save(config_path).map_err(CliError::filesystem)?;
 
-    Err("Comando Init non trovato".into())
+    let mut output = CommandOutput::new("init").data(json!({
+        "config": config_path.display().to_string(),
+        "profile": profile,
+        "settings": saved,
+    }));
+    output.line(format!("Profilo {} inizializzato in {}", profile, config_path.display()));
+    Ok(output)
 }
 
-/// Funzione helper per gestire il comando Database e generare la configurazione del database
+/// Funzione helper per gestire il comando Database e salvare la configurazione del database nel profilo
+#[allow(clippy::too_many_arguments)]
 fn handle_database(
-    cli_args: &solid_arx::cli::Cli,
-) -> Result<DatabaseType, Box<dyn std::error::Error>> {
-
-    match cli_args.command {
-        Commands::Database { database_type } => {
-            match database_type {
-                Some(database_type) => {
-                    info!("Configurazione del database:");
-                    info!("Database: {}", database_type_reference);
-                    info!("Database URL: {:?}", database_url);
-                    info!("Max Connections: {:?}", max_connections);
-                    info!("Retry Attempts: {:?}", retry_attempts);
-                    info!("Max Idle Time: {:?}", max_idle_time);
-                    info!("Connection Timeout: {:?}", connection_timeout);
-                    return Ok(DatabaseType::new(
-                        database_type_reference,
-                        database_url,
-                        max_connections,
-                        retry_attempts,
-                        max_ile_time,
-                        connection_timeout,
-                    ));
+    config_path: &Path,
+    profile: &str,
+    database_type: &DatabaseType,
+    database_url: &Option<String>,
+    max_connections: Option<u32>,
+    retry_attempts: Option<u32>,
+    max_idle_time: Option<u64>,
+    connection_timeout: Option<u64>,
+) -> Result<CommandOutput, CliError> {
+    info!("Configurazione del database (profilo {}):", profile);
+    info!("Database: {}", database_type.kind());
+    info!("Database URL: {:?}", database_url);
+    info!("Max Connections: {:?}", max_connections);
+    info!("Retry Attempts: {:?}", retry_attempts);
+    info!("Max Idle Time: {:?}", max_idle_time);
+    info!("Connection Timeout: {:?}", connection_timeout);
+
+    let mut project_config = ProjectConfig::load(config_path).map_err(CliError::config)?;
+    let database = DatabaseProfile {
+        database_type: Some(database_type.kind().to_string()),
+        database_url: database_url.clone(),
+        max_connections,
+        retry_attempts,
+        max_idle_time,
+        connection_timeout,
+    };
+    project_config.profile_mut(profile).database = database.clone();
+
+    // Verifica che la configurazione sia valida prima di salvarla
+    project_config.profile(profile)
+        .and_then(|p| p.database_type())
+        .map_err(CliError::config)?;
+    project_config.save(config_path).map_err(CliError::filesystem)?;
+
+    let mut output = CommandOutput::new("database").data(json!({
+        "config": config_path.display().to_string(),
+        "profile": profile,
+        "database": database,
+    }));
+    output.line(format!("Database del profilo {} configurato in {}", profile, config_path.display()));
+    Ok(output)
+}
+
+/// Funzione helper per gestire il comando Config e mostrare un profilo con i valori risolti
+fn handle_config(config_path: &Path, profile: &str) -> Result<CommandOutput, CliError> {
+    let project_config = ProjectConfig::load(config_path).map_err(CliError::config)?;
+    let profile_config = project_config.profile(profile).map_err(CliError::config)?;
+    let resolved = profile_config.resolve().map_err(CliError::config)?;
+
+    let mut output = CommandOutput::new("config").data(json!({
+        "config": config_path.display().to_string(),
+        "profile": profile,
+        "settings": profile_config,
+        "resolved": {
+            "app_type": format!("{:?}", resolved.core.app_type),
+            "max_threads": resolved.core.max_threads,
+            "pool_size": resolved.memory.pool_size,
+            "buffer_size": resolved.memory.buffer_size,
+            "memory_scale": resolved.memory.memory_scale,
+            "database_type": resolved.database.kind(),
+        },
+    }));
+    output.line(format!("Profilo {} ({})", profile, config_path.display()));
+    output.line(format!("  App Type: {:?}", resolved.core.app_type));
+    output.line(format!("  Max Threads: {}", resolved.core.max_threads));
+    output.line(format!("  Pool Size: {}", resolved.memory.pool_size));
+    output.line(format!("  Buffer Size: {}", resolved.memory.buffer_size));
+    output.line(format!("  Memory Scale: {}", resolved.memory.memory_scale));
+    output.line(format!("  Database: {}", resolved.database.kind()));
+    Ok(output)
+}
+
+/// Funzione helper per gestire il comando Status e riassumere i profili del progetto
+fn handle_status(config_path: &Path) -> Result<CommandOutput, CliError> {
+    use solid_arx_lib::monitoring::doctor::compiled_features;
+
+    let project_config = ProjectConfig::load(config_path).map_err(CliError::config)?;
+    let features = compiled_features();
+
+    let mut output = CommandOutput::new("status");
+    output.line(format!("Progetto: {}", config_path.display()));
+    if project_config.profiles.is_empty() {
+        output.line("  Nessun profilo configurato. Eseguire 'arx init'");
+    }
+    let mut profiles = Vec::new();
+    for (name, profile_config) in &project_config.profiles {
+        let app_type = profile_config.core.app_type.as_deref().unwrap_or("non impostato");
+        let database_type = profile_config.database.database_type.as_deref().unwrap_or("none");
+        // Il profilo è pronto per `arx run` se la sua configurazione viene risolta
+        let error = profile_config.resolve().err();
+        match &error {
+            None => output.line(format!("  {}: {} (database {})", name, app_type, database_type)),
+            Some(e) => output.line(format!("  {}: {} (database {}) non valido: {}", name, app_type, database_type, e)),
+        }
+        profiles.push(json!({
+            "name": name,
+            "app_type": profile_config.core.app_type,
+            "database_type": database_type,
+            "ready": error.is_none(),
+            "error": error,
+        }));
+    }
+    output.line(format!("Feature: {}", features.join(", ")));
+
+    Ok(output.data(json!({
+        "config": config_path.display().to_string(),
+        "exists": config_path.exists(),
+        "profiles": profiles,
+        "features": features,
+    })))
+}
+
+/// Funzione helper per gestire il comando Run e avviare il CoreSystem dal profilo indicato
+fn handle_run(
+    config_path: &Path,
+    profile: &str,
+    overrides: &RunOverrides,
+) -> Result<CommandOutput, CliError> {
+    let project_config = ProjectConfig::load(config_path).map_err(CliError::config)?;
+    let profile_config = project_config.profile(profile)
+        .map_err(CliError::config)?
+        .merge(&overrides.profile());
+    let resolved = profile_config.resolve().map_err(CliError::config)?;
+
+    info!("Avvio del profilo {}:", profile);
+    info!("Core: {:?}", resolved.core);
+    info!("Memory: {:?}", resolved.memory);
+    resolved.database.log_status();
+    let app_type = format!("{:?}", resolved.core.app_type);
+
+    // Inizializza il CoreSystem con la configurazione ottenuta
+    let core_system = CoreSystem::new(
+        resolved.core,
+        resolved.memory,
+        resolved.database,
+    )?;
+
+    // Esegui il core system
+    core_system.run()?;
+
+    let mut output = CommandOutput::new("run").data(json!({
+        "profile": profile,
+        "app_type": app_type,
+    }));
+    output.line(format!("CoreSystem avviato con il profilo {}", profile));
+    Ok(output)
+}
+
+/// Funzione helper per gestire il comando Model e generare i modelli CRUD personalizzati
+#[cfg(feature = "crud")]
+fn handle_model(action: &ModelCommands) -> Result<CommandOutput, CliError> {
+    use solid_arx_lib::crud::models::model_generator::{
+        generate_model,
+        models_path,
+        ModelSpec,
+    };
+
+    match action {
+        ModelCommands::New { name, fields, store, ops } => {
+            info!("Generazione del modello {}:", name);
+            info!("Fields: {:?}", fields);
+            info!("Store: {}", store);
+            info!("Ops: {}", ops);
+
+            let spec = ModelSpec::new(name, fields, store, ops).map_err(CliError::usage)?;
+            let model_file = generate_model(&spec, &models_path()).map_err(CliError::filesystem)?;
+
+            let mut output = CommandOutput::new("model new").data(json!({
+                "model": spec.name,
+                "file": model_file.display().to_string(),
+            }));
+            output.line(format!("Modello {} generato in {}", spec.name, model_file.display()));
+            Ok(output)
+        }
+    }
+}
+
+/// Senza la feature `crud` i modelli non vengono compilati, quindi il generatore non è disponibile
+#[cfg(not(feature = "crud"))]
+fn handle_model(_action: &ModelCommands) -> Result<CommandOutput, CliError> {
+    Err(CliError::config("Il comando Model richiede la feature `crud`"))
+}
+
+/// Restituisce il database di `arx db` e `arx data`, letto dal profilo di `arx.toml` se `-d` e `-u` non sono specificati
+#[cfg(feature = "crud")]
+fn connection_database(config_path: &Path, connection: &DbConnectionArgs) -> Result<DatabaseType, CliError> {
+    let project_config = ProjectConfig::load(config_path).map_err(CliError::config)?;
+    connection.database(&project_config).map_err(CliError::config)
+}
+
+/// Funzione helper per gestire il comando Db e le migrazioni dello schema
+#[cfg(feature = "crud")]
+fn handle_db(
+    config_path: &Path,
+    connection: &DbConnectionArgs,
+    action: &DbCommands,
+    command: &'static str,
+) -> Result<CommandOutput, CliError> {
+    use solid_arx_lib::crud::models::migrations::MigrationManager;
+
+    let database = connection_database(config_path, connection)?;
+    let manager = MigrationManager::new(database.clone())?;
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut output = CommandOutput::new(command);
+
+    match action {
+        DbCommands::Plan => {
+            let plan = runtime.block_on(manager.plan())?;
+            for warning in &plan.warnings {
+                output.line(format!("ATTENZIONE: {}", warning));
+            }
+            if plan.is_empty() {
+                output.line("Nessuna modifica dello schema in attesa");
+            }
+            for statement in &plan.up {
+                output.line(format!("{};", statement));
+            }
+            output = output.data(json!({
+                "up": plan.up,
+                "down": plan.down,
+                "warnings": plan.warnings,
+            }));
+        }
+        DbCommands::Status => {
+            let status = runtime.block_on(manager.status())?;
+            output.line(format!("Migrazioni applicate: {}", status.applied.len()));
+            for migration in status.applied.iter().rev() {
+                output.line(format!("  [{}] {} ({})", migration.version, migration.name, migration.applied_at));
+            }
+            output.line(format!("Istruzioni in attesa: {}", status.pending.up.len()));
+            let applied: Vec<_> = status.applied.iter()
+                .map(|m| json!({ "version": m.version, "name": m.name, "applied_at": m.applied_at }))
+                .collect();
+            output = output.data(json!({
+                "applied": applied,
+                "pending": status.pending.up,
+                "warnings": status.pending.warnings,
+            }));
+        }
+        DbCommands::Migrate { name } => {
+            match runtime.block_on(manager.migrate(name.clone()))? {
+                Some(migration) => {
+                    output.line(format!("Migrazione {} ({}) applicata", migration.version, migration.name));
+                    output = output.data(json!({
+                        "applied": { "version": migration.version, "name": migration.name },
+                    }));
                 }
                 None => {
-                    info!("Database Type: None");
-                    return Ok(DatabaseType::None)
+                    output.line("Schema del database già allineato con i modelli");
+                    output = output.data(json!({ "applied": null }));
                 }
             }
         }
-        _ => {
-            return Err("Comando Database non trovato".into());
+        DbCommands::Rollback { steps } => {
+            let mut reverted = Vec::new();
+            for migration in runtime.block_on(manager.rollback(*steps))? {
+                output.line(format!("Migrazione {} ({}) annullata", migration.version, migration.name));
+                reverted.push(json!({ "version": migration.version, "name": migration.name }));
+            }
+            output = output.data(json!({ "reverted": reverted }));
+        }
+        DbCommands::Introspect { tables, sample_size, dry_run } => {
+            use solid_arx_lib::crud::models::table_introspector::{introspect, write_models};
+            use solid_arx_lib::crud::models::model_generator::{models_path, render_model};
+
+            let specs = runtime.block_on(introspect(database.clone(), tables, *sample_size))?;
+            let models: Vec<_> = specs.iter().map(|spec| spec.name.clone()).collect();
+            if *dry_run {
+                for spec in &specs {
+                    output.line(format!("// {}.rs\n{}", spec.module_name(), render_model(spec)));
+                }
+                output = output.data(json!({ "models": models, "written": [] }));
+            } else {
+                let written = write_models(&specs, &models_path()).map_err(CliError::filesystem)?;
+                for path in &written {
+                    output.line(format!("Modello generato in {}", path));
+                }
+                output = output.data(json!({ "models": models, "written": written }));
+            }
         }
     }
+
+    Ok(output)
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Inizializza il sistema di logging
-    setup_logging().expect("Errore nell'inizializzazione del sistema di logging");
+/// Senza la feature `crud` i modelli non vengono compilati, quindi non è possibile ricavare lo schema
+#[cfg(not(feature = "crud"))]
+fn handle_db(
+    _config_path: &Path,
+    _connection: &DbConnectionArgs,
+    _action: &DbCommands,
+    _command: &'static str,
+) -> Result<CommandOutput, CliError> {
+    Err(CliError::config("Il comando Db richiede la feature `crud`"))
+}
 
-    // Parsing degli argomenti passati dal CLI
-    let cli_args = parse_arguments().expect("Errore nel parsing degli argomenti CLI");
+/// Funzione helper per gestire il comando Data ed esportare o importare i record di un modello
+#[cfg(feature = "crud")]
+fn handle_data(
+    config_path: &Path,
+    connection: &DbConnectionArgs,
+    action: &DataCommands,
+    command: &'static str,
+) -> Result<CommandOutput, CliError> {
+    use std::fs::File;
+    use std::io::{BufReader, BufWriter, Write};
+    use solid_arx_lib::crud::batch::BatchConfig;
+    use solid_arx_lib::crud::crud_ops::AllocType;
+    use solid_arx_lib::crud::data_transfer::{self, DataFormat};
+    use solid_arx_lib::crud::database_store;
 
-    // Gestione dei comandi
-    if let Commands::Help { message } = cli_args.command {
-        println!("{}", message);
-        return Ok(()); // Terminazione immediata per Help
+    let (model, format) = match action {
+        DataCommands::Export { model, format, .. } | DataCommands::Import { model, format, .. } => (model, format),
+    };
+    let transfer = data_transfer::model(model)?;
+    let format: DataFormat = format.parse().map_err(CliError::usage)?;
+    // I record in memoria appartengono al processo dell'applicazione, non a quello del CLI
+    if transfer.store == AllocType::InMemory {
+        return Err(CliError::usage(format!(
+            "Il modello {} è in memoria: esportarlo o importarlo dall'applicazione con `data_transfer`",
+            transfer.table
+        )));
     }
+    database_store::connect(connection_database(config_path, connection)?).map_err(CliError::database)?;
+    let mut output = CommandOutput::new(command);
 
-    // Ottenere le configurazioni (Init, Database) con valori di default
-    let (core_config, memory_config) = handle_init(&cli_args)?;
-    let database_config = match handle_database(&cli_args)?{
-        Ok(database_config) => database_config,
-        Err(e) => {
-            Ok(DatabaseType::None)
+    match action {
+        DataCommands::Export { file, .. } => {
+            let mut writer = BufWriter::new(File::create(file)?);
+            let exported = transfer.export(format, &mut writer)?;
+            writer.flush()?;
+            output.line(format!("{} record di {} esportati in {}", exported, transfer.table, file));
+            output = output.data(json!({ "model": transfer.table, "file": file, "exported": exported }));
+        }
+        DataCommands::Import { file, batch_size, .. } => {
+            let mut reader = BufReader::new(File::open(file)?);
+            let report = transfer.import(format, &mut reader, &BatchConfig::new().batch_size(*batch_size))?;
+            let failures: Vec<_> = report.failures.iter()
+                .map(|failure| json!({ "record": failure.index + 1, "error": failure.error.to_string() }))
+                .collect();
+            output.line(format!(
+                "{} record di {} importati da {} su {} ({} blocchi)",
+                report.inserted, transfer.table, file, report.total(), report.batches
+            ));
+            if !report.is_complete() {
+                // I record già inseriti restano nel database, l'errore riporta quelli da correggere
+                let lines: Vec<String> = report.failures.iter()
+                    .map(|failure| format!("  record {}: {}", failure.index + 1, failure.error))
+                    .collect();
+                return Err(CliError::database(format!(
+                    "{}\n{} record non importati:\n{}",
+                    output.lines.join("\n"),
+                    failures.len(),
+                    lines.join("\n")
+                )).details(json!({ "inserted": report.inserted, "failures": failures })));
+            }
+            output = output.data(json!({
+                "model": transfer.table,
+                "file": file,
+                "inserted": report.inserted,
+                "batches": report.batches,
+            }));
         }
+    }
+
+    Ok(output)
+}
+
+/// Senza la feature `crud` i modelli non vengono compilati, quindi non ci sono record da trasferire
+#[cfg(not(feature = "crud"))]
+fn handle_data(
+    _config_path: &Path,
+    _connection: &DbConnectionArgs,
+    _action: &DataCommands,
+    _command: &'static str,
+) -> Result<CommandOutput, CliError> {
+    Err(CliError::config("Il comando Data richiede la feature `crud`"))
+}
+
+/// Funzione helper per gestire il comando Doctor e riportare l'esito dei controlli
+///
+/// Il tipo di applicazione e il database vengono letti dal profilo, se esiste in `arx.toml`,
+/// con gli override passati dal CLI.
+fn handle_doctor(
+    config_path: &Path,
+    profile: &str,
+    app_type: &Option<ApplicationType>,
+    database_type: &Option<DatabaseType>,
+    database_url: &Option<String>,
+) -> Result<CommandOutput, CliError> {
+    use solid_arx_lib::monitoring::doctor::run_checks;
+
+    let project_config = ProjectConfig::load(config_path).map_err(CliError::config)?;
+    let overrides = RunOverrides {
+        app_type: app_type.clone(),
+        database_type: database_type.clone(),
+        database_url: database_url.clone(),
+        ..Default::default()
     };
+    let profile_config = project_config.profiles.get(profile)
+        .cloned()
+        .unwrap_or_default()
+        .merge(&overrides.profile());
+    let app_type = profile_config.app_type().map_err(CliError::config)?;
+    let database = profile_config.database_type().map_err(CliError::config)?;
 
-    // Inizializza il CoreSystem con la configurazione ottenuta
-    let core_system = CoreSystem::new(
-        core_config, 
-        memory_config, 
-        database_config,
-        ).expect("Errore nell'inizializzazione del Core System");
+    let runtime = tokio::runtime::Runtime::new()?;
+    let report = runtime.block_on(run_checks(app_type, database));
 
-    // Esegui il core system
-    core_system.run()?;
+    let mut output = CommandOutput::new("doctor");
+    let mut checks = Vec::new();
+    for check in &report.checks {
+        output.line(format!("[{}] {}: {}", check.status, check.name, check.message));
+        if let Some(hint) = &check.hint {
+            output.line(format!("       -> {}", hint));
+        }
+        checks.push(json!({
+            "name": check.name,
+            "status": check.status.to_string().to_lowercase(),
+            "message": check.message,
+            "hint": check.hint,
+        }));
+    }
 
-    Ok(())
+    if report.has_failures() {
+        // In modalità testo il messaggio di errore riporta l'esito di tutti i controlli
+        return Err(CliError::check(format!(
+            "Uno o più controlli di arx doctor sono falliti\n{}",
+            output.lines.join("\n")
+        )).details(json!({ "checks": checks })));
+    }
+
+    Ok(output.data(json!({ "checks": checks })))
 }
 
+fn main() -> ExitCode {
+    // Parsing degli argomenti passati dal CLI, gli errori di clap terminano con codice 2
+    // e con `--output json` vengono emessi come documento JSON della classe `usage`
+    let cli_args = match parse_arguments() {
+        Ok(cli_args) => cli_args,
+        Err(e) if !e.use_stderr() => e.exit(),
+        Err(e) => match requested_output(std::env::args_os()) {
+            (OutputFormat::Json, command) => {
+                let rendered = e.render().to_string();
+                let message = rendered.lines().next().unwrap_or_default().trim_start_matches("error: ");
+                let error = CliError::usage(message).details(json!({
+                    "kind": format!("{:?}", e.kind()),
+                    "usage": rendered,
+                }));
+                return emit(OutputFormat::Json, &command, Err(error));
+            }
+            (OutputFormat::Text, _) => e.exit(),
+        },
+    };
+
+    // Inizializza il sistema di logging: con `--output json` stdout contiene solo il documento JSON
+    let logging = match cli_args.output {
+        OutputFormat::Text => setup_logging(),
+        OutputFormat::Json => setup_logging_to_stderr(),
+    };
+    if let Err(e) = logging {
+        eprintln!("Errore nell'inizializzazione del sistema di logging: {}", e);
+    }
+
+    let config_path = Path::new(&cli_args.config);
+    let command = cli_args.command.name();
+
+    // Gestione dei comandi: solo `arx run` avvia il CoreSystem
+    let result = match &cli_args.command {
+        Commands::Init { app_type, interactive, memory_scale, max_threads, buffer_size, pool_size, profile } => {
+            handle_init(
+                config_path,
+                profile,
+                app_type,
+                *interactive,
+                *memory_scale,
+                *max_threads,
+                *buffer_size,
+                *pool_size,
+            )
+        }
+        Commands::Database {
+            database_type,
+            database_url,
+            max_connections,
+            retry_attempts,
+            max_idle_time,
+            connection_timeout,
+            profile,
+        } => handle_database(
+            config_path,
+            profile,
+            database_type,
+            database_url,
+            *max_connections,
+            *retry_attempts,
+            *max_idle_time,
+            *connection_timeout,
+        ),
+        Commands::Config { profile } => handle_config(config_path, profile),
+        Commands::Status => handle_status(config_path),
+        Commands::Run { profile, overrides } => handle_run(config_path, profile, overrides),
+        Commands::Model { action } => handle_model(action),
+        Commands::Db { connection, action } => handle_db(config_path, connection, action, command),
+        Commands::Data { connection, action } => handle_data(config_path, connection, action, command),
+        Commands::Doctor { profile, app_type, database_type, database_url } => {
+            handle_doctor(config_path, profile, app_type, database_type, database_url)
+        }
+    };
+
+    emit(cli_args.output, command, result)
+}
@@ -0,0 +1,308 @@
+//! Modulo per la diagnostica dell'ambiente di sviluppo e di esecuzione.
+//!
+//! Utilizzato dal comando `arx doctor`, sostituisce l'installazione di Python che prima avveniva
+//! in `build.rs` durante la compilazione. Il doctor non modifica il sistema: esegue una serie di
+//! controlli e per ognuno riporta l'esito (`Pass`, `Warn`, `Fail`) con un suggerimento di risoluzione.
+//!
+//! ### Controlli eseguiti:
+//! - Interprete Python e relativa versione (necessario per i wrapper di auth, api, ml e task automation).
+//! - Scrivibilità della cartella dei log utilizzata da `logger.rs`.
+//! - Raggiungibilità del database del profilo di `arx.toml` (o passato dal CLI).
+//! - Feature compilate rispetto a quelle richieste dal tipo di applicazione, come in `CoreSystem::run`.
+//! - Presenza delle cartelle dei modelli utilizzate da `table_scraper.rs` (con la feature `crud`).
+//!
+//! I percorsi verificati sono risolti dalla root del progetto e non dalla cartella corrente.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use log::{info, warn, error};
+
+use crate::config::global_config::ApplicationType;
+use crate::config::network_config::DatabaseType;
+use crate::monitoring::logger::log_dir;
+use crate::network::connection_management::{ConnectionManager, DatabaseConnection};
+
+pub use crate::config::global_config::compiled_features;
+
+/// Versione minima di Python supportata dai wrapper del framework.
+const MIN_PYTHON_VERSION: (u32, u32) = (3, 8);
+/// Cartella dei modelli di default, obbligatoria, relativa alla cartella `models`.
+#[cfg(feature = "crud")]
+const DEFAULT_MODELS_DIR: &str = "default";
+/// Cartella dei modelli personalizzati, creata da `arx model new`, relativa alla cartella `models`.
+#[cfg(feature = "crud")]
+const DEV_MODELS_DIR: &str = "dev";
+
+/// Esito di un controllo del doctor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl std::fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckStatus::Pass => write!(f, "PASS"),
+            CheckStatus::Warn => write!(f, "WARN"),
+            CheckStatus::Fail => write!(f, "FAIL"),
+        }
+    }
+}
+
+/// Risultato di un singolo controllo.
+///
+/// # Campi
+/// - `name`: Nome del controllo.
+/// - `status`: Esito del controllo.
+/// - `message`: Descrizione dell'esito.
+/// - `hint`: Suggerimento di risoluzione, presente per `Warn` e `Fail`.
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, message: String) -> Self {
+        CheckResult { name, status: CheckStatus::Pass, message, hint: None }
+    }
+
+    fn warn(name: &'static str, message: String, hint: String) -> Self {
+        CheckResult { name, status: CheckStatus::Warn, message, hint: Some(hint) }
+    }
+
+    fn fail(name: &'static str, message: String, hint: String) -> Self {
+        CheckResult { name, status: CheckStatus::Fail, message, hint: Some(hint) }
+    }
+}
+
+/// Report completo restituito da `run_checks`.
+#[derive(Debug, Clone, Default)]
+pub struct DoctorReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    /// Indica se almeno un controllo è fallito.
+    pub fn has_failures(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Fail)
+    }
+}
+
+/// Esegue tutti i controlli del doctor.
+///
+/// # Parametri
+/// - `app_type`: Tipo di applicazione, se noto, per verificare le feature richieste.
+/// - `database`: Configurazione del database da verificare, `DatabaseType::None` se non configurato.
+///
+/// # Ritorna
+/// Un `DoctorReport` con l'esito di ogni controllo.
+pub async fn run_checks(app_type: Option<ApplicationType>, database: DatabaseType) -> DoctorReport {
+    #[allow(unused_mut)]
+    let mut checks = vec![
+        check_python(),
+        check_log_dir(&log_dir()),
+        check_database(database).await,
+        check_features(app_type),
+    ];
+    #[cfg(feature = "crud")]
+    checks.push(check_model_dirs(&crate::crud::models::model_generator::models_path()));
+
+    for check in &checks {
+        match check.status {
+            CheckStatus::Pass => info!("[{}] {}: {}", check.status, check.name, check.message),
+            CheckStatus::Warn => warn!("[{}] {}: {}", check.status, check.name, check.message),
+            CheckStatus::Fail => error!("[{}] {}: {}", check.status, check.name, check.message),
+        }
+    }
+
+    DoctorReport { checks }
+}
+
+/// Suggerimento di installazione di Python per il sistema operativo corrente.
+fn python_install_hint() -> String {
+    if cfg!(target_os = "linux") {
+        "Installare Python con: sudo apt-get install -y python3".to_string()
+    } else if cfg!(target_os = "macos") {
+        "Installare Python con: brew install python".to_string()
+    } else if cfg!(target_os = "windows") {
+        "Installare Python con: choco install -y python".to_string()
+    } else {
+        "Installare Python dal sito ufficiale https://www.python.org".to_string()
+    }
+}
+
+/// Estrae la versione (major, minor) dall'output di `python --version` (es. `Python 3.11.4`).
+pub fn parse_python_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.trim().strip_prefix("Python ")?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Verifica la presenza dell'interprete Python e la sua versione.
+pub fn check_python() -> CheckResult {
+    const NAME: &str = "Python";
+
+    for interpreter in ["python3", "python"] {
+        let Ok(output) = Command::new(interpreter).arg("--version").output() else {
+            continue;
+        };
+        // Le versioni precedenti alla 3.4 scrivono la versione su stderr
+        let text = if output.stdout.is_empty() {
+            String::from_utf8_lossy(&output.stderr).to_string()
+        } else {
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+
+        return match parse_python_version(&text) {
+            Some(version) if version >= MIN_PYTHON_VERSION => {
+                CheckResult::pass(NAME, format!("{} trovato ({})", interpreter, text.trim()))
+            }
+            Some(_) => CheckResult::warn(
+                NAME,
+                format!("{} trovato ma la versione è obsoleta ({})", interpreter, text.trim()),
+                format!(
+                    "Aggiornare Python ad una versione >= {}.{}. {}",
+                    MIN_PYTHON_VERSION.0, MIN_PYTHON_VERSION.1, python_install_hint()
+                ),
+            ),
+            None => CheckResult::warn(
+                NAME,
+                format!("Impossibile determinare la versione di {}: {}", interpreter, text.trim()),
+                "Verificare che l'interprete nel PATH sia Python 3".to_string(),
+            ),
+        };
+    }
+
+    CheckResult::fail(NAME, "Interprete Python non trovato".to_string(), python_install_hint())
+}
+
+/// Verifica che la cartella dei log esista e sia scrivibile.
+///
+/// # Parametri
+/// - `path`: Cartella in cui `logger.rs` scrive il file di log (di default `logger::log_dir()`).
+pub fn check_log_dir(path: &Path) -> CheckResult {
+    const NAME: &str = "Log directory";
+
+    let display = path.display();
+    if !path.is_dir() {
+        return CheckResult::fail(
+            NAME,
+            format!("La cartella {} non esiste", display),
+            format!("Creare la cartella con: mkdir -p {}", display),
+        );
+    }
+
+    let probe = path.join(".arx_doctor_probe");
+    match fs::write(&probe, b"probe") {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            CheckResult::pass(NAME, format!("{} scrivibile", display))
+        }
+        Err(e) => CheckResult::fail(
+            NAME,
+            format!("La cartella {} non è scrivibile: {}", display, e),
+            format!("Verificare i permessi della cartella {}", display),
+        ),
+    }
+}
+
+/// Verifica la raggiungibilità del database configurato.
+///
+/// # Parametri
+/// - `database`: Configurazione del database.
+///
+/// # Note
+/// Viene effettuato un solo tentativo di connessione, senza il meccanismo di retry
+/// di `initialize_connection`, per non bloccare la diagnostica.
+pub async fn check_database(database: DatabaseType) -> CheckResult {
+    const NAME: &str = "Database";
+
+    if let DatabaseType::None = database {
+        return CheckResult::warn(
+            NAME,
+            "Nessun database configurato".to_string(),
+            "Configurare il database con il comando `arx database` se l'applicazione ne fa uso".to_string(),
+        );
+    }
+
+    let connection_manager = ConnectionManager::new(database);
+    match connection_manager.connect().await {
+        Ok(_) => CheckResult::pass(NAME, "Connessione al database riuscita".to_string()),
+        Err(e) => CheckResult::fail(
+            NAME,
+            format!("Database non raggiungibile: {}", e),
+            "Verificare URL, credenziali e che il server del database sia avviato".to_string(),
+        ),
+    }
+}
+
+/// Verifica che le feature compilate coprano quelle richieste dal tipo di applicazione.
+///
+/// # Parametri
+/// - `app_type`: Tipo di applicazione, se noto.
+pub fn check_features(app_type: Option<ApplicationType>) -> CheckResult {
+    const NAME: &str = "Features";
+
+    let compiled = compiled_features();
+    let Some(app_type) = app_type else {
+        return CheckResult::warn(
+            NAME,
+            format!("Feature compilate: [{}]", compiled.join(", ")),
+            "Specificare --app-type per verificare le feature richieste dall'applicazione".to_string(),
+        );
+    };
+
+    let missing = app_type.missing_features();
+
+    if missing.is_empty() {
+        CheckResult::pass(
+            NAME,
+            format!("Tutte le feature richieste da {:?} sono compilate: [{}]", app_type, compiled.join(", ")),
+        )
+    } else {
+        CheckResult::fail(
+            NAME,
+            format!("Feature mancanti per {:?}: [{}]", app_type, missing.join(", ")),
+            format!(
+                "Ricompilare con: cargo build --features \"{}\"",
+                app_type.required_features().join(",")
+            ),
+        )
+    }
+}
+
+/// Verifica la presenza delle cartelle dei modelli.
+///
+/// # Parametri
+/// - `models_path`: Cartella `models` del progetto (di default `model_generator::models_path()`).
+#[cfg(feature = "crud")]
+pub fn check_model_dirs(models_path: &Path) -> CheckResult {
+    const NAME: &str = "Model directories";
+
+    let default_path = models_path.join(DEFAULT_MODELS_DIR);
+    let dev_path = models_path.join(DEV_MODELS_DIR);
+    if !default_path.is_dir() {
+        return CheckResult::fail(
+            NAME,
+            format!("La cartella {} non esiste", default_path.display()),
+            "Ripristinare i modelli di default del framework".to_string(),
+        );
+    }
+    if !dev_path.is_dir() {
+        return CheckResult::warn(
+            NAME,
+            format!("La cartella {} non esiste", dev_path.display()),
+            format!("Verrà creata da `arx model new`, oppure crearla con: mkdir -p {}", dev_path.display()),
+        );
+    }
+
+    CheckResult::pass(NAME, format!("{} e {} presenti", default_path.display(), dev_path.display()))
+}
@@ -1,10 +1,22 @@
 use fern::Dispatch;
 use chrono::Local;
 use log::{info, warn, error};
+use std::path::PathBuf;
 use std::sync::Once;
 
+use crate::config::project_config::project_root;
+
 // Flag per assicurarsi che la configurazione del logger venga eseguita solo una volta
 static LOGGER_SETUP: Once = Once::new();
+
+/// Cartella in cui viene scritto il file di log, relativa alla root del progetto
+pub const LOG_DIR: &str = "monitoring/logs";
+
+/// Restituisce la cartella dei log nella root del progetto, verificata anche da `arx doctor`.
+pub fn log_dir() -> PathBuf {
+    project_root().join(LOG_DIR)
+}
+
 /// Configura il sistema di logging usando `fern`.
 ///
 /// I log vengono scritti sia sulla console che su file.
@@ -15,25 +27,44 @@ static LOGGER_SETUP: Once = Once::new();
 /// * `Ok(())` se l'inizializzazione ha successo.
 /// * `Err(fern::InitError)` se ci sono problemi nell'inizializzazione del logging.
 pub fn setup_logging() -> Result<(), fern::InitError> {
+    configure_logging(std::io::stdout().into())
+}
+
+/// Configura il sistema di logging scrivendo i messaggi della console su stderr.
+///
+/// Utilizzato con `arx --output json`, in modo che stdout contenga solo il documento JSON.
+pub fn setup_logging_to_stderr() -> Result<(), fern::InitError> {
+    configure_logging(std::io::stderr().into())
+}
+
+/// Applica la configurazione di `fern` con l'output di console indicato.
+fn configure_logging(console: fern::Output) -> Result<(), fern::InitError> {
+    let mut result = Ok(());
+
     // Configura il logger solo la prima volta che viene chiamato
     LOGGER_SETUP.call_once(|| {
-        Dispatch::new()
-            .format(|out, message, record| {
-                out.finish(format_args!(
-                    "[{}][{}][{}] {}",
-                    Local::now().format("%Y-%m-%d %H:%M:%S"),
-                    record.target(),
-                    record.level(),
-                    message
-                ))
-            })
-            .level(log::LevelFilter::Info)  // Livello di log globale, può essere configurato
-            .chain(std::io::stdout())       // Scrittura del log nella console
-            .chain(fern::log_file("monitoring/logs/arx_framework.log"))  // Scrittura su file
-            .apply();  // Applica la configurazione
+        result = fern::log_file(log_dir().join("arx_framework.log"))
+            .map_err(fern::InitError::from)
+            .and_then(|log_file| {
+                Dispatch::new()
+                    .format(|out, message, record| {
+                        out.finish(format_args!(
+                            "[{}][{}][{}] {}",
+                            Local::now().format("%Y-%m-%d %H:%M:%S"),
+                            record.target(),
+                            record.level(),
+                            message
+                        ))
+                    })
+                    .level(log::LevelFilter::Info)  // Livello di log globale, può essere configurato
+                    .chain(console)                 // Scrittura del log nella console
+                    .chain(log_file)                // Scrittura su file
+                    .apply()                        // Applica la configurazione
+                    .map_err(fern::InitError::from)
+            });
     });
     
-    Ok(())
+    result
 }
 
 /// Funzione per monitorare lo stato dei moduli.
@@ -0,0 +1,245 @@
+//! Modulo per l'output dei comandi del CLI.
+//!
+//! Ogni comando di `arx` restituisce un `CommandOutput` oppure un `CliError`, che vengono emessi
+//! come testo (default) o come documento JSON con `arx --output json`, così da poter pilotare
+//! `arx` dagli script di deploy. Anche gli errori di parsing di clap rispettano `--output json`
+//! (vedi `cli::requested_output`) ed emettono un errore `usage` con il tipo di errore di clap in
+//! `details.kind`; `--help` e `--version` restano testuali.
+//!
+//! ### Formato del documento JSON:
+//! ```json
+//! { "command": "db status", "status": "ok", "data": { ... } }
+//! { "command": "run", "status": "error", "error": { "class": "config", "code": 3, "message": "..." } }
+//! ```
+//!
+//! ### Codici di uscita:
+//! | Classe       | Codice | Descrizione                                         |
+//! |--------------|--------|-----------------------------------------------------|
+//! | `runtime`    | 1      | Errore generico durante l'esecuzione del CoreSystem |
+//! | `usage`      | 2      | Argomenti non validi (anche per gli errori di clap) |
+//! | `config`     | 3      | File `arx.toml`, profilo o configurazione non validi|
+//! | `database`   | 4      | Connessione o migrazione del database fallita       |
+//! | `filesystem` | 5      | Lettura o scrittura di file fallita                 |
+//! | `check`      | 6      | Uno o più controlli di `arx doctor` falliti         |
+
+use std::process::ExitCode;
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::core::system_core::CoreError;
+
+/// Formato di output del CLI, selezionato con `--output`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Classe di errore del CLI, ognuna associata ad un codice di uscita stabile.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorClass {
+    Runtime,
+    Usage,
+    Config,
+    Database,
+    Filesystem,
+    Check,
+}
+
+impl ErrorClass {
+    /// Restituisce il codice di uscita del processo per la classe di errore.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            ErrorClass::Runtime => 1,
+            ErrorClass::Usage => 2,
+            ErrorClass::Config => 3,
+            ErrorClass::Database => 4,
+            ErrorClass::Filesystem => 5,
+            ErrorClass::Check => 6,
+        }
+    }
+}
+
+/// Errore restituito da un comando del CLI.
+///
+/// # Campi
+/// - `class`: Classe dell'errore, determina il codice di uscita.
+/// - `message`: Descrizione dell'errore.
+/// - `details`: Dati strutturati aggiuntivi, emessi con `--output json` (es. i controlli di `arx doctor`).
+#[derive(Debug, Clone)]
+pub struct CliError {
+    pub class: ErrorClass,
+    pub message: String,
+    pub details: Option<Value>,
+}
+
+impl CliError {
+    pub fn new(class: ErrorClass, message: impl ToString) -> Self {
+        CliError { class, message: message.to_string(), details: None }
+    }
+
+    /// Aggiunge i dati strutturati dell'errore.
+    pub fn details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn runtime(message: impl ToString) -> Self {
+        CliError::new(ErrorClass::Runtime, message)
+    }
+
+    pub fn usage(message: impl ToString) -> Self {
+        CliError::new(ErrorClass::Usage, message)
+    }
+
+    pub fn config(message: impl ToString) -> Self {
+        CliError::new(ErrorClass::Config, message)
+    }
+
+    pub fn database(message: impl ToString) -> Self {
+        CliError::new(ErrorClass::Database, message)
+    }
+
+    pub fn filesystem(message: impl ToString) -> Self {
+        CliError::new(ErrorClass::Filesystem, message)
+    }
+
+    pub fn check(message: impl ToString) -> Self {
+        CliError::new(ErrorClass::Check, message)
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<CoreError> for CliError {
+    fn from(error: CoreError) -> Self {
+        match error {
+            CoreError::ConfigurationError(_) => CliError::config(error),
+            _ => CliError::runtime(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(error: std::io::Error) -> Self {
+        CliError::filesystem(error)
+    }
+}
+
+#[cfg(feature = "crud")]
+impl From<crate::crud::models::migrations::MigrationError> for CliError {
+    fn from(error: crate::crud::models::migrations::MigrationError) -> Self {
+        use crate::crud::models::migrations::MigrationError;
+
+        match error {
+            MigrationError::ScrapeError(_) => CliError::filesystem(error),
+            MigrationError::InvalidRollback(_) => CliError::usage(error),
+            _ => CliError::database(error),
+        }
+    }
+}
+
+#[cfg(feature = "crud")]
+impl From<crate::crud::data_transfer::TransferError> for CliError {
+    fn from(error: crate::crud::data_transfer::TransferError) -> Self {
+        use crate::crud::data_transfer::TransferError;
+
+        match error {
+            TransferError::Io(_) => CliError::filesystem(error),
+            TransferError::Crud(_) => CliError::database(error),
+            _ => CliError::usage(error),
+        }
+    }
+}
+
+/// Risultato di un comando del CLI eseguito con successo.
+///
+/// # Campi
+/// - `command`: Nome del comando (es. `db status`).
+/// - `data`: Dati strutturati del risultato, emessi con `--output json`.
+/// - `lines`: Righe di testo emesse con `--output text`.
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub command: &'static str,
+    pub data: Value,
+    pub lines: Vec<String>,
+}
+
+impl CommandOutput {
+    /// Crea un nuovo output per il comando indicato, senza dati.
+    pub fn new(command: &'static str) -> Self {
+        CommandOutput { command, data: json!({}), lines: Vec::new() }
+    }
+
+    /// Imposta i dati strutturati del risultato.
+    pub fn data(mut self, data: Value) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Aggiunge una riga all'output testuale.
+    pub fn line(&mut self, line: impl ToString) {
+        self.lines.push(line.to_string());
+    }
+}
+
+/// Costruisce il documento JSON di un comando riuscito.
+pub fn success_document(output: &CommandOutput) -> Value {
+    json!({
+        "command": output.command,
+        "status": "ok",
+        "data": output.data,
+    })
+}
+
+/// Costruisce il documento JSON di un comando fallito.
+pub fn error_document(command: &str, error: &CliError) -> Value {
+    json!({
+        "command": command,
+        "status": "error",
+        "error": {
+            "class": error.class,
+            "code": error.class.exit_code(),
+            "message": error.message,
+            "details": error.details,
+        },
+    })
+}
+
+/// Emette il risultato di un comando nel formato richiesto e restituisce il codice di uscita.
+///
+/// # Parametri
+/// - `format`: Formato di output selezionato con `--output`.
+/// - `command`: Nome del comando, utilizzato nel documento JSON in caso di errore.
+/// - `result`: Risultato del comando.
+pub fn emit(format: OutputFormat, command: &str, result: Result<CommandOutput, CliError>) -> ExitCode {
+    match (format, result) {
+        (OutputFormat::Text, Ok(output)) => {
+            for line in &output.lines {
+                println!("{}", line);
+            }
+            ExitCode::SUCCESS
+        }
+        (OutputFormat::Text, Err(error)) => {
+            eprintln!("Errore: {}", error);
+            ExitCode::from(error.class.exit_code())
+        }
+        (OutputFormat::Json, Ok(output)) => {
+            println!("{}", success_document(&output));
+            ExitCode::SUCCESS
+        }
+        (OutputFormat::Json, Err(error)) => {
+            println!("{}", error_document(command, &error));
+            ExitCode::from(error.class.exit_code())
+        }
+    }
+}
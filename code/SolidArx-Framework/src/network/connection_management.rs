@@ -1,5 +1,5 @@
-/// Il modulo `ConnectionManager` gestisce la connessione a vari database in base alla configurazione specificata.
-/// Supporta PostgreSQL, SQLite e MongoDB, con funzionalità di retry per tentativi di connessione falliti.
+//! Il modulo `ConnectionManager` gestisce la connessione a vari database in base alla configurazione specificata.
+//! Supporta PostgreSQL, SQLite e MongoDB, con funzionalità di retry per tentativi di connessione falliti.
 
 use mongodb::{Client, options::ClientOptions}; 
 use diesel::{
@@ -17,7 +17,7 @@ use crate::config::network_config::{DatabaseType};
 
 /// Enum per rappresentare errori di connessione al database
 #[derive(Debug)]
-enum ConnectionErrors {
+pub enum ConnectionErrors {
     Postgres(String),
     SQLite(String),
     Mongo(String),
@@ -1,3 +1,3 @@
 pub mod connection_management;
-pub mod lead_balancer;
+pub mod load_balancer;
 pub mod resource_pool;
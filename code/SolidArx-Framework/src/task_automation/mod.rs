@@ -2,6 +2,6 @@ pub mod task_core;
 
 pub fn initialize() -> Result<(), String> {
     // Logica di inizializzazione per Task Automation
-    println!("Initializing Task Automation module...");
+    log::info!("Initializing Task Automation module...");
     Ok(())
 }
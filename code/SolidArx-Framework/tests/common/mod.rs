@@ -0,0 +1,95 @@
+//! # Supporto condiviso dai test di integrazione
+//!
+//! Crea un database SQLite temporaneo con le tabelle richieste dal test e lo installa come
+//! connessione dei modelli con `AllocType::Database` e `AllocType::Cached`.
+//! Le tabelle utilizzate da più test sono definite una sola volta in questo modulo, con l'ID
+//! `INTEGER PRIMARY KEY AUTOINCREMENT` generato da `arx db migrate` per gli ID assegnati dal database.
+//! In alternativa `TestDatabase::migrated` crea lo schema di tutti i modelli con `MigrationManager::migrate`.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use diesel::{Connection, RunQueryDsl, SqliteConnection};
+
+use solid_arx_lib::config::network_config::{ConnectionConfig, DatabaseType};
+use solid_arx_lib::crud::database_store;
+use solid_arx_lib::crud::models::migrations::MigrationManager;
+
+/// Log di audit, richiesto dai modelli con `#[arx(audit)]`.
+pub const AUDIT_ENTRY: &str = "CREATE TABLE audit_entry (id INTEGER PRIMARY KEY AUTOINCREMENT, model TEXT, record_id TEXT, action TEXT, actor TEXT, timestamp TIMESTAMP, before TEXT NULL, after TEXT NULL, changed_fields JSON)";
+pub const CONFIGURATION: &str = "CREATE TABLE configuration (id INTEGER PRIMARY KEY AUTOINCREMENT, device_id INTEGER, key TEXT, value TEXT, version BIGINT, revocation JSON NULL)";
+pub const DEVICE: &str = "CREATE TABLE device (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT, device_type TEXT, revocation JSON NULL)";
+pub const FIRMWARE_VERSION: &str = "CREATE TABLE firmware_version (id INTEGER PRIMARY KEY AUTOINCREMENT, device_id INTEGER, version TEXT, release_date TEXT, revocation JSON NULL)";
+pub const USER: &str = "CREATE TABLE user (id INTEGER PRIMARY KEY AUTOINCREMENT, username TEXT, email TEXT, password TEXT)";
+/// Chiavi API degli utenti, eliminate a cascata con l'utente (feature "api").
+pub const API_KEY: &str = "CREATE TABLE api_key (id INTEGER PRIMARY KEY AUTOINCREMENT, key TEXT, user_id INTEGER, revocation JSON NULL)";
+pub const ARTICLE: &str = "CREATE TABLE article (id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT, content TEXT, author_id INTEGER, deleted_at TIMESTAMP NULL)";
+pub const COMMENT: &str = "CREATE TABLE comment (id INTEGER PRIMARY KEY AUTOINCREMENT, content TEXT, author_id INTEGER, article_id INTEGER)";
+pub const ARTICLE_TAG: &str = "CREATE TABLE article_tag (id INTEGER PRIMARY KEY AUTOINCREMENT, article_id INTEGER, tag_id INTEGER)";
+pub const ARTICLE_CATEGORY: &str = "CREATE TABLE article_category (id INTEGER PRIMARY KEY AUTOINCREMENT, article_id INTEGER, category_id INTEGER)";
+
+/// Database SQLite temporaneo installato in `database_store`.
+///
+/// Alla fine del test la connessione viene rimossa e il file eliminato.
+pub struct TestDatabase {
+    /// Connessione diretta al file, per preparare o verificare i dati senza passare dai modelli.
+    pub conn: SqliteConnection,
+    path: PathBuf,
+}
+
+impl TestDatabase {
+    /// Crea il file `arx_<name>_<pid>.db` nella cartella temporanea con le tabelle indicate.
+    ///
+    /// # Parametri
+    /// - `name`: Nome del test, distingue i file dei test eseguiti in parallelo.
+    /// - `tables`: Istruzioni `CREATE TABLE` da eseguire prima di installare la connessione.
+    pub fn sqlite(name: &str, tables: &[&str]) -> Self {
+        let path = std::env::temp_dir().join(format!("arx_{}_{}.db", name, std::process::id()));
+        let url = path.display().to_string();
+
+        let mut conn = SqliteConnection::establish(&url).unwrap();
+        for table in tables {
+            diesel::sql_query(*table).execute(&mut conn).unwrap();
+        }
+
+        database_store::connect(sqlite_database(url)).unwrap();
+        TestDatabase { conn, path }
+    }
+
+    /// Crea il file `arx_<name>_<pid>.db` nella cartella temporanea con lo schema di tutti i modelli,
+    /// applicato da `MigrationManager::migrate` come con `arx db migrate`.
+    ///
+    /// # Parametri
+    /// - `name`: Nome del test, distingue i file dei test eseguiti in parallelo.
+    pub fn migrated(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("arx_{}_{}.db", name, std::process::id()));
+        let url = path.display().to_string();
+
+        let manager = MigrationManager::new(sqlite_database(url.clone())).unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let migration = runtime.block_on(manager.migrate(Some(name.to_string()))).unwrap();
+        assert!(migration.is_some(), "Nessuna migrazione applicata al database {}", url);
+
+        let conn = SqliteConnection::establish(&url).unwrap();
+        database_store::connect(sqlite_database(url)).unwrap();
+        TestDatabase { conn, path }
+    }
+}
+
+/// Configurazione SQLite del file indicato, con una sola connessione.
+fn sqlite_database(url: String) -> DatabaseType {
+    DatabaseType::SQLite(ConnectionConfig {
+        database_url: Some(url),
+        max_connections: Some(1),
+        retry_attempts: Some(1),
+        max_idle_time: Some(30),
+        connection_timeout: Some(1),
+    })
+}
+
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        database_store::uninstall();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
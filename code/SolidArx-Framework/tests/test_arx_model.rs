@@ -0,0 +1,121 @@
+/// # Unit Tests per `#[derive(ArxModel)]`
+///
+/// Verifica che le implementazioni generate dalla derive utilizzino lo store in memoria dedicato
+/// al modello, che le operazioni non dichiarate restituiscano `CrudError::OperationNotPermitted`
+/// che i record revocati vengano esclusi dalle letture e dagli aggiornamenti e che la conversione
+/// in `ModelValue` sia reversibile per i campi persistiti.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use solid_arx_lib::crud::crud_ops::{
+        is_permitted,
+        operations,
+        AllocType,
+        ArxModel,
+        Create,
+        CrudError,
+        CrudOperation,
+        Delete,
+        List,
+        Read,
+        Revoke,
+        Search,
+        Update,
+    };
+    use solid_arx_lib::crud::model_value::ModelValue;
+    use solid_arx_lib::crud::models::default::command::model::Command;
+    use solid_arx_lib::crud::models::default::device::model::Device;
+    use solid_arx_lib::crud::models::default::log_event::model::LogEvent;
+    use solid_arx_lib::crud::query::Query;
+    use solid_arx_lib::crud::revocation::revoke_for_user;
+
+    /// Test per verificare le operazioni CRUD in memoria generate dalla derive
+    #[test]
+    fn test_command_in_memory_crud() {
+        let command = Command::new(901, 1, "irrigazione_serra".to_string(), "2024-01-01 00:00:00".to_string(), vec![0u8; 8].into_boxed_slice());
+        assert_eq!(command.store(), AllocType::InMemory);
+        Command::create(command.clone()).unwrap();
+
+        let mut stored = Command::read(901).unwrap();
+        assert_eq!(stored.command_type, "irrigazione_serra");
+
+        // Lo storage è quello del modello anche se il campo `store` del record è stato modificato
+        stored.command_type = "irrigazione_orto".to_string();
+        stored.store = AllocType::Database;
+        Command::update(stored).unwrap();
+        assert_eq!(Command::search(&Query::new().text("ORTO")).unwrap().items.len(), 1);
+
+        Command::delete(901).unwrap();
+        assert_eq!(Command::read(901).unwrap_err(), CrudError::NotFound { model: "command", id: "901".to_string() });
+    }
+
+    /// Test per verificare che le operazioni disabilitate in `ops` vengano rifiutate
+    #[test]
+    fn test_log_event_operation_not_permitted() {
+        let event = LogEvent::new(
+            903,
+            1,
+            "boot".to_string(),
+            "2024-01-01 00:00:00".to_string(),
+            "Avvio del dispositivo".to_string(),
+            vec![0u8; 4].into_boxed_slice(),
+        );
+        LogEvent::create(event.clone()).unwrap();
+
+        let expected = CrudError::OperationNotPermitted { model: "log_event", operation: CrudOperation::Update };
+        assert_eq!(LogEvent::update(event).unwrap_err(), expected);
+        assert!(matches!(LogEvent::delete(903), Err(CrudError::OperationNotPermitted { .. })));
+        assert!(matches!(LogEvent::list(&Query::new()), Err(CrudError::OperationNotPermitted { .. })));
+        assert!(LogEvent::read(903).is_ok(), "Il record non è stato eliminato");
+
+        assert!(!is_permitted::<LogEvent>(CrudOperation::Delete));
+        assert_eq!(
+            operations::<LogEvent>().enabled(),
+            vec![CrudOperation::Create, CrudOperation::Read, CrudOperation::Search]
+        );
+    }
+
+    /// Test per verificare la revoca di un record e la sua esclusione dalle letture
+    #[test]
+    fn test_command_revoke() {
+        Command::create(Command::new(904, 1, "reboot".to_string(), "2024-01-01 00:00:00".to_string(), Box::new([]))).unwrap();
+        assert!(!Command::is_revoked(904).unwrap());
+
+        let revocation = Command::revoke(904, "Comando non autorizzato", "admin").unwrap();
+        assert_eq!(revocation.reason, "Comando non autorizzato");
+        assert_eq!(revocation.actor, "admin");
+        assert!(Command::is_revoked(904).unwrap());
+        assert_eq!(Command::read(904).unwrap_err(), CrudError::Revoked { model: "command", id: "904".to_string() });
+        let update = Command::new(904, 1, "shutdown".to_string(), "2024-01-01 00:00:00".to_string(), Box::new([]));
+        assert_eq!(Command::update(update).unwrap_err(), CrudError::Revoked { model: "command", id: "904".to_string() });
+
+        let query = Query::new().eq("id", 904u32);
+        assert_eq!(Command::list(&query).unwrap().total, 0);
+        let revoked = Command::list(&query.include_revoked()).unwrap();
+        assert_eq!(revoked.items[0].revocation, Some(revocation));
+
+        assert!(matches!(revoke_for_user::<Command>(1, "test", "admin"), Err(CrudError::InvalidQuery(_))));
+    }
+
+    /// Test per verificare la conversione dei campi persistiti in `ModelValue` e ritorno
+    #[test]
+    fn test_device_values_roundtrip() {
+        let device = Device::new(902, "Gateway".to_string(), vec![1u8; 4].into_boxed_slice());
+        assert_eq!(Device::TABLE_NAME, "device");
+        assert_eq!(Device::field_names(), vec!["id", "name", "device_type", "revocation"]);
+
+        let values: HashMap<String, ModelValue> = device
+            .to_values()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        let rebuilt = Device::from_values(&values).unwrap();
+        assert_eq!(rebuilt.id, 902);
+        assert_eq!(rebuilt.name, "Gateway");
+        assert!(rebuilt.memory.is_empty(), "Il buffer di memoria non viene persistito");
+        assert_eq!(rebuilt.ops, Device::declared_operations());
+    }
+}
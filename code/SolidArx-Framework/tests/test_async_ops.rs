@@ -0,0 +1,45 @@
+/// # Integration Tests per `async_ops.rs`
+///
+/// Verifica le operazioni CRUD asincrone su `LogEvent` (in memoria) e `Configuration` (SQLite temporaneo):
+/// creazione, lettura, aggiornamento ed eliminazione attese da un runtime tokio, e l'autore impostato
+/// con `async_ops::with_actor` registrato nel log di audit dal thread di `spawn_blocking`.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use solid_arx_lib::crud::async_ops::{self, AsyncCreate, AsyncDelete, AsyncRead, AsyncSearch, AsyncUpdate};
+    use solid_arx_lib::crud::audit;
+    use solid_arx_lib::crud::crud_ops::CrudError;
+    use solid_arx_lib::crud::models::default::configuration::model::Configuration;
+    use solid_arx_lib::crud::models::default::log_event::model::LogEvent;
+    use solid_arx_lib::crud::query::Query;
+
+    use crate::common::{TestDatabase, AUDIT_ENTRY, CONFIGURATION};
+
+    /// Test per verificare le operazioni asincrone in memoria e su database
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_async_crud() {
+        let event = LogEvent::new(1, 3, "boot".to_string(), "2024-01-01T00:00:00".to_string(), String::new(), Box::new([]));
+        LogEvent::create(event).await.unwrap();
+        assert_eq!(LogEvent::read(1).await.unwrap().event_type, "boot");
+        assert_eq!(LogEvent::search(&Query::new().eq("device_id", 3u32)).await.unwrap().total, 1);
+        assert!(matches!(LogEvent::delete(1).await, Err(CrudError::OperationNotPermitted { .. })));
+
+        let _db = TestDatabase::sqlite("async_ops", &[CONFIGURATION, AUDIT_ENTRY]);
+
+        let config = Configuration::new(1, Some(3), "sampling".to_string(), "10".to_string(), Box::new([]));
+        async_ops::with_actor("api", Configuration::create(config)).await.unwrap();
+        let mut config = Configuration::read(1).await.unwrap();
+        config.value = "30".to_string();
+        Configuration::update(config).await.unwrap();
+        Configuration::delete(1).await.unwrap();
+        assert!(matches!(Configuration::read(1).await, Err(CrudError::NotFound { .. })));
+
+        let actors: Vec<String> =
+            audit::history::<Configuration>(&1).unwrap().into_iter().map(|entry| entry.actor).collect();
+        assert_eq!(actors, vec!["api", audit::DEFAULT_ACTOR, audit::DEFAULT_ACTOR]);
+    }
+}
+
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod common;
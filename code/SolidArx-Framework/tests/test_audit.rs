@@ -0,0 +1,59 @@
+/// # Integration Tests per `audit.rs`
+///
+/// Verifica lo storico delle modifiche di `Configuration` (`#[arx(audit)]`) su un file SQLite temporaneo:
+/// voci registrate per creazione, aggiornamento, revoca ed eliminazione con autore e campi modificati,
+/// e la ricostruzione del record in un determinato momento.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    use chrono::Utc;
+
+    use solid_arx_lib::crud::audit;
+    use solid_arx_lib::crud::crud_ops::{Create, Delete, Read, Revoke, Update};
+    use solid_arx_lib::crud::models::default::audit_entry::model::AuditAction;
+    use solid_arx_lib::crud::models::default::configuration::model::Configuration;
+
+    use crate::common::{TestDatabase, AUDIT_ENTRY, CONFIGURATION};
+
+    /// Test per verificare storico e ricostruzione di un record
+    #[test]
+    fn test_history_and_state_at() {
+        let _db = TestDatabase::sqlite("audit", &[CONFIGURATION, AUDIT_ENTRY]);
+
+        let before_create = Utc::now().naive_utc();
+        sleep(Duration::from_millis(10));
+        audit::with_actor("installer", || {
+            Configuration::create(Configuration::new(1, Some(7), "sampling".to_string(), "10".to_string(), Box::new([])))
+        })
+        .unwrap();
+        sleep(Duration::from_millis(10));
+        let after_create = Utc::now().naive_utc();
+        sleep(Duration::from_millis(10));
+
+        let mut config = Configuration::read(1).unwrap();
+        config.value = "30".to_string();
+        audit::with_actor("admin", || Configuration::update(config)).unwrap();
+        Configuration::revoke(1, "Valore non supportato", "operator").unwrap();
+        Configuration::delete(1).unwrap();
+
+        let history = audit::history::<Configuration>(&1).unwrap();
+        let actions: Vec<AuditAction> = history.iter().map(|entry| entry.action).collect();
+        assert_eq!(actions, vec![AuditAction::Create, AuditAction::Update, AuditAction::Revoke, AuditAction::Delete]);
+        let actors: Vec<&str> = history.iter().map(|entry| entry.actor.as_str()).collect();
+        assert_eq!(actors, vec!["installer", "admin", "operator", audit::DEFAULT_ACTOR]);
+        assert_eq!(history[1].changed_fields, vec!["value".to_string(), "version".to_string()]);
+        assert!(history[3].after.is_none());
+
+        // Il record non esisteva, poi aveva il valore iniziale, infine è stato eliminato
+        assert!(audit::state_at::<Configuration>(&1, before_create).unwrap().is_none());
+        assert_eq!(audit::state_at::<Configuration>(&1, after_create).unwrap().unwrap().value, "10");
+        assert!(audit::state_at::<Configuration>(&1, Utc::now().naive_utc()).unwrap().is_none());
+    }
+}
+
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod common;
@@ -0,0 +1,76 @@
+/// # Integration Tests per `batch.rs`
+///
+/// Verifica l'inserimento a blocchi di `SensorData` (in memoria) e `Configuration` (SQLite temporaneo):
+/// record non validi riportati con la loro posizione senza interrompere i blocchi, ID assegnati
+/// dal database e inserimento dei record ricevuti da un canale.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use solid_arx_lib::crud::batch::{self, BatchConfig};
+    use solid_arx_lib::crud::crud_ops::{Create, CrudError, List, Read};
+    use solid_arx_lib::crud::models::default::configuration::model::Configuration;
+    use solid_arx_lib::crud::models::default::device::model::Device;
+    use solid_arx_lib::crud::models::default::log_event::model::LogEvent;
+    use solid_arx_lib::crud::models::default::sensor_data::model::SensorData;
+    use solid_arx_lib::crud::query::Query;
+
+    use crate::common::{TestDatabase, AUDIT_ENTRY, CONFIGURATION, DEVICE};
+
+    fn reading(id: u32, device_id: u32) -> SensorData {
+        SensorData::new(id, device_id, "2024-01-01T00:00:00".to_string(), "21.5".to_string(), Box::new([]))
+    }
+
+    /// Test per verificare blocchi, errori parziali e ID assegnati dal database
+    #[test]
+    fn test_insert_batch() {
+        let _db = TestDatabase::sqlite("batch", &[DEVICE, CONFIGURATION, AUDIT_ENTRY]);
+
+        // In memoria: il dispositivo 99 non esiste e l'ID 2 è duplicato
+        Device::create(Device::new(1, "sonda".to_string(), Box::new([]))).unwrap();
+        let readings = vec![reading(1, 1), reading(2, 1), reading(3, 99), reading(2, 1), reading(4, 1)];
+        let report = batch::insert(readings, &BatchConfig::new().batch_size(2)).unwrap();
+        assert_eq!((report.inserted, report.batches, report.total()), (3, 3, 5));
+        let failed: Vec<usize> = report.failures.iter().map(|failure| failure.index).collect();
+        assert_eq!(failed, vec![2, 3]);
+        assert!(matches!(report.failures[0].error, CrudError::ReferenceViolation { .. }));
+        assert!(matches!(report.failures[1].error, CrudError::Conflict { .. }));
+        assert_eq!(SensorData::list(&Query::new()).unwrap().total, 3);
+
+        // Su database: ID assegnati dopo quelli espliciti, il duplicato non annulla il blocco
+        let configurations = vec![
+            Configuration::new(5, None, "sampling".to_string(), "10".to_string(), Box::new([])),
+            Configuration::new(0, None, "threshold".to_string(), "30".to_string(), Box::new([])),
+            Configuration::new(5, None, "duplicato".to_string(), "0".to_string(), Box::new([])),
+            Configuration::new(0, None, "unit".to_string(), "celsius".to_string(), Box::new([])),
+        ];
+        let report = batch::insert(configurations, &BatchConfig::new()).unwrap();
+        assert_eq!((report.inserted, report.batches), (3, 1));
+        assert_eq!(report.failures[0].index, 2);
+        assert!(report.throughput() > 0.0);
+        // `Configuration` non dichiara l'operazione `list`: i record vengono riletti per ID
+        let keys: Vec<String> = [5, 6, 7].into_iter().map(|id| Configuration::read(id).unwrap().key).collect();
+        assert_eq!(keys, vec!["sampling", "threshold", "unit"]);
+        assert!(matches!(Configuration::read(8), Err(CrudError::NotFound { .. })));
+    }
+
+    /// Test per verificare l'inserimento dei record ricevuti da un canale
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ingest() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(16);
+        let ingest = tokio::spawn(async move { batch::ingest::<LogEvent>(&mut receiver, &BatchConfig::new().batch_size(4)).await });
+        for id in 1..=10 {
+            let event = LogEvent::new(id, 1, "tick".to_string(), "2024-01-01T00:00:00".to_string(), String::new(), Box::new([]));
+            sender.send(event).await.unwrap();
+        }
+        drop(sender);
+
+        let report = ingest.await.unwrap().unwrap();
+        assert_eq!(report.inserted, 10);
+        assert!(report.is_complete());
+        assert!(report.batches >= 3);
+    }
+}
+
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod common;
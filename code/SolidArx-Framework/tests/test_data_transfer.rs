@@ -0,0 +1,90 @@
+/// # Integration Tests per `data_transfer.rs`
+///
+/// Verifica la serializzazione serde dei modelli senza i campi del framework e il trasferimento dei record
+/// di `Configuration` (SQLite temporaneo): esportazione CSV e JSON, reimportazione con gli ID originali,
+/// distinzione tra `NULL` e testo vuoto e record non validi riportati con la loro posizione.
+/// L'esportazione di `User` non include la password, dichiarata con `#[arx(secret)]`.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use diesel::RunQueryDsl;
+
+    use solid_arx_lib::crud::batch::BatchConfig;
+    use solid_arx_lib::crud::crud_ops::{AllocType, Create, Read, Revoke};
+    use solid_arx_lib::crud::data_transfer::{self, DataFormat, TransferError};
+    use solid_arx_lib::crud::model_cache;
+    use solid_arx_lib::crud::models::default::configuration::model::Configuration;
+    use solid_arx_lib::crud::models::default::user::model::User;
+
+    use crate::common::{TestDatabase, AUDIT_ENTRY, CONFIGURATION, USER};
+
+    /// Test per verificare esportazione e importazione in CSV e JSON
+    #[test]
+    fn test_export_import() {
+        let mut db = TestDatabase::sqlite("data_transfer", &[CONFIGURATION, AUDIT_ENTRY, USER]);
+
+        let sampling = Configuration::create(Configuration::new(1, Some(3), "sampling".to_string(), "10".to_string(), Box::new([]))).unwrap();
+        Configuration::create(Configuration::new(2, None, "label".to_string(), String::new(), Box::new([]))).unwrap();
+        Configuration::revoke(1, "sostituita", "admin").unwrap();
+
+        // I campi del framework non vengono serializzati e vengono ricostruiti dal modello
+        let json = serde_json::to_value(&sampling).unwrap();
+        assert!(json.get("store").is_none() && json.get("ops").is_none() && json.get("memory").is_none());
+        let restored: Configuration = serde_json::from_value(json).unwrap();
+        assert_eq!((restored.key.as_str(), restored.store), ("sampling", AllocType::Cached));
+
+        let transfer = data_transfer::model("configuration").unwrap();
+        assert!(matches!(data_transfer::model("inesistente"), Err(TransferError::UnknownModel(_))));
+
+        let mut csv = Vec::new();
+        assert_eq!(transfer.export(DataFormat::Csv, &mut csv).unwrap(), 2);
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("id,device_id,key,value,version,revocation\n"));
+        assert!(csv.contains("\n2,,label,\"\",1,\n"), "NULL come cella vuota, testo vuoto tra virgolette");
+
+        let mut json = Vec::new();
+        transfer.export(DataFormat::Json, &mut json).unwrap();
+
+        // Reimportazione in un database vuoto: ID, versione e revoca vengono mantenuti
+        diesel::sql_query("DELETE FROM configuration").execute(&mut db.conn).unwrap();
+        model_cache::clear::<Configuration>().unwrap();
+        let report = transfer.import(DataFormat::Csv, &mut csv.as_bytes(), &BatchConfig::new()).unwrap();
+        assert!(report.is_complete());
+        let label = Configuration::read(2).unwrap();
+        assert_eq!((label.device_id, label.value.as_str(), label.version), (None, "", 1));
+        assert!(Configuration::is_revoked(1).unwrap());
+
+        // I record già presenti vengono riportati con la loro posizione nel file
+        let report = transfer.import(DataFormat::Json, &mut json.as_slice(), &BatchConfig::new()).unwrap();
+        let failed: Vec<usize> = report.failures.iter().map(|failure| failure.index).collect();
+        assert_eq!((report.inserted, failed), (0, vec![0, 1]));
+
+        let invalid = "id,key,value,version\n3,unit,celsius,uno\n";
+        let error = transfer.import(DataFormat::Csv, &mut invalid.as_bytes(), &BatchConfig::new()).unwrap_err();
+        assert!(matches!(error, TransferError::InvalidRecord { record: 1, .. }));
+
+        // La password di `User` non viene esportata e senza di essa i record non vengono reimportati
+        User::create(User::new(1, "mario".to_string(), "mario@example.com".to_string(), "segreta".to_string())).unwrap();
+        let users = data_transfer::model("user").unwrap();
+
+        let mut json = Vec::new();
+        assert_eq!(users.export(DataFormat::Json, &mut json).unwrap(), 1);
+        let records: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(records[0]["username"], "mario");
+        assert!(records[0].get("password").is_none());
+
+        let mut csv = Vec::new();
+        users.export(DataFormat::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv, "id,username,email\n1,mario,mario@example.com\n");
+
+        let error = users.import(DataFormat::Csv, &mut csv.as_bytes(), &BatchConfig::new()).unwrap_err();
+        assert!(matches!(error, TransferError::InvalidFormat(_)));
+        let error = users.import(DataFormat::Json, &mut json.as_slice(), &BatchConfig::new()).unwrap_err();
+        assert!(matches!(error, TransferError::InvalidRecord { record: 1, .. }));
+    }
+}
+
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod common;
@@ -0,0 +1,73 @@
+/// # Integration Tests per `database_store.rs`
+///
+/// Verifica inserimento, lettura, aggiornamento, query ed eliminazione di un modello con
+/// `AllocType::Database` su un file SQLite temporaneo, con la connessione ottenuta da `ConnectionManager`,
+/// e il cestino (ripristino e purge) dei record eliminati.
+/// Le tabelle vengono create da `MigrationManager::migrate`, come con `arx db migrate`, così che
+/// il test verifichi anche lo schema generato per `Article` e per i modelli collegati (vedi `relations.rs`).
+///
+/// Eseguire con: cargo test --features "crud webapp"
+#[cfg(all(test, feature = "crud", feature = "webapp"))]
+mod tests {
+    use std::time::Duration;
+
+    use solid_arx_lib::crud::crud_ops::{Create, CrudError, Delete, List, Read, Search, SoftDelete, Update};
+    use solid_arx_lib::crud::models::default::article::model::Article;
+    use solid_arx_lib::crud::models::default::user::model::User;
+    use solid_arx_lib::crud::query::{Query, SortDirection};
+
+    use crate::common::TestDatabase;
+
+    /// Test per verificare il ciclo CRUD completo su SQLite
+    #[test]
+    fn test_article_sqlite_crud() {
+        let _db = TestDatabase::migrated("database_store");
+
+        // Gli autori degli articoli devono esistere (`belongs_to = "User"`)
+        for id in [7, 8] {
+            User::create(User::new(id, format!("autore{}", id), format!("autore{}@arx.dev", id), String::new())).unwrap();
+        }
+        Article::create(Article::new(1, "Diesel".to_string(), "Storage SQL".to_string(), 7)).unwrap();
+        Article::create(Article::new(2, "Mongo".to_string(), "Storage documentale".to_string(), 7)).unwrap();
+
+        // ID esplicito duplicato e ID assegnato dal database
+        let duplicate = Article::create(Article::new(2, "Duplicato".to_string(), String::new(), 7));
+        assert_eq!(duplicate.unwrap_err(), CrudError::Conflict { model: "article", id: "2".to_string() });
+        let assigned = Article::create(Article::new(0, "Bozza".to_string(), String::new(), 8)).unwrap();
+        assert_eq!(assigned.id, 3);
+        Article::delete(assigned.id).unwrap();
+
+        let mut article = Article::read(1).unwrap();
+        assert_eq!(article.title, "Diesel");
+        assert_eq!(article.author_id, 7);
+
+        article.content = "Storage SQL tramite diesel".to_string();
+        Article::update(article).unwrap();
+        assert_eq!(Article::read(1).unwrap().content, "Storage SQL tramite diesel");
+
+        let page = Article::list(&Query::new().eq("author_id", 7u32).order_by("title", SortDirection::Desc).limit(1)).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0].title, "Mongo");
+        assert!(page.next_cursor.is_some());
+        assert_eq!(Article::search(&Query::new().text("DIESEL")).unwrap().items.len(), 1);
+
+        Article::delete(2).unwrap();
+        assert_eq!(Article::read(2).unwrap_err(), CrudError::NotFound { model: "article", id: "2".to_string() });
+        assert!(matches!(Article::delete(2), Err(CrudError::NotFound { .. })));
+        let trashed = Article::new(2, "Mongo".to_string(), "Modifica nel cestino".to_string(), 7);
+        assert_eq!(Article::update(trashed).unwrap_err(), CrudError::NotFound { model: "article", id: "2".to_string() });
+
+        // Cestino: i record eliminati restano nella tabella fino al purge
+        assert_eq!(Article::list(&Query::new()).unwrap().total, 1);
+        assert_eq!(Article::list_trashed(&Query::new()).unwrap().total, 2);
+        assert_eq!(Article::restore(2).unwrap().title, "Mongo");
+        assert_eq!(Article::read(2).unwrap().title, "Mongo");
+        Article::delete(2).unwrap();
+        assert_eq!(Article::purge(Duration::from_secs(3600)).unwrap(), 0);
+        assert_eq!(Article::purge(Duration::ZERO).unwrap(), 2);
+        assert_eq!(Article::list_trashed(&Query::new()).unwrap().total, 0);
+    }
+}
+
+#[cfg(all(test, feature = "crud", feature = "webapp"))]
+mod common;
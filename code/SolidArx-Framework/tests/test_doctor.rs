@@ -0,0 +1,83 @@
+/// # Unit Tests per `doctor.rs`
+///
+/// Verifica le feature richieste da ogni tipo di applicazione, condivise con `CoreSystem::run`,
+/// i controlli sui percorsi risolti dalla root del progetto e la lettura del profilo di `arx.toml`
+/// da parte di `arx doctor`, eseguendo il binario da una cartella temporanea con un database SQLite.
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use serde_json::Value;
+    use solid_arx_lib::config::global_config::{compiled_features, ApplicationType};
+    use solid_arx_lib::monitoring::doctor::{check_features, check_log_dir, CheckStatus};
+
+    /// Test per verificare le feature richieste per tipo di applicazione
+    #[test]
+    fn test_required_features() {
+        assert_eq!(ApplicationType::AutomationScript.required_features(), ["task_automation"]);
+        assert_eq!(ApplicationType::WebApp.required_features(), ["auth", "crud", "api", "frontend"]);
+        assert!(ApplicationType::EmbeddedSystem.missing_features().is_empty());
+
+        let missing = ApplicationType::ApiBackend.missing_features();
+        assert!(missing.iter().all(|feature| !compiled_features().contains(feature)));
+        let status = check_features(Some(ApplicationType::ApiBackend)).status;
+        assert_eq!(status == CheckStatus::Pass, missing.is_empty());
+    }
+
+    /// Test per verificare i controlli sulle cartelle dei log e dei modelli
+    #[test]
+    fn test_check_paths() {
+        let dir = std::env::temp_dir().join(format!("arx_doctor_paths_{}", std::process::id()));
+        assert_eq!(check_log_dir(&dir).status, CheckStatus::Fail);
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(check_log_dir(&dir).status, CheckStatus::Pass);
+
+        #[cfg(feature = "crud")]
+        {
+            use solid_arx_lib::crud::models::model_generator::models_path;
+            use solid_arx_lib::monitoring::doctor::check_model_dirs;
+
+            assert_ne!(check_model_dirs(&models_path()).status, CheckStatus::Fail);
+            assert_eq!(check_model_dirs(&dir).status, CheckStatus::Fail);
+        }
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// Test per verificare che `arx doctor` utilizzi il profilo di `arx.toml` e la root del progetto
+    #[test]
+    fn test_doctor_profile() {
+        let dir = std::env::temp_dir().join(format!("arx_doctor_profile_{}", std::process::id()));
+        let work_dir = dir.join("src");
+        std::fs::create_dir_all(&work_dir).unwrap();
+        let database = dir.join("doctor.db");
+        std::fs::write(dir.join("arx.toml"), format!(
+            "[profiles.dev.core]\napp_type = \"embedded\"\n\n[profiles.dev.database]\ndatabase_type = \"sqlite\"\ndatabase_url = \"{}\"\n",
+            database.display()
+        )).unwrap();
+
+        // `arx` viene eseguito da una sottocartella del progetto
+        let output = Command::new(env!("CARGO_BIN_EXE_arx"))
+            .args(["--output", "json", "--config"])
+            .arg(dir.join("arx.toml"))
+            .arg("doctor")
+            .current_dir(&work_dir)
+            .output()
+            .unwrap();
+        let document: Value = serde_json::from_slice(&output.stdout).unwrap();
+        let checks = if document["status"] == "ok" {
+            &document["data"]["checks"]
+        } else {
+            &document["error"]["details"]["checks"]
+        };
+        let check = |name: &str| checks.as_array().unwrap().iter().find(|c| c["name"] == name).unwrap().clone();
+
+        assert_eq!(check("Database")["status"], "pass");
+        assert_eq!(check("Features")["status"], "pass");
+        assert!(check("Features")["message"].as_str().unwrap().contains("EmbeddedSystem"));
+        let log_dir = dir.join("monitoring/logs").display().to_string();
+        assert!(check("Log directory")["message"].as_str().unwrap().contains(&log_dir));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
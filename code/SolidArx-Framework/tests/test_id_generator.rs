@@ -0,0 +1,44 @@
+/// # Unit Tests per `id_generator.rs`
+///
+/// Verifica l'assegnazione degli ID incrementali in memoria, il conflitto sugli ID espliciti duplicati
+/// e l'ordinamento degli ID Snowflake e UUIDv7.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use solid_arx_lib::crud::crud_ops::{ArxModel, Create, CrudError, Read};
+    use solid_arx_lib::crud::id_generator::{snowflake, uuid_v7, IdStrategy};
+    use solid_arx_lib::crud::models::default::command::model::Command;
+
+    fn command(id: u32) -> Command {
+        Command::new(id, 1, "reboot".to_string(), "2024-01-01 00:00:00".to_string(), Box::new([]))
+    }
+
+    /// Test per verificare gli ID incrementali e i conflitti sullo store in memoria
+    #[test]
+    fn test_auto_increment_in_memory() {
+        assert_eq!(Command::ID_STRATEGY, IdStrategy::AutoIncrement);
+
+        let first = Command::create(command(0)).unwrap();
+        let second = Command::create(command(0)).unwrap();
+        assert!(second.id > first.id);
+        assert_eq!(Command::read(second.id).unwrap().id, second.id);
+
+        Command::create(command(500)).unwrap();
+        assert_eq!(Command::create(command(0)).unwrap().id, 501, "Il contatore supera gli ID espliciti");
+
+        let conflict = Command::create(command(500)).unwrap_err();
+        assert_eq!(conflict, CrudError::Conflict { model: "command", id: "500".to_string() });
+    }
+
+    /// Test per verificare univocità e ordinamento degli ID generati
+    #[test]
+    fn test_snowflake_and_uuid_v7() {
+        let ids: Vec<u64> = (0..10_000).map(|_| snowflake()).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let (a, b) = (uuid_v7(), uuid_v7());
+        assert_eq!(a.get_version_num(), 7);
+        assert!(a < b);
+    }
+}
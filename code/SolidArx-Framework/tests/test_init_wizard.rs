@@ -0,0 +1,48 @@
+/// # Unit Tests per `init_wizard.rs`
+///
+/// Verifica la configurazione guidata di `arx init --interactive` simulando le risposte
+/// dell'utente: applicazione dei default per tipo di applicazione, ripetizione delle domande
+/// con risposte non valide e validazione dell'URL del database.
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use solid_arx_lib::config::init_wizard::{run_wizard, validate_database_url};
+    use solid_arx_lib::config::network_config::{ConnectionConfig, DatabaseType};
+
+    /// Test per verificare che le risposte vuote utilizzino i default e che le risposte non valide vengano ripetute
+    #[test]
+    fn test_wizard_defaults_and_retry() {
+        // app type non valido, poi webapp; default per scale, thread e dimensioni; sqlite con URL
+        let answers = "desktop-phone\nwebapp\n\n0\n16\n\n\nsqlite\n\ndev.db\n";
+        let mut prompts = Vec::new();
+
+        let profile = run_wizard(&mut Cursor::new(answers), &mut prompts).unwrap();
+        assert_eq!(profile.core.app_type.as_deref(), Some("webapp"));
+        assert_eq!(profile.core.max_threads, Some(16));
+        assert_eq!(profile.memory.memory_scale, Some(1));
+        assert_eq!(profile.memory.buffer_size, None);
+        assert_eq!(profile.memory.pool_size, None);
+        assert_eq!(profile.database.database_type.as_deref(), Some("sqlite"));
+        assert_eq!(profile.database.database_url.as_deref(), Some("dev.db"));
+
+        // Il default del buffer per WebApp (16 MB) viene mostrato nella domanda
+        let prompts = String::from_utf8(prompts).unwrap();
+        assert!(prompts.contains(&format!("[{}]", 16 * 1024 * 1024)));
+        assert!(prompts.contains("Tipo di applicazione non riconosciuto"));
+    }
+
+    /// Test per verificare l'errore quando l'input termina prima della fine della configurazione
+    #[test]
+    fn test_wizard_incomplete_input() {
+        assert!(run_wizard(&mut Cursor::new("webapp\n"), &mut Vec::new()).is_err());
+    }
+
+    /// Test per verificare la validazione dell'URL in base al tipo di database
+    #[test]
+    fn test_validate_database_url() {
+        let postgres = DatabaseType::PostgreSQL(ConnectionConfig::default());
+        assert!(validate_database_url(&postgres, "postgres://localhost/arx").is_ok());
+        assert!(validate_database_url(&postgres, "mysql://localhost/arx").is_err());
+        assert!(validate_database_url(&DatabaseType::SQLite(ConnectionConfig::default()), "").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+/// # Unit Tests per `migrations.rs`
+///
+/// Verifica il calcolo del piano di migrazione usato da `arx db plan` e `arx db migrate`
+/// e la serializzazione dello snapshot dello schema salvato nella tabella `arx_migrations`,
+/// oltre allo schema ricavato dai modelli reali di `crud/models/default` tramite `table_scraper.rs`.
+/// I test non richiedono una connessione al database.
+/// Eseguire con: cargo test --features "crud"
+#[cfg(all(test, feature = "crud"))]
+mod tests {
+    use std::collections::BTreeMap;
+    use solid_arx_lib::config::network_config::{ConnectionConfig, DatabaseType};
+    use solid_arx_lib::crud::models::migrations::{
+        desired_schema,
+        parse_snapshot,
+        plan_migration,
+        serialize_snapshot,
+        SchemaSnapshot,
+    };
+    use solid_arx_lib::crud::models::table_scraper::column_type;
+
+    fn schema(tables: &[(&str, &[(&str, &str)])]) -> SchemaSnapshot {
+        tables
+            .iter()
+            .map(|(table, fields)| {
+                let fields = fields
+                    .iter()
+                    .map(|(f, t)| (f.to_string(), t.to_string()))
+                    .collect::<BTreeMap<_, _>>();
+                (table.to_string(), fields)
+            })
+            .collect()
+    }
+
+    /// Test per verificare che uno snapshot serializzato venga ricostruito identico
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let snapshot = schema(&[("user", &[("id", "INTEGER"), ("email", "TEXT")])]);
+        assert_eq!(parse_snapshot(&serialize_snapshot(&snapshot)), snapshot);
+    }
+
+    /// Test per verificare la creazione di nuove tabelle e colonne con il relativo rollback
+    #[test]
+    fn test_plan_new_table_and_column() {
+        let sqlite = DatabaseType::SQLite(ConnectionConfig::default());
+        let current = schema(&[("user", &[("id", "INTEGER")])]);
+        let desired = schema(&[
+            ("user", &[("id", "INTEGER"), ("email", "TEXT")]),
+            ("tag", &[("id", "INTEGER"), ("name", "TEXT")]),
+        ]);
+
+        let plan = plan_migration(&current, &desired, &sqlite);
+        assert_eq!(plan.up, vec![
+            r#"CREATE TABLE IF NOT EXISTS "tag" ("id" INTEGER PRIMARY KEY, "name" TEXT)"#.to_string(),
+            r#"ALTER TABLE "user" ADD COLUMN "email" TEXT"#.to_string(),
+        ]);
+        assert_eq!(plan.down, vec![
+            r#"ALTER TABLE "user" DROP COLUMN "email""#.to_string(),
+            r#"DROP TABLE IF EXISTS "tag""#.to_string(),
+        ]);
+
+        // Uno schema già allineato non produce istruzioni
+        assert!(plan_migration(&desired, &desired, &sqlite).is_empty());
+    }
+
+    /// Test per verificare che le tabelle referenziate dalle chiavi esterne vengano create prima
+    #[test]
+    fn test_plan_foreign_key_order() {
+        let sqlite = DatabaseType::SQLite(ConnectionConfig::default());
+        let desired = schema(&[
+            ("article", &[("id", "INTEGER")]),
+            ("article_tag", &[
+                ("id", "INTEGER"),
+                ("article_id", "INTEGER REFERENCES article(id) ON DELETE CASCADE"),
+                ("tag_id", "INTEGER REFERENCES tag(id) ON DELETE CASCADE"),
+            ]),
+            ("tag", &[("id", "INTEGER")]),
+        ]);
+
+        let plan = plan_migration(&SchemaSnapshot::new(), &desired, &sqlite);
+        assert_eq!(plan.up, vec![
+            r#"CREATE TABLE IF NOT EXISTS "article" ("id" INTEGER PRIMARY KEY)"#.to_string(),
+            r#"CREATE TABLE IF NOT EXISTS "tag" ("id" INTEGER PRIMARY KEY)"#.to_string(),
+            concat!(
+                r#"CREATE TABLE IF NOT EXISTS "article_tag" ("article_id" INTEGER REFERENCES "article"(id) ON DELETE CASCADE, "#,
+                r#""id" INTEGER PRIMARY KEY, "tag_id" INTEGER REFERENCES "tag"(id) ON DELETE CASCADE)"#,
+            ).to_string(),
+        ]);
+        assert_eq!(plan.down[0], r#"DROP TABLE IF EXISTS "article_tag""#);
+    }
+
+    /// Test per verificare le colonne degli ID assegnati dal database su SQLite e PostgreSQL
+    #[test]
+    fn test_plan_auto_increment_id() {
+        let sqlite = DatabaseType::SQLite(ConnectionConfig::default());
+        let postgres = DatabaseType::PostgreSQL(ConnectionConfig::default());
+        let desired = schema(&[
+            ("device", &[("id", "INTEGER AUTOINCREMENT"), ("name", "TEXT")]),
+            ("reading", &[("id", "BIGINT AUTOINCREMENT")]),
+        ]);
+
+        let plan = plan_migration(&SchemaSnapshot::new(), &desired, &sqlite);
+        assert_eq!(plan.up, vec![
+            r#"CREATE TABLE IF NOT EXISTS "device" ("id" INTEGER PRIMARY KEY AUTOINCREMENT, "name" TEXT)"#.to_string(),
+            r#"CREATE TABLE IF NOT EXISTS "reading" ("id" INTEGER PRIMARY KEY AUTOINCREMENT)"#.to_string(),
+        ]);
+        let plan = plan_migration(&SchemaSnapshot::new(), &desired, &postgres);
+        assert_eq!(plan.up, vec![
+            r#"CREATE TABLE IF NOT EXISTS "device" ("id" INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY, "name" TEXT)"#.to_string(),
+            r#"CREATE TABLE IF NOT EXISTS "reading" ("id" BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY)"#.to_string(),
+        ]);
+    }
+
+    /// Test per verificare i nomi riservati di SQL tra doppi apici e i vincoli `CHECK` sulla colonna
+    #[test]
+    fn test_plan_quoted_identifiers() {
+        let sqlite = DatabaseType::SQLite(ConnectionConfig::default());
+        let current = schema(&[("rate_limit", &[("id", "INTEGER")])]);
+        let desired = schema(&[("rate_limit", &[
+            ("id", "INTEGER"),
+            ("limit", "INTEGER"),
+            ("status", "TEXT CHECK (value IN ('Active', 'Disabled'))"),
+        ])]);
+
+        let plan = plan_migration(&current, &desired, &sqlite);
+        assert_eq!(plan.up, vec![
+            r#"ALTER TABLE "rate_limit" ADD COLUMN "limit" INTEGER"#.to_string(),
+            r#"ALTER TABLE "rate_limit" ADD COLUMN "status" TEXT CHECK ("status" IN ('Active', 'Disabled'))"#.to_string(),
+        ]);
+        // Lo snapshot registra i tipi originali
+        assert_eq!(plan.target["rate_limit"]["status"], "TEXT CHECK (value IN ('Active', 'Disabled'))");
+    }
+
+    /// Test per verificare che i cambi di tipo vengano segnalati e non applicati
+    #[test]
+    fn test_plan_type_change_warning() {
+        let sqlite = DatabaseType::SQLite(ConnectionConfig::default());
+        let current = schema(&[("device", &[("id", "INTEGER"), ("name", "INTEGER")])]);
+        let desired = schema(&[("device", &[("id", "INTEGER"), ("name", "TEXT")])]);
+
+        let plan = plan_migration(&current, &desired, &sqlite);
+        assert!(plan.is_empty());
+        assert_eq!(plan.warnings.len(), 1);
+    }
+
+    /// Test per verificare la mappatura dei tipi Rust, indipendente dagli spazi tra i token
+    #[test]
+    fn test_column_type() {
+        let sqlite = DatabaseType::SQLite(ConnectionConfig::default());
+        let mongo = DatabaseType::MongoDB(ConnectionConfig::default());
+        let ty = |source: &str| syn::parse_str::<syn::Type>(source).unwrap();
+
+        assert_eq!(column_type(&ty("Option<u32>"), &sqlite), "INTEGER NULL");
+        assert_eq!(column_type(&ty("Option < String >"), &sqlite), "TEXT NULL");
+        assert_eq!(column_type(&ty("Option<Revocation>"), &sqlite), "JSON NULL");
+        assert_eq!(column_type(&ty("Option<chrono::NaiveDateTime>"), &sqlite), "TIMESTAMP NULL");
+        assert_eq!(column_type(&ty("chrono :: NaiveDateTime"), &sqlite), "TIMESTAMP");
+        assert_eq!(column_type(&ty("Box<[u8]>"), &sqlite), "BYTEA");
+        assert_eq!(column_type(&ty("Vec<String>"), &mongo), "array");
+        assert_eq!(column_type(&ty("Option<chrono::NaiveDateTime>"), &mongo), "date");
+    }
+
+    /// Test per verificare lo schema ricavato dai modelli reali, definiti nei blocchi `cfg_if!`
+    #[test]
+    fn test_desired_schema_from_models() {
+        let sqlite = DatabaseType::SQLite(ConnectionConfig::default());
+        let schema = desired_schema(&sqlite).unwrap();
+
+        let audit = schema.get("audit_entry").expect("Tabella audit_entry mancante");
+        assert_eq!(audit.get("id").map(String::as_str), Some("BIGINT AUTOINCREMENT"));
+        assert_eq!(audit.get("timestamp").map(String::as_str), Some("TIMESTAMP"));
+        assert_eq!(audit.get("before").map(String::as_str), Some("TEXT NULL"));
+        assert_eq!(audit.get("changed_fields").map(String::as_str), Some("JSON"));
+        assert!(!audit.contains_key("store") && !audit.contains_key("ops"), "Campi del framework esclusi");
+
+        // Solo i modelli delle feature compilate generano tabelle, compresi i campi con `#[cfg]`
+        assert_eq!(schema.contains_key("command"), cfg!(feature = "embedded"));
+        if cfg!(feature = "embedded") {
+            let configuration = &schema["configuration"];
+            assert_eq!(configuration.get("device_id").map(String::as_str), Some("INTEGER NULL"));
+            assert_eq!(configuration.get("revocation").map(String::as_str), Some("JSON NULL"));
+        }
+    }
+}
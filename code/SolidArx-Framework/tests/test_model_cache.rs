@@ -0,0 +1,95 @@
+/// # Integration Tests per `model_cache.rs`
+///
+/// Verifica le politiche write-through (`Configuration`) e write-behind (`Device`) della cache
+/// dei modelli con `AllocType::Cached`, l'invalidazione su aggiornamento ed eliminazione,
+/// il controllo della versione su aggiornamento e le statistiche di hit e miss, su un file SQLite temporaneo.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use diesel::sql_types::BigInt;
+    use diesel::{QueryableByName, RunQueryDsl, SqliteConnection};
+
+    use solid_arx_lib::crud::crud_ops::{AllocType, ArxModel, Create, CrudError, Delete, List, Read, Update};
+    use solid_arx_lib::crud::model_cache::{self, CachePolicy};
+    use solid_arx_lib::crud::models::default::configuration::model::Configuration;
+    use solid_arx_lib::crud::models::default::device::model::Device;
+    use solid_arx_lib::crud::query::Query;
+
+    use crate::common::{TestDatabase, AUDIT_ENTRY, CONFIGURATION, DEVICE, FIRMWARE_VERSION};
+
+    #[derive(QueryableByName)]
+    struct CountRow {
+        #[diesel(sql_type = BigInt)]
+        total: i64,
+    }
+
+    fn count(conn: &mut SqliteConnection, table: &str) -> i64 {
+        diesel::sql_query(format!("SELECT COUNT(*) AS total FROM {}", table))
+            .get_result::<CountRow>(conn)
+            .unwrap()
+            .total
+    }
+
+    /// Test per verificare write-through e write-behind sullo stesso database
+    #[test]
+    fn test_cached_models() {
+        let mut db = TestDatabase::sqlite("model_cache", &[CONFIGURATION, AUDIT_ENTRY, DEVICE, FIRMWARE_VERSION]);
+
+        // Write-through: ogni scrittura raggiunge subito il database
+        assert_eq!(Configuration::default_store(), AllocType::Cached);
+        assert_eq!(Configuration::cache_config().policy, CachePolicy::WriteThrough);
+        Configuration::create(Configuration::new(1, Some(7), "sampling".to_string(), "10".to_string(), Box::new([]))).unwrap();
+        assert_eq!(count(&mut db.conn, "configuration"), 1);
+
+        let mut config = Configuration::read(1).unwrap();
+        assert_eq!(model_cache::stats::<Configuration>().hits, 1);
+
+        config.value = "30".to_string();
+        let stale = config.clone();
+        Configuration::update(config).unwrap();
+        assert_eq!(model_cache::stats::<Configuration>().entries, 0, "Il record aggiornato viene invalidato");
+        assert_eq!(
+            Configuration::update(stale).unwrap_err(),
+            CrudError::VersionConflict { model: "configuration", id: "1".to_string(), current: 2 }
+        );
+        assert_eq!(Configuration::read(1).unwrap().value, "30");
+        let stats = model_cache::stats::<Configuration>();
+        // `Configuration` ha l'audit: ogni aggiornamento legge lo stato precedente del record (hit, poi miss
+        // dopo l'invalidazione) e il rollback del conflitto scarta il record ricaricato, riletto con un miss
+        assert_eq!((stats.hits, stats.misses), (2, 3), "Il conflitto ricarica il record dal database");
+
+        Configuration::delete(1).unwrap();
+        assert_eq!(Configuration::read(1).unwrap_err(), CrudError::NotFound { model: "configuration", id: "1".to_string() });
+        assert_eq!(count(&mut db.conn, "configuration"), 0);
+
+        // Write-behind: le scritture restano in cache fino al flush
+        assert_eq!(Device::cache_config().policy, CachePolicy::WriteBehind);
+        Device::create(Device::new(1, "Gateway".to_string(), Box::new([]))).unwrap();
+        Device::create(Device::new(2, "Sensore".to_string(), Box::new([]))).unwrap();
+        Device::update(Device::new(2, "Sensore Serra".to_string(), Box::new([]))).unwrap();
+        assert_eq!(count(&mut db.conn, "device"), 0);
+        assert_eq!(model_cache::stats::<Device>().pending_writes, 3);
+        assert_eq!(Device::read(1).unwrap().name, "Gateway");
+        assert_eq!(Device::read(2).unwrap().name, "Sensore Serra");
+
+        assert_eq!(model_cache::flush::<Device>().unwrap(), 3);
+        assert_eq!(count(&mut db.conn, "device"), 2);
+        assert_eq!(model_cache::stats::<Device>().pending_writes, 0);
+
+        // L'eliminazione applica le relazioni `has_many` in una transazione, eseguita subito sul database
+        Device::delete(2).unwrap();
+        assert_eq!(count(&mut db.conn, "device"), 1);
+        assert!(matches!(Device::read(2), Err(CrudError::NotFound { .. })));
+
+        // `List` esegue il flush prima della query sul database
+        let mut device = Device::read(1).unwrap();
+        device.name = "Gateway Serra".to_string();
+        Device::update(device).unwrap();
+        assert_eq!(Device::list(&Query::new()).unwrap().items[0].name, "Gateway Serra");
+        assert!(model_cache::stats::<Device>().hit_ratio() > 0.0);
+    }
+}
+
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod common;
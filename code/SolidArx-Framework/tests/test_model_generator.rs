@@ -0,0 +1,77 @@
+/// # Unit Tests per `model_generator.rs`
+///
+/// Verifica che il generatore utilizzato da `arx model new` produca modelli coerenti
+/// con le convenzioni di `crud/models/default`, inclusi `#[derive(ArxModel)]` e l'attributo `#[arx(...)]`,
+/// e che la registrazione in `models/mod.rs` avvenga correttamente.
+///
+/// I test lavorano sui contenuti dei file in memoria, senza scrivere su disco.
+/// Eseguire con: cargo test --features "crud"
+#[cfg(all(test, feature = "crud"))]
+mod tests {
+    use solid_arx_lib::crud::crud_ops::AllocType;
+    use solid_arx_lib::crud::models::model_generator::{
+        register_dev_module,
+        render_model,
+        FieldSpec,
+        ModelSpec,
+    };
+
+    /// Test per verificare il parsing dei campi nel formato `nome:tipo`
+    #[test]
+    fn test_field_spec_parse() {
+        let field = FieldSpec::parse("amount:f64").unwrap();
+        assert_eq!(field.name, "amount");
+        assert_eq!(field.ty, "f64");
+
+        assert!(FieldSpec::parse("amount").is_err(), "Manca il tipo");
+        assert!(FieldSpec::parse("Amount:f64").is_err(), "Il nome deve essere snake_case");
+        assert!(FieldSpec::parse("amount:Decimal").is_err(), "Tipo non supportato");
+        assert!(FieldSpec::parse("store:String").is_err(), "Campo riservato");
+        assert!(FieldSpec::parse("type:String").is_err(), "Parola chiave di Rust");
+    }
+
+    /// Test per verificare la generazione del sorgente di un modello Database
+    #[test]
+    fn test_render_database_model() {
+        let spec = ModelSpec::new(
+            "Invoice",
+            &["amount:f64".to_string(), "customer_id:u32".to_string()],
+            "database",
+            "create,read,list",
+        ).unwrap();
+
+        let source = render_model(&spec);
+        assert!(source.contains("pub struct Invoice {"));
+        assert!(source.contains("pub amount: f64,"));
+        assert!(source.contains("pub customer_id: u32,"));
+        assert!(source.contains("#[derive(Debug, Clone, Serialize, Deserialize, ArxModel)]"));
+        assert!(source.contains("#[serde(skip, default = \"Invoice::declared_operations\")]"));
+        assert!(source.contains("#[arx(store = \"database\", ops = \"create,read,list\")]"));
+        assert!(source.contains("store: AllocType::Database,"));
+        assert!(source.contains("update: false,"));
+        assert!(source.contains("list: true,"));
+        assert!(!source.contains("memory"), "I modelli Database non allocano memoria");
+    }
+
+    /// Test per verificare che i modelli InMemory ricevano il buffer di memoria
+    #[test]
+    fn test_render_in_memory_model() {
+        let spec = ModelSpec::new("SensorReading", &["value:f32".to_string()], "memory", "create,read").unwrap();
+        assert!(matches!(spec.store, AllocType::InMemory));
+        assert_eq!(spec.module_name(), "sensor_reading");
+
+        let source = render_model(&spec);
+        assert!(source.contains("pub memory: Box<[u8]>,"));
+        assert!(source.contains("memory: Box<[u8]>) -> Self"));
+        assert!(source.contains("#[arx(store = \"memory\", ops = \"create,read\")]"));
+    }
+
+    /// Test per verificare la registrazione nel blocco `dev`
+    #[test]
+    fn test_register_dev_module() {
+        let mod_rs = "pub mod dev{}\npub mod default{\n    pub mod user;\n}\n";
+        let registered = register_dev_module(mod_rs, "invoice").unwrap();
+        assert!(registered.contains("pub mod dev{\n    pub mod invoice;\n}"));
+        assert!(register_dev_module(&registered, "invoice").is_err(), "Il modulo è già registrato");
+    }
+}
@@ -0,0 +1,179 @@
+/// # Integration Tests per `mongo_store.rs`
+///
+/// Verifica la conversione dei modelli e delle query in documenti BSON, senza server.
+/// Il ciclo CRUD completo su un'istanza di MongoDB è ignorato di default e richiede la variabile
+/// d'ambiente `ARX_TEST_MONGODB_URL` (es. `ARX_TEST_MONGODB_URL=mongodb://localhost:27017`).
+///
+/// Eseguire con: cargo test --features "crud webapp"
+/// Con MongoDB: ARX_TEST_MONGODB_URL=mongodb://localhost:27017 cargo test --features "crud webapp" -- --ignored
+#[cfg(all(test, feature = "crud", feature = "webapp"))]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    use chrono::NaiveDate;
+    use mongodb::bson::{doc, oid::ObjectId, Bson};
+
+    use solid_arx_lib::config::network_config::{ConnectionConfig, DatabaseType};
+    use solid_arx_lib::crud::crud_ops::{Create, CrudError, Delete, List, Read, Search, SoftDelete, Update};
+    use solid_arx_lib::crud::database_store;
+    use solid_arx_lib::crud::model_value::ModelValue;
+    use solid_arx_lib::crud::mongo_store;
+    use solid_arx_lib::crud::models::default::article::model::Article;
+    use solid_arx_lib::crud::models::default::user::model::User;
+    use solid_arx_lib::crud::query::{Cursor, Query, SortDirection};
+    use solid_arx_lib::network::connection_management::DbConnection;
+
+    /// Test per verificare la conversione dei valori dei campi in BSON e viceversa
+    #[test]
+    fn test_model_value_bson() {
+        let timestamp = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(10, 30, 0).unwrap();
+        let map = ModelValue::Map(BTreeMap::from([("chiave".to_string(), ModelValue::Int(1))]));
+        let values = [
+            ModelValue::Null,
+            ModelValue::Bool(true),
+            ModelValue::Int(-7),
+            ModelValue::Float(1.5),
+            ModelValue::Text("testo".to_string()),
+            ModelValue::List(vec![ModelValue::Int(1), ModelValue::Text("due".to_string())]),
+            map,
+        ];
+        for value in values {
+            assert_eq!(ModelValue::from_bson(value.to_bson()), value);
+        }
+
+        // I timestamp sono salvati come testo, i `DateTime` BSON vengono riletti come timestamp
+        assert_eq!(ModelValue::Timestamp(timestamp).to_bson(), Bson::String("2024-05-01 10:30:00".to_string()));
+        let datetime = Bson::DateTime(mongodb::bson::DateTime::from_chrono(timestamp.and_utc()));
+        assert_eq!(ModelValue::from_bson(datetime), ModelValue::Timestamp(timestamp));
+        assert_eq!(ModelValue::from_bson(Bson::Int32(3)), ModelValue::Int(3));
+        let id = Bson::ObjectId(ObjectId::new());
+        assert_eq!(ModelValue::from_bson(id.clone()), ModelValue::Text(id.to_string()));
+    }
+
+    /// Test per verificare la mappatura di un modello in documento, con l'ID in `_id`
+    #[test]
+    fn test_article_document() {
+        let article = Article::new(1, "Diesel".to_string(), "Storage SQL".to_string(), 7);
+        let document = mongo_store::to_document(&article);
+        assert_eq!(document.get("_id"), Some(&Bson::Int64(1)));
+        assert_eq!(document.get("id"), None);
+        assert_eq!(document.get("title"), Some(&Bson::String("Diesel".to_string())));
+        assert_eq!(document.get("author_id"), Some(&Bson::Int64(7)));
+        assert_eq!(document.get("deleted_at"), Some(&Bson::Null));
+
+        let read: Article = mongo_store::from_document(document).unwrap();
+        assert_eq!((read.id, read.title, read.content, read.author_id), (1, "Diesel".to_string(), "Storage SQL".to_string(), 7));
+
+        let error = mongo_store::from_document::<Article>(doc! { "_id": 1i64, "title": 3 }).unwrap_err();
+        assert!(matches!(error, CrudError::StorageError(message) if message.starts_with("article")));
+    }
+
+    /// Test per verificare la traduzione delle query in filtri e ordinamento MongoDB
+    #[test]
+    fn test_query_to_mongo() {
+        let query = Query::new().eq("id", 1u32).gt("author_id", 2u32).ne("title", "Bozza");
+        let mongo = query.to_mongo(&[]);
+        assert_eq!(
+            mongo.filter,
+            doc! { "$and": [{ "_id": 1i64 }, { "author_id": { "$gt": 2i64 } }, { "title": { "$ne": "Bozza" } }] }
+        );
+        assert_eq!(mongo.count_filter, mongo.filter);
+        assert_eq!(mongo.sort, doc! { "_id": 1 });
+        assert_eq!((mongo.skip, mongo.limit), (0, None));
+
+        // I caratteri speciali delle espressioni regolari vengono trattati come testo
+        let mongo = Query::new().contains("title", "a.b*").starts_with("content", "(x)").to_mongo(&[]);
+        assert_eq!(
+            mongo.filter,
+            doc! { "$and": [{ "title": { "$regex": "a\\.b\\*" } }, { "content": { "$regex": "^\\(x\\)" } }] }
+        );
+
+        let mongo = Query::new().text("sql").to_mongo(&["title", "content"]);
+        assert_eq!(
+            mongo.filter,
+            doc! { "$and": [{ "$or": [
+                { "title": { "$regex": "sql", "$options": "i" } },
+                { "content": { "$regex": "sql", "$options": "i" } },
+            ] }] }
+        );
+
+        // Il cursore filtra solo la `find`, la paginazione richiede un record in più
+        let cursor = Cursor(vec![ModelValue::Text("Mongo".to_string()), ModelValue::Int(2)]);
+        let mongo = Query::new().order_by("title", SortDirection::Desc).limit(10).offset(5).after(cursor).to_mongo(&[]);
+        assert_eq!(mongo.count_filter, doc! {});
+        assert_eq!(
+            mongo.filter,
+            doc! { "$and": [{ "$or": [
+                { "$and": [{ "$or": [{ "title": { "$lt": "Mongo" } }, { "title": null }] }] },
+                { "$and": [{ "title": "Mongo" }, { "_id": { "$gt": 2i64 } }] },
+            ] }] }
+        );
+        assert_eq!(mongo.sort, doc! { "title": -1, "_id": 1 });
+        assert_eq!((mongo.skip, mongo.limit), (5, Some(11)));
+
+        // Un cursore con valore nullo mantiene i valori non nulli in ordine crescente e nessuno in ordine decrescente
+        let cursor = Cursor(vec![ModelValue::Null, ModelValue::Int(2)]);
+        let mongo = Query::new().order_by("content", SortDirection::Asc).after(cursor.clone()).to_mongo(&[]);
+        assert_eq!(
+            mongo.filter,
+            doc! { "$and": [{ "$or": [
+                { "$and": [{ "content": { "$ne": null } }] },
+                { "$and": [{ "content": null }, { "_id": { "$gt": 2i64 } }] },
+            ] }] }
+        );
+        let mongo = Query::new().order_by("content", SortDirection::Desc).after(cursor).to_mongo(&[]);
+        assert_eq!(mongo.filter, doc! { "$and": [{ "$or": [{ "$and": [{ "content": null }, { "_id": { "$gt": 2i64 } }] }] }] });
+    }
+
+    /// Test per verificare il ciclo CRUD completo su MongoDB
+    #[test]
+    #[ignore = "richiede un'istanza di MongoDB in ARX_TEST_MONGODB_URL"]
+    fn test_article_mongodb_crud() {
+        let url = std::env::var("ARX_TEST_MONGODB_URL").expect("ARX_TEST_MONGODB_URL non impostata");
+
+        database_store::connect(DatabaseType::MongoDB(ConnectionConfig {
+            database_url: Some(url),
+            max_connections: Some(1),
+            retry_attempts: Some(1),
+            max_idle_time: Some(30),
+            connection_timeout: Some(1),
+        }))
+        .unwrap();
+
+        // Pulizia di eventuali record lasciati da esecuzioni precedenti
+        for id in [1, 2] {
+            let _ = Article::delete(id);
+        }
+        Article::purge(Duration::ZERO).unwrap();
+        // Gli autori degli articoli devono esistere (`belongs_to = "User"`), possono restare da esecuzioni precedenti
+        let _ = User::create(User::new(7, "autore7".to_string(), "autore7@arx.dev".to_string(), String::new()));
+
+        Article::create(Article::new(1, "Diesel".to_string(), "Storage SQL".to_string(), 7)).unwrap();
+        Article::create(Article::new(2, "Mongo".to_string(), "Storage documentale".to_string(), 7)).unwrap();
+
+        let mut article = Article::read(1).unwrap();
+        assert_eq!(article.title, "Diesel");
+        assert_eq!(article.author_id, 7);
+
+        article.content = "Storage SQL tramite diesel".to_string();
+        Article::update(article).unwrap();
+        assert_eq!(Article::read(1).unwrap().content, "Storage SQL tramite diesel");
+
+        let page = Article::list(&Query::new().eq("author_id", 7u32).order_by("title", SortDirection::Desc).limit(1)).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0].title, "Mongo");
+        let cursor = page.next_cursor.unwrap();
+        let next = Article::list(&Query::new().eq("author_id", 7u32).order_by("title", SortDirection::Desc).limit(1).after(cursor)).unwrap();
+        assert_eq!(next.items[0].id, 1);
+        assert_eq!(Article::search(&Query::new().text("documentale")).unwrap().items.len(), 1);
+        assert_eq!(Article::list(&Query::new().gt("id", 1u32)).unwrap().items[0].title, "Mongo");
+
+        Article::delete(2).unwrap();
+        assert_eq!(Article::read(2).unwrap_err(), CrudError::NotFound { model: "article", id: "2".to_string() });
+        Article::delete(1).unwrap();
+        assert_eq!(Article::purge(Duration::ZERO).unwrap(), 2);
+
+        assert!(matches!(database_store::uninstall(), Some(DbConnection::MongoDB(_))));
+    }
+}
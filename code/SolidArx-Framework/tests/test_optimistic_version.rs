@@ -0,0 +1,82 @@
+/// # Unit Tests per il controllo ottimistico della concorrenza
+///
+/// Verifica che l'aggiornamento di un modello con campo `version` (`Task`, in memoria)
+/// restituisca `CrudError::VersionConflict` quando il record è stato modificato dopo la lettura
+/// e che `update_with_merge` riprovi l'aggiornamento con il record unito. Un campo `version`
+/// di tipo diverso da `u64` (`FirmwareVersion`, su SQLite temporaneo) resta un dato del modello.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use solid_arx_lib::crud::crud_ops::{update_with_merge, ArxModel, Create, CrudError, Read, Update};
+    use solid_arx_lib::crud::models::default::device::model::Device;
+    use solid_arx_lib::crud::models::default::firmware_version::model::FirmwareVersion;
+    use solid_arx_lib::crud::models::default::task::model::Task;
+
+    use crate::common::{TestDatabase, DEVICE, FIRMWARE_VERSION};
+
+    /// Test per verificare il conflitto di versione e la risoluzione con merge
+    #[test]
+    fn test_task_version_conflict() {
+        const { assert!(Task::VERSIONED) };
+        // Costruito come letterale perché gli argomenti di `Task::new` dipendono dalle feature attive
+        let task = Task {
+            id: 971,
+            description: "Irrigazione".to_string(),
+            #[cfg(feature = "automation")]
+            schedule: None,
+            #[cfg(feature = "desktop")]
+            completed: None,
+            device_id: Some(1),
+            version: 0,
+            store: Task::default_store(),
+            memory: Box::new([]),
+            ops: Task::declared_operations(),
+        };
+        let created = Task::create(task).unwrap();
+        assert_eq!(created.version, 1);
+
+        let mut first = Task::read(971).unwrap();
+        let mut second = Task::read(971).unwrap();
+        first.description = "Irrigazione serra".to_string();
+        assert_eq!(Task::update(first).unwrap().version, 2);
+
+        second.device_id = Some(2);
+        let expected = CrudError::VersionConflict { model: "task", id: "971".to_string(), current: 2 };
+        assert_eq!(Task::update(second.clone()).unwrap_err(), expected);
+
+        // Il merge mantiene la descrizione memorizzata e il dispositivo proposto
+        let merged = update_with_merge(second.clone(), |mut stored, proposed| {
+            stored.device_id = proposed.device_id;
+            Some(stored)
+        })
+        .unwrap();
+        assert_eq!(merged.version, 3);
+        let stored = Task::read(971).unwrap();
+        assert_eq!((stored.description.as_str(), stored.device_id), ("Irrigazione serra", Some(2)));
+
+        assert!(matches!(update_with_merge(second, |_, _| None), Err(CrudError::VersionConflict { current: 3, .. })));
+    }
+    /// Test per verificare che un campo `version` non `u64` non attivi il controllo della versione
+    #[test]
+    fn test_firmware_version_field_is_data() {
+        const { assert!(!FirmwareVersion::VERSIONED) };
+        let _db = TestDatabase::sqlite("optimistic_version", &[DEVICE, FIRMWARE_VERSION]);
+
+        Device::create(Device::new(1, "Gateway".to_string(), Box::new([]))).unwrap();
+        let created = FirmwareVersion::create(FirmwareVersion::new(1, 1, "1.2.0".to_string(), "2024-01-01".to_string())).unwrap();
+        assert_eq!(created.version, "1.2.0");
+
+        // Due aggiornamenti dalla stessa lettura non generano conflitti e la versione resta quella indicata
+        let mut first = FirmwareVersion::read(1).unwrap();
+        let mut second = first.clone();
+        first.version = "1.3.0".to_string();
+        second.version = "1.3.1".to_string();
+        assert_eq!(FirmwareVersion::update(first).unwrap().version, "1.3.0");
+        assert_eq!(FirmwareVersion::update(second).unwrap().version, "1.3.1");
+        assert_eq!(FirmwareVersion::read(1).unwrap().version, "1.3.1");
+    }
+}
+
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod common;
@@ -0,0 +1,192 @@
+/// # Unit Tests per `output.rs`
+///
+/// Verifica la struttura stabile dei documenti JSON emessi con `arx --output json`
+/// e l'associazione tra classi di errore e codici di uscita del processo.
+/// I documenti di `init`, `database`, `config` e `status` vengono verificati eseguendo il binario `arx`
+/// su un file `arx.toml` temporaneo, così come i documenti degli errori di parsing di clap
+/// e la lettura del database di `arx db` dal profilo quando `-d` e `-u` non sono specificati.
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::process::Command;
+
+    use serde_json::{json, Value};
+    use solid_arx_lib::cli::requested_output;
+    use solid_arx_lib::core::system_core::CoreError;
+    use solid_arx_lib::monitoring::doctor::compiled_features;
+    use solid_arx_lib::monitoring::output::{
+        error_document,
+        success_document,
+        CliError,
+        CommandOutput,
+        ErrorClass,
+        OutputFormat,
+    };
+
+    /// Test per verificare il documento di un comando riuscito
+    #[test]
+    fn test_success_document() {
+        let mut output = CommandOutput::new("init").data(json!({ "profile": "dev" }));
+        output.line("Profilo dev inizializzato");
+
+        assert_eq!(success_document(&output), json!({
+            "command": "init",
+            "status": "ok",
+            "data": { "profile": "dev" },
+        }));
+    }
+
+    /// Test per verificare il documento di errore e i codici di uscita per classe
+    #[test]
+    fn test_error_document_and_exit_codes() {
+        let error = CliError::config("Profilo 'prod' non trovato");
+        assert_eq!(error_document("run", &error), json!({
+            "command": "run",
+            "status": "error",
+            "error": { "class": "config", "code": 3, "message": "Profilo 'prod' non trovato", "details": null },
+        }));
+
+        assert_eq!(ErrorClass::Usage.exit_code(), 2);
+        assert_eq!(ErrorClass::Database.exit_code(), 4);
+        assert_eq!(CliError::from(CoreError::ConfigurationError("x".to_string())).class, ErrorClass::Config);
+        assert_eq!(CliError::from(CoreError::InitializationError("x".to_string())).class, ErrorClass::Runtime);
+    }
+
+    /// Test per verificare la pre-analisi di `--output` e del comando quando il parsing di clap fallisce
+    #[test]
+    fn test_requested_output() {
+        assert_eq!(requested_output(["arx", "--output", "json", "db", "status", "--bogus"]), (OutputFormat::Json, "db status".to_string()));
+        assert_eq!(requested_output(["arx", "init", "--output=JSON", "--max-threads", "x"]), (OutputFormat::Json, "init".to_string()));
+        assert_eq!(requested_output(["arx", "--config", "arx.toml", "bogus"]), (OutputFormat::Text, "arx".to_string()));
+        assert_eq!(requested_output(["arx", "--output", "yaml", "status"]), (OutputFormat::Text, "status".to_string()));
+    }
+    /// Esegue `arx --output json` con il file di configurazione indicato.
+    ///
+    /// # Ritorna
+    /// Il documento JSON scritto su stdout e il codice di uscita del processo.
+    fn arx_json(config: &Path, args: &[&str]) -> (Value, i32) {
+        let output = Command::new(env!("CARGO_BIN_EXE_arx"))
+            .args(["--output", "json", "--config"])
+            .arg(config)
+            .args(args)
+            .output()
+            .unwrap();
+        (serde_json::from_slice(&output.stdout).unwrap(), output.status.code().unwrap())
+    }
+
+    /// Test per verificare i documenti di `init`, `database`, `config` e `status`
+    #[test]
+    fn test_command_documents() {
+        let dir = std::env::temp_dir().join(format!("arx_output_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("arx.toml");
+        let path = config.display().to_string();
+
+        assert_eq!(arx_json(&config, &["status"]), (json!({
+            "command": "status",
+            "status": "ok",
+            "data": { "config": path, "exists": false, "profiles": [], "features": compiled_features() },
+        }), 0));
+
+        let database = json!({
+            "database_type": null,
+            "database_url": null,
+            "max_connections": null,
+            "retry_attempts": null,
+            "max_idle_time": null,
+            "connection_timeout": null,
+        });
+        let mut settings = json!({
+            "core": { "app_type": "webapp", "max_threads": 8 },
+            "memory": { "memory_scale": 1, "buffer_size": null, "pool_size": null },
+            "database": database,
+        });
+        assert_eq!(arx_json(&config, &["init", "--app-type", "web-app"]), (json!({
+            "command": "init",
+            "status": "ok",
+            "data": { "config": path, "profile": "dev", "settings": settings },
+        }), 0));
+
+        settings["database"]["database_type"] = json!("sqlite");
+        settings["database"]["database_url"] = json!("app.db");
+        assert_eq!(arx_json(&config, &["database", "-d", "sqlite", "-u", "app.db"]), (json!({
+            "command": "database",
+            "status": "ok",
+            "data": { "config": path, "profile": "dev", "database": settings["database"] },
+        }), 0));
+
+        assert_eq!(arx_json(&config, &["config"]), (json!({
+            "command": "config",
+            "status": "ok",
+            "data": {
+                "config": path,
+                "profile": "dev",
+                "settings": settings,
+                "resolved": {
+                    "app_type": "WebApp",
+                    "max_threads": 8,
+                    "pool_size": 150 * 1024 * 1024,
+                    "buffer_size": 16 * 1024 * 1024,
+                    "memory_scale": 1,
+                    "database_type": "sqlite",
+                },
+            },
+        }), 0));
+
+        assert_eq!(arx_json(&config, &["status"]), (json!({
+            "command": "status",
+            "status": "ok",
+            "data": {
+                "config": path,
+                "exists": true,
+                "profiles": [{ "name": "dev", "app_type": "webapp", "database_type": "sqlite", "ready": true, "error": null }],
+                "features": compiled_features(),
+            },
+        }), 0));
+
+        // Errore strutturato con il codice di uscita della classe `config`
+        let (document, code) = arx_json(&config, &["config", "--profile", "prod"]);
+        assert_eq!((document["status"].as_str(), document["error"]["class"].as_str(), code), (Some("error"), Some("config"), 3));
+
+        // Anche gli errori di parsing di clap vengono emessi come documento JSON della classe `usage`
+        let (document, code) = arx_json(&config, &["init", "--max-threads", "x"]);
+        assert_eq!((document["command"].as_str(), document["status"].as_str(), code), (Some("init"), Some("error"), 2));
+        assert_eq!((document["error"]["class"].as_str(), document["error"]["code"].as_u64()), (Some("usage"), Some(2)));
+        assert_eq!(document["error"]["details"]["kind"], "ValueValidation");
+        assert!(document["error"]["message"].as_str().unwrap().starts_with("invalid value 'x' for '--max-threads"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// Test per verificare che `arx db` legga il database dal profilo se `-d` e `-u` non sono specificati
+    #[cfg(feature = "crud")]
+    #[test]
+    fn test_db_profile_database() {
+        let dir = std::env::temp_dir().join(format!("arx_output_db_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("arx.toml");
+        let database = dir.join("app.db");
+        let url = database.display().to_string();
+        assert_eq!(arx_json(&config, &["database", "-d", "sqlite", "-u", &url]).1, 0);
+
+        let (document, code) = arx_json(&config, &["db", "status"]);
+        assert_eq!((document["command"].as_str(), document["status"].as_str(), code), (Some("db status"), Some("ok"), 0));
+        assert_eq!(document["data"]["applied"], json!([]));
+        assert!(database.exists());
+
+        // Senza profilo il database deve essere specificato con `-d` e `-u`
+        let (document, code) = arx_json(&config, &["db", "--profile", "prod", "status"]);
+        assert_eq!((document["error"]["class"].as_str(), code), (Some("config"), 3));
+        let other = dir.join("other.db");
+        let other_url = other.display().to_string();
+        assert_eq!(arx_json(&config, &["db", "--profile", "prod", "-d", "sqlite", "-u", &other_url, "status"]).1, 0);
+        assert!(other.exists());
+
+        // Profilo senza database configurato
+        assert_eq!(arx_json(&config, &["init", "--profile", "bare", "--app-type", "embedded-system"]).1, 0);
+        let (document, code) = arx_json(&config, &["data", "--profile", "bare", "export", "configuration", "--file", "unused.json"]);
+        assert_eq!((document["command"].as_str(), document["error"]["class"].as_str(), code), (Some("data export"), Some("config"), 3));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
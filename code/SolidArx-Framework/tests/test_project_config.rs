@@ -0,0 +1,59 @@
+/// # Unit Tests per `project_config.rs`
+///
+/// Verifica il caricamento dei profili di `arx.toml` usati da `arx run`, la precedenza
+/// degli override passati dal CLI e la risoluzione nelle configurazioni del `CoreSystem`.
+/// I test non richiedono una connessione al database.
+#[cfg(test)]
+mod tests {
+    use solid_arx_lib::config::network_config::DatabaseType;
+    use solid_arx_lib::config::project_config::{CoreProfile, ProfileConfig, ProjectConfig};
+
+    const CONFIG: &str = r#"
+[profiles.dev.core]
+app_type = "automation"
+max_threads = 4
+
+[profiles.dev.memory]
+buffer_size = 2048
+
+[profiles.prod.core]
+app_type = "webapp"
+
+[profiles.prod.database]
+database_type = "sqlite"
+database_url = "prod.db"
+"#;
+
+    /// Test per verificare che gli override del CLI abbiano la precedenza sul profilo
+    #[test]
+    fn test_profile_merge_overrides() {
+        let project: ProjectConfig = toml::from_str(CONFIG).unwrap();
+        let overrides = ProfileConfig {
+            core: CoreProfile { app_type: None, max_threads: Some(16) },
+            ..ProfileConfig::default()
+        };
+
+        let merged = project.profile("dev").unwrap().merge(&overrides);
+        assert_eq!(merged.core.app_type.as_deref(), Some("automation"));
+        assert_eq!(merged.core.max_threads, Some(16));
+        assert_eq!(merged.memory.buffer_size, Some(2048));
+        assert!(project.profile("staging").is_err());
+    }
+
+    /// Test per verificare la risoluzione del profilo nelle configurazioni di Core, Memoria e Database
+    #[test]
+    fn test_profile_resolve() {
+        let project: ProjectConfig = toml::from_str(CONFIG).unwrap();
+
+        let dev = project.profile("dev").unwrap().resolve().unwrap();
+        assert_eq!(dev.core.max_threads, 4);
+        assert_eq!(dev.memory.buffer_size, 2048);
+        assert!(matches!(dev.database, DatabaseType::None));
+
+        let prod = project.profile("prod").unwrap().resolve().unwrap();
+        match prod.database {
+            DatabaseType::SQLite(config) => assert_eq!(config.database_url.as_deref(), Some("prod.db")),
+            other => panic!("Database inatteso: {:?}", other),
+        }
+    }
+}
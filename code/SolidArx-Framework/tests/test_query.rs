@@ -0,0 +1,149 @@
+/// # Unit Tests per `query.rs`
+///
+/// Verifica l'esecuzione in memoria di filtri, ordinamento e paginazione (offset e cursore)
+/// e la traduzione della stessa query in SQL parametrizzato e in filtro MongoDB.
+/// Le stesse query eseguite su un file SQLite temporaneo devono restituire i record dell'esecuzione in memoria,
+/// anche con caratteri jolly nei valori, maiuscole e campi di ordinamento nulli.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use solid_arx_lib::crud::crud_ops::{Create, Search};
+    use solid_arx_lib::crud::model_value::ModelValue;
+    use solid_arx_lib::crud::models::default::configuration::model::Configuration;
+    use solid_arx_lib::crud::models::default::device::model::Device;
+    use solid_arx_lib::crud::query::{Cursor, Page, Query, SortDirection, SqlDialect};
+
+    use crate::common::{TestDatabase, AUDIT_ENTRY, CONFIGURATION};
+
+    fn devices() -> Vec<Device> {
+        ["Serra", "Orto", "Garage", "Serra Nord", "Cantina"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Device::new(i as u32 + 1, name.to_string(), Box::new([])))
+            .collect()
+    }
+
+    /// Test per verificare filtri, ordinamento e totale in memoria
+    #[test]
+    fn test_apply_filters_and_sort() {
+        let query = Query::new().starts_with("name", "Serra").order_by("name", SortDirection::Desc);
+        let page = query.apply(devices());
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items.iter().map(|d| d.id).collect::<Vec<_>>(), vec![4, 1]);
+        assert!(page.next_cursor.is_none());
+
+        let page = Query::new().between("id", 2u32, 4u32).ne("name", "Garage").apply(devices());
+        assert_eq!(page.items.iter().map(|d| d.id).collect::<Vec<_>>(), vec![2, 4]);
+
+        // `contains` distingue maiuscole e minuscole, `text` no e considera solo i campi testuali
+        assert_eq!(Query::new().contains("name", "serra").apply(devices()).total, 0);
+        assert_eq!(Query::new().text("serra").apply(devices()).total, 2);
+        assert_eq!(Query::new().text("3").apply(devices()).total, 0);
+    }
+
+    /// Test per verificare la paginazione a cursore e con offset
+    #[test]
+    fn test_apply_pagination() {
+        let query = Query::new().order_by("name", SortDirection::Asc).limit(2);
+        let first = query.apply(devices());
+        assert_eq!(first.total, 5);
+        assert_eq!(first.items.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(), vec!["Cantina", "Garage"]);
+
+        let cursor = first.next_cursor.expect("Esiste una pagina successiva");
+        let second = query.clone().after(cursor).apply(devices());
+        assert_eq!(second.items.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(), vec!["Orto", "Serra"]);
+
+        let last = query.offset(4).apply(devices());
+        assert_eq!(last.items.len(), 1);
+        assert!(last.next_cursor.is_none());
+    }
+
+    /// Test per verificare la traduzione in SQL e MongoDB e la validazione dei campi
+    #[test]
+    fn test_backend_translation() {
+        let query = Query::new().eq("device_type", "sensor").contains("name", "50%").order_by("name", SortDirection::Asc).limit(10);
+
+        let sql = query.to_sql("device", &["name"], SqlDialect::PostgreSQL);
+        assert_eq!(
+            sql.select,
+            "SELECT * FROM device WHERE device_type = $1 AND name LIKE $2 ESCAPE '\\' \
+             ORDER BY name ASC NULLS FIRST, id ASC NULLS FIRST LIMIT 11 OFFSET 0"
+        );
+        assert_eq!(sql.params, vec![ModelValue::Text("sensor".to_string()), ModelValue::Text("%50\\%%".to_string())]);
+        assert_eq!(sql.count, "SELECT COUNT(*) AS total FROM device WHERE device_type = $1 AND name LIKE $2 ESCAPE '\\'");
+
+        // Su SQLite `GLOB` distingue maiuscole e minuscole, i caratteri jolly vengono racchiusi tra parentesi quadre
+        let sqlite = Query::new().contains("name", "a*?[b").to_sql("device", &[], SqlDialect::SQLite);
+        assert!(sqlite.select.starts_with("SELECT * FROM device WHERE name GLOB ? ORDER BY"));
+        assert_eq!(sqlite.params, vec![ModelValue::Text("*a[*][?][[]b*".to_string())]);
+
+        // Il cursore su un campo nullo mantiene i record con valore nullo
+        let cursor = Cursor(vec![ModelValue::Null, ModelValue::Int(2)]);
+        let sql = Query::new().order_by("device_type", SortDirection::Desc).after(cursor).to_sql("device", &[], SqlDialect::PostgreSQL);
+        assert_eq!(
+            sql.select,
+            "SELECT * FROM device WHERE ((device_type IS NULL AND id > $1)) ORDER BY device_type DESC NULLS LAST, id ASC NULLS FIRST"
+        );
+        let cursor = Cursor(vec![ModelValue::Text("sensor".to_string()), ModelValue::Int(2)]);
+        let sql = Query::new().order_by("device_type", SortDirection::Desc).after(cursor).to_sql("device", &[], SqlDialect::PostgreSQL);
+        assert!(sql.select.contains("WHERE (((device_type < $1 OR device_type IS NULL)) OR (device_type = $2 AND id > $3))"));
+
+        let mongo = query.to_mongo(&["name"]);
+        assert_eq!(mongo.limit, Some(11));
+        assert_eq!(mongo.sort.get_i32("name").unwrap(), 1);
+
+        assert!(query.validate(&["id", "name", "device_type"]).is_ok());
+        assert!(Query::new().eq("name; DROP TABLE device", 1u32).validate(&["id", "name"]).is_err());
+    }
+
+    /// Test per verificare che SQLite restituisca gli stessi record dell'esecuzione in memoria
+    #[test]
+    fn test_sqlite_matches_memory() {
+        let _db = TestDatabase::sqlite("query", &[CONFIGURATION, AUDIT_ENTRY]);
+        let configurations = vec![
+            Configuration::new(1, Some(2), "soglia_max".to_string(), "30".to_string(), Box::new([])),
+            Configuration::new(2, None, "Soglia".to_string(), "50%".to_string(), Box::new([])),
+            Configuration::new(3, Some(1), "sogliamax".to_string(), "3".to_string(), Box::new([])),
+            Configuration::new(4, None, "unita".to_string(), "celsius".to_string(), Box::new([])),
+        ];
+        for configuration in &configurations {
+            Configuration::create(configuration.clone()).unwrap();
+        }
+        let ids = |page: &Page<Configuration>| page.items.iter().map(|c| c.id).collect::<Vec<_>>();
+
+        let cases = [
+            // `_` e `%` sono caratteri e non jolly
+            (Query::new().contains("key", "soglia_"), vec![1]),
+            (Query::new().contains("value", "%"), vec![2]),
+            (Query::new().starts_with("key", "Soglia"), vec![2]),
+            (Query::new().text("SOGLIA"), vec![1, 2, 3]),
+            // `id` e `device_id` non sono campi testuali
+            (Query::new().text("2"), vec![]),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(ids(&Configuration::search(&query).unwrap()), expected, "{:?}", query);
+            assert_eq!(ids(&query.apply(configurations.clone())), expected, "{:?}", query);
+        }
+
+        // Paginazione a cursore con un record per pagina su un campo con valori nulli
+        for (direction, expected) in [(SortDirection::Asc, vec![2, 4, 3, 1]), (SortDirection::Desc, vec![1, 3, 2, 4])] {
+            let query = Query::new().order_by("device_id", direction);
+            assert_eq!(ids(&query.apply(configurations.clone())), expected);
+
+            let mut walked = Vec::new();
+            let mut page = Configuration::search(&query.clone().limit(1)).unwrap();
+            loop {
+                walked.extend(ids(&page));
+                match page.next_cursor {
+                    Some(cursor) => page = Configuration::search(&query.clone().limit(1).after(cursor)).unwrap(),
+                    None => break,
+                }
+            }
+            assert_eq!(walked, expected);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod common;
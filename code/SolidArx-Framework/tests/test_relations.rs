@@ -0,0 +1,79 @@
+/// # Integration Tests per `relations.rs`
+///
+/// Verifica le relazioni tra `User`, `Article`, `Comment` e `Tag` su un file SQLite temporaneo:
+/// controllo delle chiavi esterne, caricamento dei record collegati, molti a molti tramite `ArticleTag`
+/// e politiche di eliminazione `restrict` e `cascade`.
+///
+/// Eseguire con: cargo test --features "crud webapp"
+#[cfg(all(test, feature = "crud", feature = "webapp"))]
+mod tests {
+    use std::time::Duration;
+
+    use solid_arx_lib::crud::crud_ops::{Create, CrudError, Delete, List, SoftDelete};
+    use solid_arx_lib::crud::models::default::article::model::Article;
+    use solid_arx_lib::crud::models::default::article_tag::model::ArticleTag;
+    use solid_arx_lib::crud::models::default::comment::model::Comment;
+    use solid_arx_lib::crud::models::default::tag::model::Tag;
+    use solid_arx_lib::crud::models::default::user::model::User;
+    use solid_arx_lib::crud::query::Query;
+    use solid_arx_lib::crud::relations;
+
+    use crate::common::{TestDatabase, USER, API_KEY, ARTICLE, COMMENT, ARTICLE_TAG, ARTICLE_CATEGORY};
+
+    /// Test per verificare chiavi esterne, caricamento e politiche di eliminazione
+    #[test]
+    fn test_article_relations() {
+        let _db = TestDatabase::sqlite(
+            "relations",
+            &[
+                USER,
+                API_KEY,
+                ARTICLE,
+                COMMENT,
+                "CREATE TABLE tag (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT)",
+                ARTICLE_TAG,
+                ARTICLE_CATEGORY,
+            ],
+        );
+
+        User::create(User::new(1, "redazione".to_string(), "redazione@arx.dev".to_string(), String::new())).unwrap();
+        User::create(User::new(2, "lettore".to_string(), "lettore@arx.dev".to_string(), String::new())).unwrap();
+        Article::create(Article::new(1, "Relazioni".to_string(), "Chiavi esterne".to_string(), 1)).unwrap();
+
+        // Il record collegato deve esistere
+        assert_eq!(
+            Article::create(Article::new(2, "Orfano".to_string(), String::new(), 99)).unwrap_err(),
+            CrudError::ReferenceViolation { model: "article", id: "2".to_string(), related: "user" }
+        );
+        let comment = Comment::create(Comment::new(1, "Utile".to_string(), 2, 1)).unwrap();
+        assert!(matches!(Comment::create(Comment::new(2, "Perso".to_string(), 2, 99)), Err(CrudError::ReferenceViolation { .. })));
+
+        // Caricamento dei record collegati
+        let (article, comments) = Article::with_comments(1).unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(relations::parent::<Comment, Article>(&comment).unwrap().title, "Relazioni");
+        assert_eq!(relations::parent::<Comment, User>(&comment).unwrap().username, "lettore");
+
+        Tag::create(Tag::new(1, "rust".to_string())).unwrap();
+        relations::link::<Article, ArticleTag, Tag>(&1, &1).unwrap();
+        assert_eq!(article.tags().unwrap().iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>(), vec!["rust"]);
+
+        // `restrict`: l'autore con articoli non può essere eliminato
+        assert_eq!(
+            User::delete(1).unwrap_err(),
+            CrudError::ReferenceViolation { model: "user", id: "1".to_string(), related: "article" }
+        );
+
+        // `cascade`: il cestino conserva i commenti, il purge li elimina con i collegamenti ai tag
+        Article::delete(1).unwrap();
+        assert_eq!(Comment::list(&Query::new()).unwrap().total, 1);
+        assert_eq!(Article::purge(Duration::ZERO).unwrap(), 1);
+        assert_eq!(Comment::list(&Query::new()).unwrap().total, 0);
+        assert_eq!(ArticleTag::list(&Query::new()).unwrap().total, 0);
+        assert_eq!(Tag::list(&Query::new()).unwrap().total, 1);
+        User::delete(1).unwrap();
+    }
+}
+
+#[cfg(all(test, feature = "crud", feature = "webapp"))]
+mod common;
@@ -0,0 +1,52 @@
+/// # Integration Tests per la connessione al database di `system_core.rs`
+///
+/// Verifica che `CoreSystem::run` registri la connessione al database in `database_store`,
+/// così che i modelli con `AllocType::Database` la utilizzino, su un file SQLite temporaneo
+/// e su MongoDB. Il client MongoDB non contatta il server alla creazione, quindi il test
+/// non richiede un'istanza in esecuzione.
+/// La connessione MongoDB viene creata anche da un runtime tokio `current_thread`, su cui
+/// `block_in_place` non è disponibile.
+///
+/// Eseguire con: cargo test --features "crud"
+#[cfg(all(test, feature = "crud"))]
+mod tests {
+    use solid_arx_lib::config::global_config::{ApplicationType, CoreConfig};
+    use solid_arx_lib::config::memory_config::MemoryConfig;
+    use solid_arx_lib::config::network_config::{ConnectionConfig, DatabaseType};
+    use solid_arx_lib::core::system_core::CoreSystem;
+    use solid_arx_lib::crud::database_store;
+    use solid_arx_lib::network::connection_management::DbConnection;
+
+    fn connection_config(url: String) -> ConnectionConfig {
+        ConnectionConfig {
+            database_url: Some(url),
+            max_connections: Some(1),
+            retry_attempts: Some(1),
+            max_idle_time: Some(30),
+            connection_timeout: Some(1),
+        }
+    }
+
+    fn core_system(database: DatabaseType) -> CoreSystem {
+        let config = CoreConfig { app_type: ApplicationType::EmbeddedSystem, max_threads: 1 };
+        CoreSystem::new(config, MemoryConfig::default(), database).unwrap()
+    }
+
+    /// Test per verificare la registrazione della connessione da parte di `run`
+    #[test]
+    fn test_run_connects_database() {
+        let path = std::env::temp_dir().join(format!("arx_system_core_{}.db", std::process::id()));
+        core_system(DatabaseType::SQLite(connection_config(path.display().to_string()))).run().unwrap();
+        assert!(matches!(database_store::uninstall(), Some(DbConnection::SQLite(_))));
+        std::fs::remove_file(&path).unwrap();
+
+        // Senza database configurato non viene registrata alcuna connessione
+        core_system(DatabaseType::None).run().unwrap();
+        assert!(database_store::uninstall().is_none());
+
+        let mongodb = core_system(DatabaseType::MongoDB(connection_config("mongodb://127.0.0.1:1".to_string())));
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(async { mongodb.run() }).unwrap();
+        assert!(matches!(database_store::uninstall(), Some(DbConnection::MongoDB(_))));
+    }
+}
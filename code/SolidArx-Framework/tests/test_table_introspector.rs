@@ -0,0 +1,43 @@
+/// # Unit Tests per `table_introspector.rs`
+///
+/// Verifica la mappatura dei tipi del database sui tipi Rust usata da `arx db introspect`,
+/// in particolare delle colonne nullabili, e che le tabelle senza colonna `id` non generino modelli.
+/// I test non richiedono una connessione al database.
+/// Eseguire con: cargo test --features "crud"
+#[cfg(all(test, feature = "crud"))]
+mod tests {
+    use solid_arx_lib::crud::models::table_introspector::{map_from_mongo, map_from_sql, model_spec_from_columns, ColumnInfo};
+
+    fn column(name: &str, db_type: &str, nullable: bool) -> ColumnInfo {
+        ColumnInfo { name: name.to_string(), db_type: db_type.to_string(), nullable }
+    }
+
+    /// Test per verificare che le colonne nullabili di ogni tipo vengano mappate su `Option`
+    #[test]
+    fn test_map_nullable_columns() {
+        assert_eq!(map_from_sql("created_at", "TIMESTAMP", true), "Option<chrono::NaiveDateTime>");
+        assert_eq!(map_from_sql("total", "BIGINT", true), "Option<i64>");
+        assert_eq!(map_from_sql("active", "BOOLEAN", true), "Option<bool>");
+        assert_eq!(map_from_sql("price", "NUMERIC(10,2)", true), "Option<f64>");
+        assert_eq!(map_from_sql("device_id", "INTEGER", true), "Option<u32>");
+        assert_eq!(map_from_sql("created_at", "TIMESTAMP", false), "chrono::NaiveDateTime");
+        assert_eq!(map_from_mongo("score", "double", true), "Option<f64>");
+    }
+
+    /// Test per verificare la specifica generata e lo scarto delle tabelle senza `id`
+    #[test]
+    fn test_model_spec_from_columns() {
+        let spec = model_spec_from_columns(
+            "sensor_reading",
+            &[column("id", "INTEGER", false), column("read_at", "TIMESTAMP", true), column("type", "TEXT", false)],
+            map_from_sql,
+        )
+        .unwrap();
+        assert_eq!(spec.name, "SensorReading");
+        // `type` è una parola chiave di Rust e la colonna viene ignorata
+        let fields: Vec<(&str, &str)> = spec.fields.iter().map(|f| (f.name.as_str(), f.ty.as_str())).collect();
+        assert_eq!(fields, vec![("read_at", "Option<chrono::NaiveDateTime>")]);
+
+        assert!(model_spec_from_columns("legacy_log", &[column("message", "TEXT", false)], map_from_sql).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+/// # Integration Tests per `unit_of_work.rs`
+///
+/// Verifica che le operazioni raggruppate in una `UnitOfWork` o in `transaction` vengano
+/// applicate tutte o nessuna: in memoria (`Command`) e su un file SQLite temporaneo
+/// con la cache write-behind di `Device`.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use solid_arx_lib::crud::crud_ops::{Create, CrudError, Read, Update};
+    use solid_arx_lib::crud::model_cache;
+    use solid_arx_lib::crud::models::default::command::model::Command;
+    use solid_arx_lib::crud::models::default::device::model::Device;
+    use solid_arx_lib::crud::unit_of_work::{self, UnitOfWork};
+
+    use crate::common::{TestDatabase, DEVICE};
+
+    fn command(id: u32, command_type: &str) -> Command {
+        Command::new(id, 1, command_type.to_string(), "2024-01-01 00:00:00".to_string(), Box::new([]))
+    }
+
+    /// Test per verificare il rollback delle scritture in memoria
+    #[test]
+    fn test_in_memory_rollback() {
+        Command::create(command(951, "reboot")).unwrap();
+
+        let mut unit = UnitOfWork::new();
+        unit.update(command(951, "shutdown")).create(command(952, "ping")).delete::<Command>(999);
+        assert_eq!(unit.len(), 3);
+        assert_eq!(unit.commit().unwrap_err(), CrudError::NotFound { model: "command", id: "999".to_string() });
+        assert_eq!(Command::read(951).unwrap().command_type, "reboot");
+        assert!(matches!(Command::read(952), Err(CrudError::NotFound { .. })));
+
+        let created = unit_of_work::transaction(|| {
+            let created = Command::create(command(953, "ping"))?;
+            Command::update(command(951, "shutdown"))?;
+            Ok(created)
+        })
+        .unwrap();
+        assert_eq!(Command::read(created.id).unwrap().command_type, "ping");
+        assert_eq!(Command::read(951).unwrap().command_type, "shutdown");
+        assert!(!unit_of_work::is_active());
+    }
+
+    /// Test per verificare il rollback della transazione SQLite e della coda write-behind
+    #[test]
+    fn test_database_rollback() {
+        let _db = TestDatabase::sqlite("unit_of_work", &[DEVICE]);
+
+        Device::create(Device::new(1, "Gateway".to_string(), Box::new([]))).unwrap();
+        assert_eq!(model_cache::stats::<Device>().pending_writes, 1);
+
+        // Il flush della coda, l'aggiornamento e il comando vengono annullati dal Conflict
+        let mut unit = UnitOfWork::new();
+        unit.create(command(961, "calibrate"))
+            .update(Device::new(1, "Gateway Serra".to_string(), Box::new([])))
+            .create(Device::new(1, "Duplicato".to_string(), Box::new([])));
+        assert_eq!(unit.commit().unwrap_err(), CrudError::Conflict { model: "device", id: "1".to_string() });
+        assert!(matches!(Command::read(961), Err(CrudError::NotFound { .. })));
+        assert_eq!(model_cache::stats::<Device>().pending_writes, 1);
+        assert_eq!(Device::read(1).unwrap().name, "Gateway");
+
+        let mut unit = UnitOfWork::new();
+        unit.create(command(962, "calibrate")).update(Device::new(1, "Gateway Serra".to_string(), Box::new([])));
+        assert_eq!(unit.commit().unwrap(), 2);
+        assert_eq!(model_cache::stats::<Device>().pending_writes, 0);
+        assert_eq!(Device::read(1).unwrap().name, "Gateway Serra");
+    }
+}
+
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod common;
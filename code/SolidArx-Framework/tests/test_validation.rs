@@ -0,0 +1,57 @@
+/// # Integration Tests per `validation.rs`
+///
+/// Verifica le regole dichiarate con `#[arx(validate(...))]` su `User`, `Endpoint` e `RateLimitRule`:
+/// `Create` e `Update` restituiscono `CrudError::ValidationFailed` con tutti i campi non validi
+/// prima di accedere allo storage, e la verifica delle espressioni cron.
+///
+/// Eseguire con: cargo test --features "crud api"
+#[cfg(all(test, feature = "crud", feature = "api"))]
+mod tests {
+    use solid_arx_lib::crud::crud_ops::{ArxModel, Create, CrudError, Update};
+    use solid_arx_lib::crud::models::default::endpoint::model::Endpoint;
+    use solid_arx_lib::crud::models::default::rate_limit::model::RateLimitRule;
+    use solid_arx_lib::crud::models::default::user::model::User;
+    use solid_arx_lib::crud::validation::{self, FieldError};
+
+    /// Test per verificare che tutti i campi non validi vengano restituiti insieme
+    #[test]
+    fn test_validation_failed() {
+        let user = User::new(1, "ab".to_string(), "non-una-email".to_string(), String::new());
+        let errors = match User::create(user.clone()) {
+            Err(CrudError::ValidationFailed { model, errors }) => {
+                assert_eq!(model, "user");
+                errors
+            }
+            other => panic!("Atteso ValidationFailed, ottenuto {:?}", other),
+        };
+        let failed: Vec<(&str, &str)> = errors.iter().map(|e| (e.field.as_str(), e.rule.as_str())).collect();
+        assert_eq!(failed, vec![("username", "length"), ("email", "email")]);
+        assert!(matches!(User::update(user), Err(CrudError::ValidationFailed { .. })));
+
+        let valid = User::new(1, "redazione".to_string(), "redazione@arx.dev".to_string(), String::new());
+        assert_eq!(valid.validate(), Ok(()));
+
+        let endpoint = Endpoint::new(1, String::new(), "api/users".to_string());
+        assert_eq!(endpoint.validate().unwrap_err().len(), 2);
+        assert_eq!(Endpoint::new(1, "utenti".to_string(), "/api/users/:id".to_string()).validate(), Ok(()));
+
+        let errors = RateLimitRule::new(1, 0, "10x".to_string()).validate().unwrap_err();
+        assert_eq!(errors.iter().map(|e| e.rule.as_str()).collect::<Vec<_>>(), vec!["range", "regex"]);
+        assert_eq!(RateLimitRule::new(1, 100, "15m".to_string()).validate(), Ok(()));
+    }
+
+    /// Test per verificare le espressioni cron
+    #[test]
+    fn test_validate_cron() {
+        for expression in ["*/5 * * * *", "0 30 9 * * MON-FRI", "0 0 1,15 JAN-JUN ?", "@daily"] {
+            assert_eq!(validation::validate_cron(expression), Ok(()), "{}", expression);
+        }
+        for expression in ["* * * *", "60 * * * *", "0 0 31-1 * *", "*/0 * * * *", "0 0 * FOO *"] {
+            assert!(validation::validate_cron(expression).is_err(), "{}", expression);
+        }
+        assert_eq!(
+            FieldError::new("cron_expression", "cron", "non valida").to_string(),
+            "cron_expression (cron): non valida"
+        );
+    }
+}
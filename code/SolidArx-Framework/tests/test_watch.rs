@@ -0,0 +1,53 @@
+/// # Integration Tests per `watch.rs`
+///
+/// Verifica gli eventi delle modifiche di `Command` (in memoria): creazione, aggiornamento ed eliminazione
+/// in ordine, filtro della query, ripresa da un cursore e nessun evento per una transazione annullata.
+///
+/// Eseguire con: cargo test --features "crud embedded"
+#[cfg(all(test, feature = "crud", feature = "embedded"))]
+mod tests {
+    use solid_arx_lib::crud::crud_ops::{Create, CrudError, Delete, Update};
+    use solid_arx_lib::crud::models::default::command::model::Command;
+    use solid_arx_lib::crud::query::Query;
+    use solid_arx_lib::crud::unit_of_work;
+    use solid_arx_lib::crud::watch::{self, ChangeKind};
+
+    fn command(id: u32, device_id: u32, command_type: &str) -> Command {
+        Command::new(id, device_id, command_type.to_string(), "2024-01-01 00:00:00".to_string(), Box::new([]))
+    }
+
+    /// Test per verificare eventi, filtri e ripresa dal cursore
+    #[test]
+    fn test_watch_changes() {
+        let mut all = watch::subscribe::<Command>(&Query::new()).unwrap();
+        let mut device = watch::subscribe::<Command>(&Query::new().eq("device_id", 7u32)).unwrap();
+        assert!(matches!(watch::subscribe::<Command>(&Query::new().eq("colore", 1u32)), Err(CrudError::InvalidQuery(_))));
+
+        Command::create(command(1, 7, "reboot")).unwrap();
+        Command::create(command(2, 8, "ping")).unwrap();
+        Command::update(command(1, 7, "shutdown")).unwrap();
+        Command::delete(2).unwrap();
+
+        let kinds: Vec<(ChangeKind, u32)> =
+            std::iter::from_fn(|| all.try_next().unwrap()).map(|event| (event.kind, event.id)).collect();
+        assert_eq!(kinds, vec![(ChangeKind::Created, 1), (ChangeKind::Created, 2), (ChangeKind::Updated, 1), (ChangeKind::Deleted, 2)]);
+
+        let updated = device.try_next().unwrap().and_then(|_| device.try_next().unwrap()).unwrap();
+        assert_eq!(updated.item.unwrap().command_type, "shutdown");
+        let cursor = device.cursor();
+        drop(device);
+
+        // Ripresa dopo l'aggiornamento: resta l'eliminazione, senza stato e quindi non filtrata
+        let mut resumed = watch::resume::<Command>(&Query::new().eq("device_id", 7u32), cursor).unwrap();
+        let deleted = resumed.try_next().unwrap().unwrap();
+        assert_eq!((deleted.kind, deleted.id, resumed.cursor()), (ChangeKind::Deleted, 2, 4));
+
+        // Le modifiche di una transazione annullata non vengono notificate
+        let result = unit_of_work::transaction(|| {
+            Command::create(command(3, 7, "ping"))?;
+            Command::delete(99)
+        });
+        assert!(result.is_err());
+        assert!(all.try_next().unwrap().is_none());
+    }
+}